@@ -0,0 +1,147 @@
+use crate::build::error::Error;
+use crate::cli::{BuildArgs, CrossCompileBackend, MessageFormat, PackageArgs, PackageFormat};
+use crate::package::package_root_buildpacks;
+use libcnb_data::buildpack::BuildpackId;
+use libcnb_package::multi_target::TargetPlatform;
+use libcnb_package::pack::PackBuildCommand;
+use libcnb_package::util::absolutize_path;
+use std::env::consts;
+use std::process::{Command, Stdio};
+
+pub(crate) fn execute(args: &BuildArgs) -> Result<(), Error> {
+    let current_dir = std::env::current_dir().map_err(Error::CannotGetCurrentDir)?;
+
+    let app_dir = absolutize_path(&args.app_dir, &current_dir);
+    if !app_dir.is_dir() {
+        return Err(Error::AppDirectoryNotFound(app_dir));
+    }
+
+    let packaged_buildpack_dirs_per_target = package_root_buildpacks(&package_args(args), false)
+        .map_err(Error::CannotPackageBuildpack)?;
+
+    // pack builds images for the architecture of this machine, so buildpacks that were packaged
+    // for multiple targets are used in the variant for that architecture, if there is one.
+    let packaged_buildpack_dirs = packaged_buildpack_dirs_per_target
+        .iter()
+        .find(|(target_triple, _)| is_host_arch_target_triple(target_triple, consts::ARCH))
+        .or(packaged_buildpack_dirs_per_target.first())
+        .map(|(_, packaged_buildpack_dirs)| packaged_buildpack_dirs.clone())
+        .unwrap_or_default();
+
+    let Some((buildpack_id, packaged_buildpack_dir)) = packaged_buildpack_dirs
+        .first_key_value()
+        .filter(|_| packaged_buildpack_dirs.len() == 1)
+    else {
+        return Err(Error::NotASingleBuildpack(packaged_buildpack_dirs.len()));
+    };
+
+    let image_name = args
+        .image
+        .clone()
+        .unwrap_or_else(|| default_image_name(buildpack_id));
+
+    let mut pack_build_command = PackBuildCommand::new(&args.builder, &app_dir, &image_name);
+    pack_build_command.buildpack(packaged_buildpack_dir.clone());
+    for (key, value) in &args.env {
+        pack_build_command.env(key, value);
+    }
+
+    eprintln!("🛠️ Building image {image_name} with pack...");
+
+    // The name of the built image is the only output on stdout, so pack's output goes to stderr.
+    let status = Command::from(pack_build_command)
+        .stdout(Stdio::from(std::io::stderr()))
+        .status()
+        .map_err(Error::CannotRunPack)?;
+
+    if !status.success() {
+        return Err(Error::PackBuildFailed(status));
+    }
+
+    eprintln!("✨ Successfully built image {image_name}!");
+    eprintln!();
+    eprintln!("💡 To run the app, run:");
+    eprintln!("docker run --rm -it {image_name}");
+    eprintln!();
+
+    println!("{image_name}");
+
+    Ok(())
+}
+
+fn package_args(args: &BuildArgs) -> PackageArgs {
+    PackageArgs {
+        no_cross_compile_assistance: false,
+        backend: CrossCompileBackend::Cargo,
+        release: args.release,
+        no_strip: false,
+        features: Vec::new(),
+        profile: None,
+        rustflags: None,
+        locked: false,
+        target: args.target.clone().into_iter().collect(),
+        package_dir: args.package_dir.clone(),
+        format: PackageFormat::Dir,
+        message_format: MessageFormat::Human,
+        watch: false,
+        exec: None,
+    }
+}
+
+/// Determines if the given target triple is for the given host architecture, which uses the names
+/// of `std::env::consts::ARCH`.
+fn is_host_arch_target_triple(target_triple: &str, host_arch: &str) -> bool {
+    let host_platform_arch = match host_arch {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        arch => arch,
+    };
+
+    TargetPlatform::from_target_triple(target_triple)
+        .is_some_and(|target_platform| target_platform.arch == host_platform_arch)
+}
+
+/// Image names must be lowercase and buildpack IDs can contain characters that aren't allowed in
+/// image names, so they are replaced the same way as for crate names of new buildpacks.
+fn default_image_name(buildpack_id: &BuildpackId) -> String {
+    buildpack_id.replace(['/', '.'], "-").to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libcnb_data::buildpack_id;
+
+    #[test]
+    fn derive_image_name_from_buildpack_id() {
+        assert_eq!(
+            default_image_name(&buildpack_id!("Heroku/nodejs.engine")),
+            "heroku-nodejs-engine"
+        );
+    }
+
+    #[test]
+    fn match_target_triples_to_host_arch() {
+        assert!(is_host_arch_target_triple(
+            "x86_64-unknown-linux-musl",
+            "x86_64"
+        ));
+        assert!(is_host_arch_target_triple(
+            "aarch64-unknown-linux-gnu",
+            "aarch64"
+        ));
+        assert!(is_host_arch_target_triple(
+            "armv7-unknown-linux-musleabihf",
+            "arm"
+        ));
+        assert!(is_host_arch_target_triple(
+            "riscv64gc-unknown-linux-musl",
+            "riscv64"
+        ));
+        assert!(!is_host_arch_target_triple(
+            "aarch64-unknown-linux-musl",
+            "x86_64"
+        ));
+        assert!(!is_host_arch_target_triple("x86_64-apple-darwin", "x86_64"));
+    }
+}
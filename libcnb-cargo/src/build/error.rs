@@ -0,0 +1,17 @@
+use std::path::PathBuf;
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("Failed to get current dir: {0}")]
+    CannotGetCurrentDir(#[source] std::io::Error),
+    #[error("App directory {0} does not exist")]
+    AppDirectoryNotFound(PathBuf),
+    #[error(transparent)]
+    CannotPackageBuildpack(crate::package::Error),
+    #[error("Expected a single buildpack to build with, found {0}. Run this command in a buildpack directory.")]
+    NotASingleBuildpack(usize),
+    #[error("Failed to run pack: {0}")]
+    CannotRunPack(#[source] std::io::Error),
+    #[error("pack build failed ({0})")]
+    PackBuildFailed(std::process::ExitStatus),
+}
@@ -0,0 +1,33 @@
+use crate::check::error::Error;
+use crate::cli::CheckArgs;
+use libcnb_package::check::check_buildpacks;
+use libcnb_package::find_cargo_workspace_root_dir;
+
+pub(crate) fn execute(_args: &CheckArgs) -> Result<(), Error> {
+    let current_dir = std::env::current_dir().map_err(Error::CannotGetCurrentDir)?;
+
+    let workspace_root_path =
+        find_cargo_workspace_root_dir(&current_dir).map_err(Error::CannotFindCargoWorkspaceRoot)?;
+
+    eprintln!("🔍 Checking buildpacks...");
+    let check_report =
+        check_buildpacks(&workspace_root_path).map_err(Error::CannotCheckBuildpacks)?;
+
+    for problem in &check_report.problems {
+        eprintln!("{}", problem.display_relative_to(&current_dir));
+    }
+
+    for warning in &check_report.warnings {
+        eprintln!("⚠️ {}", warning.display_relative_to(&current_dir));
+    }
+
+    if check_report.problems.is_empty() {
+        eprintln!(
+            "✨ No problems found in {} buildpack(s)",
+            check_report.buildpack_dirs.len()
+        );
+        Ok(())
+    } else {
+        Err(Error::ProblemsFound(check_report.problems.len()))
+    }
+}
@@ -0,0 +1,13 @@
+use libcnb_package::check::CheckBuildpacksError;
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("Failed to get current dir: {0}")]
+    CannotGetCurrentDir(#[source] std::io::Error),
+    #[error("Failed to find Cargo workspace root: {0}")]
+    CannotFindCargoWorkspaceRoot(#[source] libcnb_package::FindCargoWorkspaceRootError),
+    #[error("Failed to check buildpacks: {0}")]
+    CannotCheckBuildpacks(#[source] CheckBuildpacksError),
+    #[error("Found {0} problem(s)")]
+    ProblemsFound(usize),
+}
@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -13,24 +13,161 @@ pub(crate) enum Cli {
 pub(crate) enum LibcnbSubcommand {
     /// Packages a libcnb.rs Cargo project as a Cloud Native Buildpack
     Package(PackageArgs),
+    /// Creates a new libcnb.rs buildpack crate
+    New(NewArgs),
+    /// Checks the buildpacks in a Cargo workspace for problems without building them
+    Check(CheckArgs),
+    /// Prints the dependency graph of the buildpacks in a Cargo workspace
+    Graph(GraphArgs),
+    /// Packages the buildpack in the current directory and builds an app with it using pack
+    Build(BuildArgs),
 }
 
 #[derive(Parser)]
+#[allow(clippy::struct_excessive_bools)]
 pub(crate) struct PackageArgs {
     /// Disable cross-compile assistance
     #[arg(long)]
     pub(crate) no_cross_compile_assistance: bool,
+    /// Cross-compile backend used to build buildpack binaries
+    #[arg(long, value_enum, default_value_t = CrossCompileBackend::Cargo)]
+    pub(crate) backend: CrossCompileBackend,
     /// Build in release mode, with optimizations
     #[arg(long)]
     pub(crate) release: bool,
-    /// Build for the target triple
-    #[arg(long, default_value = "x86_64-unknown-linux-musl")]
-    pub(crate) target: String,
+    /// Keep symbols in buildpack binaries instead of stripping them
+    #[arg(long)]
+    pub(crate) no_strip: bool,
+    /// Enable Cargo features (repeatable or comma-separated) for the buildpacks that define them
+    #[arg(long, value_delimiter = ',')]
+    pub(crate) features: Vec<String>,
+    /// Build with the given Cargo profile instead of 'dev' or 'release'
+    #[arg(long)]
+    pub(crate) profile: Option<String>,
+    /// Additional flags for the compiler, appended to RUSTFLAGS (rustflags in .cargo/config.toml are ignored)
+    #[arg(long, allow_hyphen_values = true)]
+    pub(crate) rustflags: Option<String>,
+    /// Require Cargo.lock to be up to date
+    #[arg(long)]
+    pub(crate) locked: bool,
+    /// Build for the target triple (repeatable), defaults to the targets in buildpack.toml or x86_64-unknown-linux-musl
+    #[arg(long)]
+    pub(crate) target: Vec<String>,
+    /// Directory for packaged buildpacks, defaults to 'packaged' in Cargo workspace root
+    #[arg(long)]
+    pub(crate) package_dir: Option<PathBuf>,
+    /// Output format, 'oci' additionally writes buildpackage archives (.cnb) next to the buildpack directories
+    #[arg(long, value_enum, default_value_t = PackageFormat::Dir)]
+    pub(crate) format: PackageFormat,
+    /// Message format, 'json' prints one JSON object per event to stdout instead of the packaged paths
+    #[arg(long, value_enum, value_name = "FMT", default_value_t = MessageFormat::Human)]
+    pub(crate) message_format: MessageFormat,
+    /// Watch the buildpacks for changes and repackage the affected buildpacks
+    #[arg(long)]
+    pub(crate) watch: bool,
+    /// Shell command to run after each successful packaging in watch mode
+    #[arg(long, value_name = "COMMAND", requires = "watch")]
+    pub(crate) exec: Option<String>,
+}
+
+#[derive(Parser)]
+pub(crate) struct NewArgs {
+    /// ID of the new buildpack, for example 'my-org/my-buildpack'
+    pub(crate) buildpack_id: String,
+    /// Directory to create the crate in, defaults to the buildpack ID with '/' and '.' replaced by '-'
+    #[arg(long)]
+    pub(crate) path: Option<PathBuf>,
+    /// Target triple to declare in buildpack.toml (repeatable), defaults to x86_64-unknown-linux-musl
+    #[arg(long)]
+    pub(crate) target: Vec<String>,
+    /// Builder image used by the generated integration test
+    #[arg(long, default_value = "heroku/builder:24")]
+    pub(crate) builder: String,
+    /// Add the new crate to the members of the enclosing Cargo workspace
+    #[arg(long)]
+    pub(crate) add_to_workspace: bool,
+    /// Add the new buildpack to the package.toml of the composite buildpack in the given directory
+    #[arg(long)]
+    pub(crate) composite: Option<PathBuf>,
+}
+
+#[derive(Parser)]
+pub(crate) struct CheckArgs {}
+
+#[derive(Parser)]
+pub(crate) struct GraphArgs {
+    /// Output format, 'tree' shows each buildpack with its dependencies, 'dot' and 'json' list buildpacks in build order
+    #[arg(long, value_enum, default_value_t = GraphFormat::Tree)]
+    pub(crate) format: GraphFormat,
+}
+
+#[derive(Parser)]
+pub(crate) struct BuildArgs {
+    /// Directory of the app to build
+    pub(crate) app_dir: PathBuf,
+    /// Builder image to build the app with
+    #[arg(long, default_value = "heroku/builder:24")]
+    pub(crate) builder: String,
+    /// Name of the built image, defaults to the buildpack ID with '/' and '.' replaced by '-'
+    #[arg(long)]
+    pub(crate) image: Option<String>,
+    /// Build-time environment variable for the app build (repeatable)
+    #[arg(long, value_name = "KEY=VALUE", value_parser = parse_env_var)]
+    pub(crate) env: Vec<(String, String)>,
+    /// Build the buildpack in release mode, with optimizations
+    #[arg(long)]
+    pub(crate) release: bool,
+    /// Build the buildpack for the target triple, defaults to the targets in buildpack.toml or x86_64-unknown-linux-musl
+    #[arg(long)]
+    pub(crate) target: Option<String>,
     /// Directory for packaged buildpacks, defaults to 'packaged' in Cargo workspace root
     #[arg(long)]
     pub(crate) package_dir: Option<PathBuf>,
 }
 
+fn parse_env_var(value: &str) -> Result<(String, String), String> {
+    value
+        .split_once('=')
+        .map(|(key, value)| (String::from(key), String::from(value)))
+        .ok_or_else(|| format!("'{value}' is not in the format KEY=VALUE"))
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum PackageFormat {
+    Dir,
+    Oci,
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum MessageFormat {
+    Human,
+    Json,
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum CrossCompileBackend {
+    Cargo,
+    Zigbuild,
+    Cross,
+}
+
+impl From<CrossCompileBackend> for libcnb_package::cross_compile::CrossCompileBackend {
+    fn from(value: CrossCompileBackend) -> Self {
+        match value {
+            CrossCompileBackend::Cargo => Self::Cargo,
+            CrossCompileBackend::Zigbuild => Self::Zigbuild,
+            CrossCompileBackend::Cross => Self::Cross,
+        }
+    }
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum GraphFormat {
+    Tree,
+    Dot,
+    Json,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
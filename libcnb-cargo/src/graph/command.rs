@@ -0,0 +1,365 @@
+use crate::cli::{GraphArgs, GraphFormat};
+use crate::graph::error::Error;
+use libcnb_package::find_cargo_workspace_root_dir;
+use libcnb_package::workspace_graph::{build_workspace_graph, WorkspaceGraphNode};
+use petgraph::algo::toposort;
+use petgraph::graph::NodeIndex;
+use petgraph::{Direction, Graph};
+use std::collections::HashSet;
+use std::path::Path;
+
+pub(crate) fn execute(args: &GraphArgs) -> Result<(), Error> {
+    let current_dir = std::env::current_dir().map_err(Error::CannotGetCurrentDir)?;
+
+    let workspace_root_path =
+        find_cargo_workspace_root_dir(&current_dir).map_err(Error::CannotFindCargoWorkspaceRoot)?;
+
+    let graph =
+        build_workspace_graph(&workspace_root_path).map_err(Error::CannotBuildWorkspaceGraph)?;
+
+    let output = match args.format {
+        GraphFormat::Tree => render_tree(&graph, &workspace_root_path),
+        GraphFormat::Dot => render_dot(&graph, &workspace_root_path),
+        GraphFormat::Json => render_json(&graph, &workspace_root_path)?,
+    };
+
+    print!("{output}");
+
+    for node in graph.node_weights().filter(|node| node.is_unpinned()) {
+        eprintln!(
+            "⚠️ Dependency {} isn't pinned to a specific version or digest",
+            node.id()
+        );
+    }
+
+    Ok(())
+}
+
+// Renders the graph similar to `cargo tree`, starting with the buildpacks no other buildpack
+// depends on. Nodes that were already rendered further up are marked with `(*)` instead of
+// rendering their dependencies again.
+fn render_tree(graph: &Graph<WorkspaceGraphNode, ()>, workspace_root_path: &Path) -> String {
+    let mut lines = Vec::new();
+    let mut rendered = HashSet::new();
+
+    let root_indices = graph
+        .node_indices()
+        .filter(|index| {
+            graph
+                .neighbors_directed(*index, Direction::Incoming)
+                .next()
+                .is_none()
+        })
+        .collect::<Vec<_>>();
+
+    // Nodes that are only part of dependency cycles aren't reachable from any root.
+    let remaining_indices = graph.node_indices().collect::<Vec<_>>();
+
+    for index in root_indices.into_iter().chain(remaining_indices) {
+        if !rendered.contains(&index) {
+            render_tree_node(
+                graph,
+                workspace_root_path,
+                index,
+                "",
+                "",
+                &mut rendered,
+                &mut lines,
+            );
+        }
+    }
+
+    lines_to_string(&lines)
+}
+
+fn render_tree_node(
+    graph: &Graph<WorkspaceGraphNode, ()>,
+    workspace_root_path: &Path,
+    index: NodeIndex,
+    prefix: &str,
+    child_prefix: &str,
+    rendered: &mut HashSet<NodeIndex>,
+    lines: &mut Vec<String>,
+) {
+    let node = &graph[index];
+    let description = match (node.path(), node.pinned_version()) {
+        (Some(path), _) => format!(
+            "{} ({}, {})",
+            node.id(),
+            node.kind(),
+            display_path(path, workspace_root_path)
+        ),
+        (None, Some(pinned_version)) => {
+            format!("{} ({}, {pinned_version})", node.id(), node.kind())
+        }
+        (None, None) if node.is_unpinned() => format!("{} ({}, unpinned)", node.id(), node.kind()),
+        (None, None) => format!("{} ({})", node.id(), node.kind()),
+    };
+
+    if !rendered.insert(index) {
+        lines.push(format!("{prefix}{description} (*)"));
+        return;
+    }
+
+    lines.push(format!("{prefix}{description}"));
+
+    let dependencies = sorted_dependencies(graph, index);
+    for (position, dependency_index) in dependencies.iter().enumerate() {
+        let (branch, continuation) = if position + 1 == dependencies.len() {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+
+        render_tree_node(
+            graph,
+            workspace_root_path,
+            *dependency_index,
+            &format!("{child_prefix}{branch}"),
+            &format!("{child_prefix}{continuation}"),
+            rendered,
+            lines,
+        );
+    }
+}
+
+fn render_dot(graph: &Graph<WorkspaceGraphNode, ()>, workspace_root_path: &Path) -> String {
+    let mut lines = vec![String::from("digraph buildpacks {")];
+
+    for index in build_order(graph) {
+        let node = &graph[index];
+
+        let label = match node.path() {
+            Some(path) => format!(
+                "{}\\n{}",
+                node.id(),
+                display_path(path, workspace_root_path)
+            ),
+            None => node.id(),
+        };
+
+        let style = match node {
+            WorkspaceGraphNode::Libcnb { .. } => "shape=box",
+            WorkspaceGraphNode::Composite { .. } => "shape=box3d",
+            WorkspaceGraphNode::Other { .. } => "shape=box, style=dashed",
+            WorkspaceGraphNode::DockerImage(_)
+            | WorkspaceGraphNode::RegistryBuildpack(_)
+            | WorkspaceGraphNode::External { .. } => "shape=ellipse, style=dashed",
+        };
+
+        lines.push(format!(
+            "    {} [label={}, kind={}, {style}];",
+            dot_string(&node.id()),
+            dot_string(&label),
+            node.kind()
+        ));
+    }
+
+    for index in build_order(graph) {
+        for dependency_index in sorted_dependencies(graph, index) {
+            lines.push(format!(
+                "    {} -> {};",
+                dot_string(&graph[index].id()),
+                dot_string(&graph[dependency_index].id())
+            ));
+        }
+    }
+
+    lines.push(String::from("}"));
+    lines_to_string(&lines)
+}
+
+fn render_json(
+    graph: &Graph<WorkspaceGraphNode, ()>,
+    workspace_root_path: &Path,
+) -> Result<String, Error> {
+    let nodes = build_order(graph)
+        .into_iter()
+        .map(|index| {
+            let node = &graph[index];
+
+            serde_json::json!({
+                "id": node.id(),
+                "kind": node.kind(),
+                "path": node.path().map(|path| display_path(path, workspace_root_path)),
+                "pinned_version": node.pinned_version(),
+                "dependencies": sorted_dependencies(graph, index)
+                    .into_iter()
+                    .map(|dependency_index| graph[dependency_index].id())
+                    .collect::<Vec<_>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::to_string_pretty(&serde_json::json!({ "nodes": nodes }))
+        .map(|json| format!("{json}\n"))
+        .map_err(Error::CannotSerializeGraph)
+}
+
+// Returns all nodes with dependencies before the buildpacks that depend on them, the order in which
+// buildpacks are packaged. Falls back to the order of the nodes in the graph if there are cycles.
+fn build_order(graph: &Graph<WorkspaceGraphNode, ()>) -> Vec<NodeIndex> {
+    toposort(graph, None).map_or_else(
+        |_| graph.node_indices().collect(),
+        |indices| indices.into_iter().rev().collect(),
+    )
+}
+
+fn sorted_dependencies(graph: &Graph<WorkspaceGraphNode, ()>, index: NodeIndex) -> Vec<NodeIndex> {
+    let mut dependencies = graph.neighbors(index).collect::<Vec<_>>();
+    dependencies.sort_by_key(|dependency_index| graph[*dependency_index].id());
+    dependencies
+}
+
+fn display_path(path: &Path, workspace_root_path: &Path) -> String {
+    path.strip_prefix(workspace_root_path)
+        .ok()
+        .filter(|relative_path| !relative_path.as_os_str().is_empty())
+        .unwrap_or(Path::new("."))
+        .to_string_lossy()
+        .to_string()
+}
+
+fn lines_to_string(lines: &[String]) -> String {
+    lines.iter().fold(String::new(), |mut output, line| {
+        output.push_str(line);
+        output.push('\n');
+        output
+    })
+}
+
+fn dot_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libcnb_data::buildpack_id;
+    use libcnb_package::dependency_reference::{DockerImageReference, RegistryBuildpackReference};
+    use std::path::PathBuf;
+
+    fn test_graph() -> Graph<WorkspaceGraphNode, ()> {
+        let mut graph = Graph::new();
+
+        let composite = graph.add_node(WorkspaceGraphNode::Composite {
+            buildpack_id: buildpack_id!("example/composite"),
+            path: PathBuf::from("/workspace/composite"),
+        });
+        let libcnb = graph.add_node(WorkspaceGraphNode::Libcnb {
+            buildpack_id: buildpack_id!("example/libcnb"),
+            path: PathBuf::from("/workspace/buildpacks/libcnb"),
+        });
+        let other = graph.add_node(WorkspaceGraphNode::Other {
+            buildpack_id: buildpack_id!("example/other"),
+            path: PathBuf::from("/workspace/buildpacks/other"),
+        });
+        let external = graph.add_node(WorkspaceGraphNode::DockerImage(DockerImageReference {
+            name: String::from("docker.io/heroku/example"),
+            tag: Some(String::from("1.2.3")),
+            digest: None,
+        }));
+        let registry = graph.add_node(WorkspaceGraphNode::RegistryBuildpack(
+            RegistryBuildpackReference {
+                buildpack_id: buildpack_id!("heroku/registry"),
+                version: None,
+            },
+        ));
+        let nested_composite = graph.add_node(WorkspaceGraphNode::Composite {
+            buildpack_id: buildpack_id!("example/nested"),
+            path: PathBuf::from("/workspace/nested"),
+        });
+
+        graph.add_edge(composite, libcnb, ());
+        graph.add_edge(composite, nested_composite, ());
+        graph.add_edge(composite, external, ());
+        graph.add_edge(nested_composite, libcnb, ());
+        graph.add_edge(nested_composite, other, ());
+        graph.add_edge(nested_composite, registry, ());
+
+        graph
+    }
+
+    #[test]
+    fn render_graph_as_tree() {
+        assert_eq!(
+            render_tree(&test_graph(), Path::new("/workspace")),
+            "\
+example/composite (composite, composite)
+├── docker://docker.io/heroku/example:1.2.3 (docker, 1.2.3)
+├── example/libcnb (libcnb, buildpacks/libcnb)
+└── example/nested (composite, nested)
+    ├── example/libcnb (libcnb, buildpacks/libcnb) (*)
+    ├── example/other (other, buildpacks/other)
+    └── urn:cnb:registry:heroku/registry (registry, unpinned)
+"
+        );
+    }
+
+    #[test]
+    fn render_graph_as_dot() {
+        assert_eq!(
+            render_dot(&test_graph(), Path::new("/workspace")),
+            r#"digraph buildpacks {
+    "example/libcnb" [label="example/libcnb\nbuildpacks/libcnb", kind=libcnb, shape=box];
+    "example/other" [label="example/other\nbuildpacks/other", kind=other, shape=box, style=dashed];
+    "urn:cnb:registry:heroku/registry" [label="urn:cnb:registry:heroku/registry", kind=registry, shape=ellipse, style=dashed];
+    "example/nested" [label="example/nested\nnested", kind=composite, shape=box3d];
+    "docker://docker.io/heroku/example:1.2.3" [label="docker://docker.io/heroku/example:1.2.3", kind=docker, shape=ellipse, style=dashed];
+    "example/composite" [label="example/composite\ncomposite", kind=composite, shape=box3d];
+    "example/nested" -> "example/libcnb";
+    "example/nested" -> "example/other";
+    "example/nested" -> "urn:cnb:registry:heroku/registry";
+    "example/composite" -> "docker://docker.io/heroku/example:1.2.3";
+    "example/composite" -> "example/libcnb";
+    "example/composite" -> "example/nested";
+}
+"#
+        );
+    }
+
+    #[test]
+    fn render_graph_as_json() {
+        let json: serde_json::Value =
+            serde_json::from_str(&render_json(&test_graph(), Path::new("/workspace")).unwrap())
+                .unwrap();
+
+        let nodes = json["nodes"].as_array().unwrap();
+        let ids = nodes
+            .iter()
+            .map(|node| node["id"].as_str().unwrap())
+            .collect::<Vec<_>>();
+
+        // Dependencies are always listed before the buildpacks depending on them.
+        assert_eq!(ids.last(), Some(&"example/composite"));
+        assert!(
+            ids.iter().position(|id| *id == "example/libcnb")
+                < ids.iter().position(|id| *id == "example/nested")
+        );
+
+        assert_eq!(
+            nodes[ids.len() - 1],
+            serde_json::json!({
+                "id": "example/composite",
+                "kind": "composite",
+                "path": "composite",
+                "pinned_version": null,
+                "dependencies": [
+                    "docker://docker.io/heroku/example:1.2.3",
+                    "example/libcnb",
+                    "example/nested"
+                ]
+            })
+        );
+        assert_eq!(
+            nodes[ids.iter().position(|id| id.starts_with("docker")).unwrap()],
+            serde_json::json!({
+                "id": "docker://docker.io/heroku/example:1.2.3",
+                "kind": "docker",
+                "path": null,
+                "pinned_version": "1.2.3",
+                "dependencies": []
+            })
+        );
+    }
+}
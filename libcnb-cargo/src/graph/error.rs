@@ -0,0 +1,14 @@
+use libcnb_package::workspace_graph::BuildWorkspaceGraphError;
+
+#[derive(thiserror::Error, Debug)]
+#[allow(clippy::enum_variant_names)]
+pub(crate) enum Error {
+    #[error("Failed to get current dir: {0}")]
+    CannotGetCurrentDir(#[source] std::io::Error),
+    #[error("Failed to find Cargo workspace root: {0}")]
+    CannotFindCargoWorkspaceRoot(#[source] libcnb_package::FindCargoWorkspaceRootError),
+    #[error("Failed to create buildpack graph: {0}")]
+    CannotBuildWorkspaceGraph(#[source] BuildWorkspaceGraphError),
+    #[error("Failed to serialize buildpack graph: {0}")]
+    CannotSerializeGraph(#[source] serde_json::Error),
+}
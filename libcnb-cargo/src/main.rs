@@ -2,14 +2,16 @@
 
 // Suppress warnings due to the `unused_crate_dependencies` lint not handling integration tests well.
 #[cfg(test)]
-use libcnb_common as _;
-#[cfg(test)]
 use tempfile as _;
 
+mod build;
+mod check;
 mod cli;
+mod graph;
+mod new;
 mod package;
 
-use crate::cli::{Cli, LibcnbSubcommand};
+use crate::cli::{Cli, LibcnbSubcommand, MessageFormat};
 use clap::Parser;
 
 const UNSPECIFIED_ERROR: i32 = 1;
@@ -18,6 +20,33 @@ fn main() {
     match Cli::parse() {
         Cli::Libcnb(LibcnbSubcommand::Package(args)) => {
             if let Err(error) = package::execute(&args) {
+                if args.message_format == MessageFormat::Json {
+                    package::emit_error_message(&error);
+                }
+                eprintln!("❌ {error}");
+                std::process::exit(UNSPECIFIED_ERROR);
+            }
+        }
+        Cli::Libcnb(LibcnbSubcommand::New(args)) => {
+            if let Err(error) = new::execute(&args) {
+                eprintln!("❌ {error}");
+                std::process::exit(UNSPECIFIED_ERROR);
+            }
+        }
+        Cli::Libcnb(LibcnbSubcommand::Check(args)) => {
+            if let Err(error) = check::execute(&args) {
+                eprintln!("❌ {error}");
+                std::process::exit(UNSPECIFIED_ERROR);
+            }
+        }
+        Cli::Libcnb(LibcnbSubcommand::Graph(args)) => {
+            if let Err(error) = graph::execute(&args) {
+                eprintln!("❌ {error}");
+                std::process::exit(UNSPECIFIED_ERROR);
+            }
+        }
+        Cli::Libcnb(LibcnbSubcommand::Build(args)) => {
+            if let Err(error) = build::execute(&args) {
                 eprintln!("❌ {error}");
                 std::process::exit(UNSPECIFIED_ERROR);
             }
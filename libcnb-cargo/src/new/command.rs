@@ -0,0 +1,362 @@
+use crate::cli::NewArgs;
+use crate::new::error::Error;
+use libcnb_common::toml_file::write_toml_file;
+use libcnb_data::buildpack::{
+    BuildpackApi, BuildpackBuilder, BuildpackId, BuildpackTarget, BuildpackVersion,
+    ComponentBuildpackDescriptor, ComponentBuildpackDescriptorBuilder,
+};
+use libcnb_package::find_cargo_workspace_root_dir;
+use libcnb_package::multi_target::TargetPlatform;
+use libcnb_package::util::absolutize_path;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{value, Array, ArrayOfTables, DocumentMut, Item, RawString, Table};
+
+pub(crate) fn execute(args: &NewArgs) -> Result<(), Error> {
+    let current_dir = std::env::current_dir().map_err(Error::CannotGetCurrentDir)?;
+
+    let buildpack_id = args
+        .buildpack_id
+        .parse::<BuildpackId>()
+        .map_err(Error::InvalidBuildpackId)?;
+
+    let crate_name = crate_name(&buildpack_id);
+
+    let crate_dir = absolutize_path(
+        &args
+            .path
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(&crate_name)),
+        &current_dir,
+    );
+
+    if crate_dir.exists() {
+        return Err(Error::DestinationAlreadyExists(crate_dir));
+    }
+
+    let targets = if args.target.is_empty() {
+        vec![String::from(DEFAULT_TARGET_TRIPLE)]
+    } else {
+        args.target.clone()
+    }
+    .iter()
+    .map(|target_triple| {
+        TargetPlatform::from_target_triple(target_triple)
+            .map(|target_platform| BuildpackTarget {
+                os: Some(target_platform.os),
+                arch: Some(target_platform.arch),
+                variant: target_platform.variant,
+                distros: Vec::new(),
+            })
+            .ok_or_else(|| Error::UnsupportedTargetTriple(target_triple.clone()))
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+
+    eprintln!("🌱 Creating buildpack {buildpack_id}...");
+    write_crate(
+        &crate_dir,
+        &crate_name,
+        &buildpack_descriptor(&buildpack_id, targets),
+        &args.builder,
+    )?;
+
+    if args.add_to_workspace {
+        let workspace_root_dir = find_cargo_workspace_root_dir(&current_dir)
+            .map_err(Error::CannotFindCargoWorkspaceRoot)?;
+
+        eprintln!("🗂️ Adding crate to the Cargo workspace...");
+        add_workspace_member(&workspace_root_dir, &crate_dir)?;
+    }
+
+    if let Some(composite_dir) = &args.composite {
+        eprintln!("🧩 Adding buildpack to the composite buildpack...");
+        add_composite_dependency(&absolutize_path(composite_dir, &current_dir), &buildpack_id)?;
+    }
+
+    eprintln!(
+        "✨ Created buildpack {buildpack_id} in {}",
+        pathdiff::diff_paths(&crate_dir, &current_dir)
+            .unwrap_or_else(|| crate_dir.clone())
+            .to_string_lossy()
+    );
+
+    if args.composite.is_some() {
+        eprintln!();
+        eprintln!("💡 Add the buildpack to an [[order]] group in the composite buildpack's buildpack.toml to use it.");
+    }
+
+    println!("{}", crate_dir.to_string_lossy());
+
+    Ok(())
+}
+
+/// Derives a Cargo crate name from a buildpack ID. Buildpack IDs can contain `/` and `.`, which
+/// aren't allowed in crate names.
+fn crate_name(buildpack_id: &BuildpackId) -> String {
+    buildpack_id.replace(['/', '.'], "-")
+}
+
+/// Derives the name of the buildpack struct from the last segment of a buildpack ID, for example
+/// `NodejsEngineBuildpack` for `my-org/nodejs-engine`.
+fn buildpack_struct_name(buildpack_id: &BuildpackId) -> String {
+    buildpack_id
+        .rsplit('/')
+        .next()
+        .unwrap_or(buildpack_id.as_str())
+        // Struct names can't start with a digit.
+        .trim_start_matches(|char: char| char.is_ascii_digit())
+        .split(|char: char| !char.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .chain([String::from("Buildpack")])
+        .collect()
+}
+
+fn buildpack_descriptor(
+    buildpack_id: &BuildpackId,
+    targets: Vec<BuildpackTarget>,
+) -> ComponentBuildpackDescriptor {
+    ComponentBuildpackDescriptorBuilder::new(
+        BuildpackApi {
+            major: 0,
+            minor: 10,
+        },
+        BuildpackBuilder::new(buildpack_id.clone(), BuildpackVersion::new(0, 1, 0))
+            .name(buildpack_id.to_string())
+            .build(),
+    )
+    .targets(targets)
+    .build()
+}
+
+fn write_crate(
+    crate_dir: &Path,
+    crate_name: &str,
+    buildpack_descriptor: &ComponentBuildpackDescriptor,
+    builder: &str,
+) -> Result<(), Error> {
+    let buildpack_struct_name = buildpack_struct_name(&buildpack_descriptor.buildpack.id);
+
+    let files = [
+        (
+            PathBuf::from("Cargo.toml"),
+            CARGO_TOML_TEMPLATE
+                .replace("{{CRATE_NAME}}", crate_name)
+                .replace("{{LIBCNB_VERSION}}", env!("CARGO_PKG_VERSION")),
+        ),
+        (
+            PathBuf::from(".gitignore"),
+            String::from(GITIGNORE_TEMPLATE),
+        ),
+        (
+            PathBuf::from("src").join("main.rs"),
+            MAIN_RS_TEMPLATE.replace("{{BUILDPACK_STRUCT_NAME}}", &buildpack_struct_name),
+        ),
+        (
+            PathBuf::from("tests").join("integration_test.rs"),
+            INTEGRATION_TEST_RS_TEMPLATE.replace("{{BUILDER}}", builder),
+        ),
+        (
+            PathBuf::from("tests")
+                .join("fixtures")
+                .join("basic-app")
+                .join(".gitkeep"),
+            String::new(),
+        ),
+    ];
+
+    for (relative_path, contents) in files {
+        let path = crate_dir.join(relative_path);
+        path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(&path, contents))
+            .map_err(|error| Error::CannotWriteFile(path.clone(), error))?;
+    }
+
+    write_toml_file(buildpack_descriptor, crate_dir.join("buildpack.toml"))
+        .map_err(Error::CannotWriteBuildpackDescriptor)
+}
+
+/// Adds the crate in the given directory to the `members` of the workspace, keeping the formatting
+/// of the workspace's `Cargo.toml` intact.
+fn add_workspace_member(workspace_root_dir: &Path, crate_dir: &Path) -> Result<(), Error> {
+    let cargo_toml_path = workspace_root_dir.join("Cargo.toml");
+    let mut document = read_toml_document(&cargo_toml_path)?;
+
+    let members = document
+        .get_mut("workspace")
+        .and_then(|workspace| workspace.get_mut("members"))
+        .and_then(Item::as_array_mut)
+        .ok_or_else(|| Error::NoWorkspaceMembers(cargo_toml_path.clone()))?;
+
+    let member = relative_toml_path(workspace_root_dir, crate_dir);
+    if !members
+        .iter()
+        .any(|existing| existing.as_str() == Some(&member))
+    {
+        push_array_value(members, member);
+    }
+
+    fs::write(&cargo_toml_path, document.to_string())
+        .map_err(|error| Error::CannotWriteFile(cargo_toml_path, error))
+}
+
+/// Adds a `libcnb:` dependency on the given buildpack to the `package.toml` of the composite
+/// buildpack in the given directory, keeping its formatting intact.
+fn add_composite_dependency(composite_dir: &Path, buildpack_id: &BuildpackId) -> Result<(), Error> {
+    let package_toml_path = composite_dir.join("package.toml");
+    let mut document = read_toml_document(&package_toml_path)?;
+
+    let dependencies = document
+        .entry("dependencies")
+        .or_insert_with(|| Item::ArrayOfTables(ArrayOfTables::new()))
+        .as_array_of_tables_mut()
+        .ok_or_else(|| Error::InvalidPackageDescriptorDependencies(package_toml_path.clone()))?;
+
+    let uri = format!("libcnb:{buildpack_id}");
+    if !dependencies
+        .iter()
+        .any(|dependency| dependency.get("uri").and_then(Item::as_str) == Some(&uri))
+    {
+        let mut dependency = Table::new();
+        dependency.insert("uri", value(uri));
+        dependencies.push(dependency);
+    }
+
+    fs::write(&package_toml_path, document.to_string())
+        .map_err(|error| Error::CannotWriteFile(package_toml_path, error))
+}
+
+fn read_toml_document(path: &Path) -> Result<DocumentMut, Error> {
+    fs::read_to_string(path)
+        .map_err(|error| Error::CannotReadFile(path.to_path_buf(), error))?
+        .parse::<DocumentMut>()
+        .map_err(|error| Error::CannotParseToml(path.to_path_buf(), error))
+}
+
+// Pushes a value to an array. If the array is formatted with one value per line, the new value is
+// added on its own line with the same indentation as the existing ones.
+fn push_array_value(array: &mut Array, value: String) {
+    let indentation = array
+        .iter()
+        .filter_map(|existing| existing.decor().prefix().and_then(RawString::as_str))
+        .find(|prefix| prefix.contains('\n'))
+        .and_then(|prefix| prefix.rsplit('\n').next())
+        .map(String::from);
+
+    let Some(indentation) = indentation else {
+        array.push(value);
+        return;
+    };
+
+    // Without a trailing comma, the line break after the last value is part of that value's
+    // decoration instead of the array's trailing whitespace.
+    if !array.trailing_comma() {
+        if let Some(last) = array.iter_mut().last() {
+            let suffix = last
+                .decor()
+                .suffix()
+                .and_then(RawString::as_str)
+                .unwrap_or_default()
+                .to_string();
+            last.decor_mut().set_suffix("");
+
+            let trailing = array.trailing().as_str().unwrap_or_default().to_string();
+            array.set_trailing(suffix + &trailing);
+        }
+    }
+
+    let previous_trailing = array.trailing().as_str().unwrap_or("\n").to_string();
+
+    array.push(value);
+    if let Some(pushed) = array.iter_mut().last() {
+        pushed
+            .decor_mut()
+            .set_prefix(previous_trailing + &indentation);
+    }
+
+    array.set_trailing("\n");
+    array.set_trailing_comma(true);
+}
+
+// Cargo expects workspace member paths to use `/` as the separator on all platforms.
+fn relative_toml_path(base_dir: &Path, path: &Path) -> String {
+    pathdiff::diff_paths(path, base_dir)
+        .unwrap_or_else(|| path.to_path_buf())
+        .iter()
+        .map(|component| component.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+const DEFAULT_TARGET_TRIPLE: &str = "x86_64-unknown-linux-musl";
+
+const CARGO_TOML_TEMPLATE: &str = include_str!("templates/Cargo.toml.template");
+const GITIGNORE_TEMPLATE: &str = include_str!("templates/gitignore.template");
+const MAIN_RS_TEMPLATE: &str = include_str!("templates/main.rs.template");
+const INTEGRATION_TEST_RS_TEMPLATE: &str = include_str!("templates/integration_test.rs.template");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libcnb_data::buildpack_id;
+
+    #[test]
+    fn derive_names_from_buildpack_id() {
+        let buildpack_id = buildpack_id!("my-org/nodejs-engine.v2");
+
+        assert_eq!(crate_name(&buildpack_id), "my-org-nodejs-engine-v2");
+        assert_eq!(
+            buildpack_struct_name(&buildpack_id),
+            "NodejsEngineV2Buildpack"
+        );
+        assert_eq!(
+            buildpack_struct_name(&buildpack_id!("3d-printer")),
+            "DPrinterBuildpack"
+        );
+    }
+
+    #[test]
+    fn add_workspace_member_keeps_formatting() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[workspace]\nresolver = \"2\"\nmembers = [\n    \"buildpacks/a\", # The first one\n]\n",
+        )
+        .unwrap();
+
+        add_workspace_member(
+            temp_dir.path(),
+            &temp_dir.path().join("buildpacks").join("b"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("Cargo.toml")).unwrap(),
+            "[workspace]\nresolver = \"2\"\nmembers = [\n    \"buildpacks/a\", # The first one\n    \"buildpacks/b\",\n]\n"
+        );
+    }
+
+    #[test]
+    fn add_composite_dependency_appends_libcnb_uri() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let package_toml =
+            "[buildpack]\nuri = \".\"\n\n[[dependencies]]\nuri = \"libcnb:my-org/a\"\n";
+        fs::write(temp_dir.path().join("package.toml"), package_toml).unwrap();
+
+        add_composite_dependency(temp_dir.path(), &buildpack_id!("my-org/b")).unwrap();
+        // Adding the same dependency again is a no-op.
+        add_composite_dependency(temp_dir.path(), &buildpack_id!("my-org/b")).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("package.toml")).unwrap(),
+            format!("{package_toml}\n[[dependencies]]\nuri = \"libcnb:my-org/b\"\n")
+        );
+    }
+}
@@ -0,0 +1,29 @@
+use libcnb_common::toml_file::TomlFileError;
+use libcnb_data::buildpack::BuildpackIdError;
+use std::path::PathBuf;
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("Failed to get current dir: {0}")]
+    CannotGetCurrentDir(#[source] std::io::Error),
+    #[error("Invalid buildpack ID: {0}")]
+    InvalidBuildpackId(#[source] BuildpackIdError),
+    #[error("Target triple {0} is not supported")]
+    UnsupportedTargetTriple(String),
+    #[error("Destination {0} already exists")]
+    DestinationAlreadyExists(PathBuf),
+    #[error("Failed to write {0}: {1}")]
+    CannotWriteFile(PathBuf, #[source] std::io::Error),
+    #[error("Failed to write buildpack.toml: {0}")]
+    CannotWriteBuildpackDescriptor(#[source] TomlFileError),
+    #[error("Failed to find Cargo workspace root: {0}")]
+    CannotFindCargoWorkspaceRoot(#[source] libcnb_package::FindCargoWorkspaceRootError),
+    #[error("Failed to read {0}: {1}")]
+    CannotReadFile(PathBuf, #[source] std::io::Error),
+    #[error("Failed to parse {0}: {1}")]
+    CannotParseToml(PathBuf, #[source] toml_edit::TomlError),
+    #[error("{0} does not define a Cargo workspace with a members list")]
+    NoWorkspaceMembers(PathBuf),
+    #[error("{0} has an invalid dependencies list")]
+    InvalidPackageDescriptorDependencies(PathBuf),
+}
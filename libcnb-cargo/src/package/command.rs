@@ -1,18 +1,59 @@
-use crate::cli::PackageArgs;
+use crate::cli::{CrossCompileBackend, MessageFormat, PackageArgs, PackageFormat};
 use crate::package::error::Error;
+use crate::package::message::{emit_error_message, Reporter};
+use crate::package::watch;
+use cargo_metadata::MetadataCommand;
 use libcnb_data::buildpack::BuildpackId;
-use libcnb_package::buildpack_dependency_graph::build_libcnb_buildpacks_dependency_graph;
-use libcnb_package::cross_compile::{cross_compile_assistance, CrossCompileAssistance};
+use libcnb_package::binary_audit::{read_binary_audit_report, BinaryAuditReport, Linkage};
+use libcnb_package::build::CargoBuildOptions;
+use libcnb_package::buildpack_dependency_graph::{
+    build_libcnb_buildpacks_dependency_graph, BuildpackDependencyGraphNode,
+};
+use libcnb_package::buildpackage::write_buildpackage;
+use libcnb_package::cross_compile::{
+    cross_compile_backend_assistance, glibc_incompatibilities, CrossCompileAssistance,
+};
 use libcnb_package::dependency_graph::get_dependencies;
-use libcnb_package::output::create_packaged_buildpack_dir_resolver;
+use libcnb_package::multi_target::{
+    assemble_multi_target_buildpack_directory, buildpack_target_distros, buildpack_target_triples,
+    TargetPlatform,
+};
+use libcnb_package::output::{
+    buildpackage_archive_path, create_multi_target_packaged_buildpack_dir_resolver,
+    create_packaged_buildpack_dir_resolver,
+};
+use libcnb_package::package::PackageBuildpackOutcome;
 use libcnb_package::util::absolutize_path;
 use libcnb_package::{find_cargo_workspace_root_dir, CargoProfile};
 use std::collections::BTreeMap;
+use std::ffi::OsString;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
 
 pub(crate) fn execute(args: &PackageArgs) -> Result<(), Error> {
+    package_root_buildpacks(args, true).map(|_| ())
+}
+
+/// Packages the buildpack in the current directory or, when run from the workspace root, all
+/// buildpacks in the workspace, together with their dependencies.
+///
+/// Returns the packaged directories of the root buildpacks for each target triple, in the order
+/// they were packaged, which can be passed to `pack build`. With `print_outputs` disabled, neither
+/// the output paths nor the pack command hint are printed, for commands that use packaging as one
+/// of their steps.
+pub(crate) fn package_root_buildpacks(
+    args: &PackageArgs,
+    print_outputs: bool,
+) -> Result<RootBuildpackDirsPerTarget, Error> {
     let current_dir = std::env::current_dir().map_err(Error::CannotGetCurrentDir)?;
+    let reporter = Reporter {
+        current_dir: &current_dir,
+        message_format: args.message_format,
+        print_outputs,
+    };
 
     let cargo_profile = if args.release {
         CargoProfile::Release
@@ -35,48 +76,16 @@ pub(crate) fn execute(args: &PackageArgs) -> Result<(), Error> {
     fs::create_dir_all(&package_dir)
         .map_err(|error| Error::CannotCreatePackageDirectory(package_dir.clone(), error))?;
 
-    let buildpack_dir_resolver =
-        create_packaged_buildpack_dir_resolver(&package_dir, cargo_profile, &args.target);
-
-    eprintln!("🖥️ Gathering Cargo configuration (for {})", args.target);
-    let cargo_build_env = if args.no_cross_compile_assistance {
-        Vec::new()
-    } else {
-        match cross_compile_assistance(&args.target) {
-            CrossCompileAssistance::Configuration { cargo_env } => cargo_env,
-            CrossCompileAssistance::NoAssistance => {
-                eprintln!(
-                    "Couldn't determine automatic cross-compile settings for target triple {}.",
-                    args.target
-                );
-                eprintln!("This is not an error, but without proper cross-compile settings in your Cargo manifest and locally installed toolchains, compilation might fail.");
-                eprintln!("To disable this warning, pass --no-cross-compile-assistance.");
-                Vec::new()
-            }
-            CrossCompileAssistance::HelpText(help_text) => {
-                eprintln!("{help_text}");
-                return Err(Error::CannotConfigureCrossCompilation);
-            }
-        }
-    };
-
     eprintln!("🏗️ Building buildpack dependency graph...");
     let buildpack_dependency_graph = build_libcnb_buildpacks_dependency_graph(&workspace_root_path)
         .map_err(Error::CannotBuildBuildpackDependencyGraph)?;
 
     eprintln!("🔀 Determining build order...");
-    let root_nodes = buildpack_dependency_graph
-        .node_weights()
-        .find(|node| node.path == current_dir)
-        .map(|node| vec![node])
-        .or_else(|| {
-            current_dir.eq(&workspace_root_path).then(|| {
-                buildpack_dependency_graph
-                    .node_weights()
-                    .collect::<Vec<_>>()
-            })
-        })
-        .unwrap_or_default();
+    let root_nodes = root_nodes(
+        buildpack_dependency_graph.node_weights().collect(),
+        &current_dir,
+        &workspace_root_path,
+    );
 
     let build_order = get_dependencies(&buildpack_dependency_graph, &root_nodes)
         .map_err(Error::CannotGetDependencies)?;
@@ -85,93 +94,808 @@ pub(crate) fn execute(args: &PackageArgs) -> Result<(), Error> {
         return Err(Error::NoBuildpacksFound);
     }
 
-    eprintln!("🚚 Building {} buildpacks...", build_order.len());
-    let mut packaged_buildpack_dirs = BTreeMap::new();
+    reporter.dependency_graph_computed(
+        &buildpack_dependency_graph
+            .node_weights()
+            .collect::<Vec<_>>(),
+        &build_order,
+    );
+
+    let (target_triples, buildpack_target_triples) = if args.target.is_empty() {
+        determine_target_triples(&build_order)?
+    } else {
+        (args.target.clone(), BuildpackTargetTriples::new())
+    };
+
+    let target_platforms = target_platforms(args.format, &target_triples)?;
+
+    let mut cargo_build_envs = Vec::new();
+    for target_triple in &target_triples {
+        cargo_build_envs.push(cargo_build_env(args, target_triple)?);
+    }
+
+    let context = PackageContext {
+        args,
+        cargo_profile,
+        cargo_build_options: cargo_build_options(args),
+        package_dir,
+        root_nodes,
+        target_triples,
+        buildpack_target_triples,
+        target_platforms,
+        cargo_build_envs,
+        reporter,
+    };
+
+    let mut packaged_buildpack_dirs = PackagedBuildpackDirs {
+        per_target: vec![BTreeMap::new(); context.target_triples.len()],
+        output: BTreeMap::new(),
+    };
+
+    if !args.watch {
+        package(&context, &build_order, &mut packaged_buildpack_dirs)?;
+
+        return Ok(root_buildpack_dirs_per_target(
+            &context,
+            packaged_buildpack_dirs,
+        ));
+    }
+
+    // Packaging failures are reported, but don't end watch mode since they're usually fixed by the
+    // next change.
+    let result = package(&context, &build_order, &mut packaged_buildpack_dirs);
+    let previous_run_succeeded = report_watch_result(&context, result);
+
+    watch(
+        &context,
+        &build_order,
+        &[
+            context.package_dir.clone(),
+            cargo_target_dir(&workspace_root_path)?,
+        ],
+        &mut packaged_buildpack_dirs,
+        previous_run_succeeded,
+    )
+}
+
+/// Determines the Cargo target directory of the given workspace, which watch mode ignores so that
+/// build outputs don't trigger repackaging. It isn't necessarily the `target` directory in the
+/// workspace root, since it can be configured.
+fn cargo_target_dir(workspace_root_path: &Path) -> Result<PathBuf, Error> {
+    MetadataCommand::new()
+        .current_dir(workspace_root_path)
+        .no_deps()
+        .exec()
+        .map(|cargo_metadata| cargo_metadata.target_directory.into_std_path_buf())
+        .map_err(Error::CannotReadCargoMetadata)
+}
+
+/// Everything needed to package buildpacks that doesn't change between packaging runs in watch
+/// mode.
+struct PackageContext<'a> {
+    args: &'a PackageArgs,
+    cargo_profile: CargoProfile,
+    cargo_build_options: CargoBuildOptions,
+    package_dir: PathBuf,
+    root_nodes: Vec<&'a BuildpackDependencyGraphNode>,
+    target_triples: Vec<String>,
+    /// The target triples of the buildpacks that declare targets in `buildpack.toml`. Empty when
+    /// the target triples were passed explicitly, since they apply to all buildpacks then.
+    buildpack_target_triples: BuildpackTargetTriples,
+    target_platforms: Vec<TargetPlatform>,
+    cargo_build_envs: Vec<Vec<(OsString, OsString)>>,
+    reporter: Reporter<'a>,
+}
+
+/// The packaged directories of the root buildpacks, keyed by buildpack ID, for each target triple.
+type RootBuildpackDirsPerTarget = Vec<(String, BTreeMap<BuildpackId, PathBuf>)>;
+
+/// The target triples declared by each buildpack, keyed by buildpack ID.
+type BuildpackTargetTriples = BTreeMap<BuildpackId, Vec<String>>;
+
+/// The packaged buildpack directories for each target triple, in the same order as the target
+/// triples of the [`PackageContext`], and the buildpack directories to output. These are the same
+/// directories when packaging for a single target, and multi-target buildpack directories
+/// otherwise.
+struct PackagedBuildpackDirs {
+    per_target: Vec<BTreeMap<BuildpackId, PathBuf>>,
+    output: BTreeMap<BuildpackId, PathBuf>,
+}
+
+/// Selects the packaged directories of the root buildpacks, for each target triple.
+fn root_buildpack_dirs_per_target(
+    context: &PackageContext,
+    packaged_buildpack_dirs: PackagedBuildpackDirs,
+) -> RootBuildpackDirsPerTarget {
+    context
+        .target_triples
+        .iter()
+        .cloned()
+        .zip(packaged_buildpack_dirs.per_target)
+        .map(|(target_triple, dirs)| {
+            let root_buildpack_dirs = dirs
+                .into_iter()
+                .filter(|(buildpack_id, _)| {
+                    context
+                        .root_nodes
+                        .iter()
+                        .any(|node| node.buildpack_id == *buildpack_id)
+                })
+                .collect();
+
+            (target_triple, root_buildpack_dirs)
+        })
+        .collect()
+}
+
+/// Packages the given buildpacks, in order, for all target triples and writes the outputs of the
+/// root buildpacks among them.
+///
+/// Dependencies that aren't part of the given build order must have been packaged before, which
+/// allows repackaging only the buildpacks affected by a change in watch mode.
+fn package(
+    context: &PackageContext,
+    build_order: &[&BuildpackDependencyGraphNode],
+    packaged_buildpack_dirs: &mut PackagedBuildpackDirs,
+) -> Result<(), Error> {
+    for ((target_triple, cargo_build_env), packaged_buildpack_dirs) in context
+        .target_triples
+        .iter()
+        .zip(&context.cargo_build_envs)
+        .zip(&mut packaged_buildpack_dirs.per_target)
+    {
+        package_buildpacks(
+            context,
+            build_order,
+            target_triple,
+            cargo_build_env,
+            packaged_buildpack_dirs,
+        )?;
+    }
+
+    if context.target_triples.len() == 1 {
+        packaged_buildpack_dirs.output = packaged_buildpack_dirs
+            .per_target
+            .first()
+            .cloned()
+            .unwrap_or_default();
+    } else {
+        packaged_buildpack_dirs
+            .output
+            .extend(assemble_multi_target_buildpack_dirs(
+                build_order,
+                &context.target_platforms,
+                &packaged_buildpack_dirs.per_target,
+                &create_multi_target_packaged_buildpack_dir_resolver(
+                    &context.package_dir,
+                    context.cargo_profile,
+                ),
+                &context.reporter,
+            )?);
+    }
+
+    let root_nodes = context
+        .root_nodes
+        .iter()
+        .filter(|root_node| {
+            build_order
+                .iter()
+                .any(|node| node.buildpack_id == root_node.buildpack_id)
+        })
+        .copied()
+        .collect::<Vec<_>>();
+
+    write_outputs(
+        context.args.format,
+        &root_nodes,
+        &context.target_platforms,
+        &packaged_buildpack_dirs.per_target,
+        packaged_buildpack_dirs.output.clone(),
+        &context.reporter,
+    )?;
+
+    context.reporter.finished();
+
+    Ok(())
+}
+
+/// Watches the files of the given buildpacks and repackages the buildpacks affected by changes,
+/// until the process is terminated. The package directory and Cargo's target directory are
+/// excluded, since packaging writes to them.
+fn watch(
+    context: &PackageContext,
+    build_order: &[&BuildpackDependencyGraphNode],
+    excluded_dirs: &[PathBuf],
+    packaged_buildpack_dirs: &mut PackagedBuildpackDirs,
+    mut previous_run_succeeded: bool,
+) -> ! {
+    eprintln!(
+        "👀 Watching {} buildpacks for changes, press Ctrl+C to stop...",
+        build_order.len()
+    );
+
+    let mut snapshot = watch::snapshot_buildpack_files(build_order, excluded_dirs);
+
+    loop {
+        thread::sleep(WATCH_POLL_INTERVAL);
+
+        let current_snapshot = watch::snapshot_buildpack_files(build_order, excluded_dirs);
+        let changed_buildpack_ids =
+            watch::changed_buildpacks(build_order, &snapshot, &current_snapshot);
+
+        if changed_buildpack_ids.is_empty() {
+            continue;
+        }
+
+        // Editors often write files in several steps, so wait for changes to settle before
+        // taking the snapshot that the next change is compared against.
+        thread::sleep(WATCH_POLL_INTERVAL);
+        snapshot = watch::snapshot_buildpack_files(build_order, excluded_dirs);
+
+        // After a failed run, the packaged directories of unchanged buildpacks might be missing or
+        // outdated, so everything is repackaged. Up to date buildpacks are skipped anyway.
+        let affected_buildpacks = if previous_run_succeeded {
+            watch::affected_buildpacks(build_order, &changed_buildpack_ids)
+        } else {
+            build_order.to_vec()
+        };
+
+        eprintln!(
+            "🔁 Detected changes in {}, repackaging {} buildpacks...",
+            changed_buildpack_ids
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+            affected_buildpacks.len()
+        );
+
+        let result = package(context, &affected_buildpacks, packaged_buildpack_dirs);
+        previous_run_succeeded = report_watch_result(context, result);
+    }
+}
+
+/// Reports the result of a packaging run in watch mode and runs the `--exec` command if it was
+/// successful. Returns whether packaging was successful.
+fn report_watch_result(context: &PackageContext, result: Result<(), Error>) -> bool {
+    match result {
+        Ok(()) => {
+            if let Some(command) = &context.args.exec {
+                run_exec_command(command);
+            }
+            true
+        }
+        Err(error) => {
+            if context.args.message_format == MessageFormat::Json {
+                emit_error_message(&error);
+            }
+            eprintln!("❌ {error}");
+            false
+        }
+    }
+}
+
+fn run_exec_command(command: &str) {
+    eprintln!("🏃 Running: {command}");
+
+    let (shell, shell_arg) = if cfg!(target_family = "windows") {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+
+    // stdout only contains the packaged buildpack paths or JSON messages, so the command's output
+    // goes to stderr.
+    let status = Command::new(shell)
+        .args([shell_arg, command])
+        .stdout(Stdio::from(std::io::stderr()))
+        .status();
+
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => eprintln!("⚠️ Command failed with {status}"),
+        Err(error) => eprintln!("⚠️ Couldn't run command: {error}"),
+    }
+}
+
+fn cargo_build_options(args: &PackageArgs) -> CargoBuildOptions {
+    CargoBuildOptions {
+        features: args.features.clone(),
+        profile: args.profile.clone(),
+        rustflags: args
+            .rustflags
+            .iter()
+            .flat_map(|rustflags| rustflags.split_whitespace())
+            .map(String::from)
+            .collect(),
+        locked: args.locked,
+        no_strip: args.no_strip,
+        cross_compile_backend: args.backend.into(),
+    }
+}
+
+/// Writes buildpackage archives if requested and reports the output paths of the root buildpacks.
+///
+/// Pack can't use multi-target buildpack directories directly, so the hint always refers to the
+/// directories (or buildpackage archives) of the first target.
+fn write_outputs(
+    format: PackageFormat,
+    root_nodes: &[&BuildpackDependencyGraphNode],
+    target_platforms: &[TargetPlatform],
+    packaged_buildpack_dirs_per_target: &[BTreeMap<BuildpackId, PathBuf>],
+    output_buildpack_dirs: BTreeMap<BuildpackId, PathBuf>,
+    reporter: &Reporter,
+) -> Result<(), Error> {
+    let (pack_hint_paths, output_paths) = if format == PackageFormat::Oci {
+        let buildpackage_paths_per_target = write_buildpackages(
+            root_nodes,
+            target_platforms,
+            packaged_buildpack_dirs_per_target,
+            reporter,
+        )?;
+
+        (
+            buildpackage_paths_per_target.first().cloned(),
+            buildpackage_paths_per_target
+                .iter()
+                .flat_map(BTreeMap::iter)
+                .map(|(id, buildpackage_path)| (id.clone(), buildpackage_path.clone()))
+                .collect::<Vec<_>>(),
+        )
+    } else {
+        (
+            packaged_buildpack_dirs_per_target.first().cloned(),
+            output_buildpack_dirs
+                .into_iter()
+                .filter(|(id, _)| root_nodes.iter().any(|node| node.buildpack_id == *id))
+                .collect(),
+        )
+    };
+
+    eprintln!("✨ Packaging successfully finished!");
+
+    if let Some(pack_hint_paths) = pack_hint_paths.filter(|_| reporter.print_outputs) {
+        eprint_pack_command_hint(&pack_hint_paths, reporter);
+    }
+
+    for (buildpack_id, output_path) in output_paths {
+        reporter.output(&buildpack_id, &output_path);
+    }
+
+    Ok(())
+}
+
+/// Determines the buildpacks to package: the buildpack in the current directory or, when run from
+/// the workspace root, all buildpacks in the workspace.
+fn root_nodes<'a>(
+    nodes: Vec<&'a BuildpackDependencyGraphNode>,
+    current_dir: &Path,
+    workspace_root_path: &Path,
+) -> Vec<&'a BuildpackDependencyGraphNode> {
+    if let Some(node) = nodes.iter().find(|node| node.path == current_dir) {
+        vec![*node]
+    } else if current_dir == workspace_root_path {
+        nodes
+    } else {
+        Vec::new()
+    }
+}
+
+/// Determines the platforms of the given target triples, if they are needed for packaging.
+///
+/// Packaging for multiple targets and writing buildpackage archives requires knowing the platform of
+/// each target, so unsupported target triples are rejected before spending time on compilation.
+fn target_platforms(
+    format: PackageFormat,
+    target_triples: &[String],
+) -> Result<Vec<TargetPlatform>, Error> {
+    let unsupported_target_triple_error = if format == PackageFormat::Oci {
+        Error::UnsupportedBuildpackageTargetTriple
+    } else if target_triples.len() > 1 {
+        Error::UnsupportedMultiTargetTriple
+    } else {
+        return Ok(Vec::new());
+    };
+
+    target_triples
+        .iter()
+        .map(|target_triple| {
+            TargetPlatform::from_target_triple(target_triple)
+                .ok_or_else(|| unsupported_target_triple_error(target_triple.clone()))
+        })
+        .collect()
+}
+
+/// Packages the given buildpacks, in order, for a single target triple and adds their packaged
+/// directories to the given ones.
+fn package_buildpacks(
+    context: &PackageContext,
+    build_order: &[&BuildpackDependencyGraphNode],
+    target_triple: &str,
+    cargo_build_env: &[(OsString, OsString)],
+    packaged_buildpack_dirs: &mut BTreeMap<BuildpackId, PathBuf>,
+) -> Result<(), Error> {
+    let reporter = &context.reporter;
+    let buildpack_dir_resolver = create_packaged_buildpack_dir_resolver(
+        &context.package_dir,
+        context.cargo_profile,
+        target_triple,
+    );
+
+    let build_order =
+        buildpacks_for_target(context, build_order, target_triple, packaged_buildpack_dirs);
+
+    eprintln!(
+        "🚚 Building {} buildpacks (for {target_triple})...",
+        build_order.len()
+    );
+    let mut up_to_date_count = 0;
     for (node_index, node) in build_order.iter().enumerate() {
         eprintln!(
             "📦 [{}/{}] Building {} (./{})",
             node_index + 1,
             build_order.len(),
             node.buildpack_id,
-            pathdiff::diff_paths(&node.path, &current_dir)
-                .unwrap_or_else(|| node.path.clone())
-                .to_string_lossy()
+            reporter.relative_path(&node.path)
         );
 
         let buildpack_destination_dir = buildpack_dir_resolver(&node.buildpack_id);
-        let _ = fs::remove_dir_all(&buildpack_destination_dir);
-        fs::create_dir_all(&buildpack_destination_dir).map_err(|error| {
-            Error::CannotCreateBuildpackDestinationDir(buildpack_destination_dir.clone(), error)
-        })?;
 
-        libcnb_package::package::package_buildpack(
+        let outcome = libcnb_package::package::package_buildpack(
             &node.path,
-            cargo_profile,
-            &args.target,
-            &cargo_build_env,
+            context.cargo_profile,
+            &context.cargo_build_options,
+            target_triple,
+            cargo_build_env,
             &buildpack_destination_dir,
-            &packaged_buildpack_dirs,
+            packaged_buildpack_dirs,
         )
         .map_err(Error::CannotPackageBuildpack)?;
 
-        eprint_compiled_buildpack_success(&current_dir, &buildpack_destination_dir);
+        if outcome == PackageBuildpackOutcome::UpToDate {
+            up_to_date_count += 1;
+        }
+
+        eprint_compiled_buildpack_success(reporter, &buildpack_destination_dir, outcome);
+
+        // Only libcnb.rs buildpacks have a binary audit report.
+        let binary_audit_report = read_binary_audit_report(&buildpack_destination_dir).ok();
+        if let Some(binary_audit_report) = &binary_audit_report {
+            eprint_binary_audit_report(binary_audit_report);
+            eprint_glibc_warnings(node, target_triple, binary_audit_report)?;
+        }
+
+        reporter.buildpack_packaged(
+            &node.buildpack_id,
+            target_triple,
+            &buildpack_destination_dir,
+            outcome,
+            binary_audit_report.as_ref(),
+        );
 
         packaged_buildpack_dirs.insert(node.buildpack_id.clone(), buildpack_destination_dir);
     }
 
-    eprint_pack_command_hint(&packaged_buildpack_dirs, &current_dir);
+    if up_to_date_count > 0 {
+        eprintln!(
+            "⏭️ Skipped repackaging {up_to_date_count} of {} buildpacks, their packaged directories were up to date (for {target_triple})",
+            build_order.len()
+        );
+    }
+
+    Ok(())
+}
 
-    packaged_buildpack_dirs
-        .iter()
-        .filter(|(id, _)| root_nodes.iter().any(|node| node.buildpack_id == **id))
-        .for_each(|(_, packaged_buildpack_dir)| {
-            println!("{}", packaged_buildpack_dir.to_string_lossy());
+/// Determines the buildpacks of the given build order to package for the given target triple.
+///
+/// Buildpacks that declare targets are only packaged for them, and buildpacks that depend on a
+/// buildpack that isn't packaged for the target triple are skipped as well.
+fn buildpacks_for_target<'a>(
+    context: &PackageContext,
+    build_order: &[&'a BuildpackDependencyGraphNode],
+    target_triple: &str,
+    packaged_buildpack_dirs: &BTreeMap<BuildpackId, PathBuf>,
+) -> Vec<&'a BuildpackDependencyGraphNode> {
+    let mut buildpacks: Vec<&BuildpackDependencyGraphNode> = Vec::new();
+
+    // Dependencies always come before their dependents in the build order, so a single pass is
+    // enough to skip transitive dependents.
+    for node in build_order {
+        let supports_target_triple = context
+            .buildpack_target_triples
+            .get(&node.buildpack_id)
+            .map_or(true, |target_triples| {
+                target_triples.iter().any(|other| other == target_triple)
+            });
+
+        let dependencies_packaged = node.dependencies.iter().all(|dependency| {
+            packaged_buildpack_dirs.contains_key(dependency)
+                || buildpacks
+                    .iter()
+                    .any(|buildpack| buildpack.buildpack_id == *dependency)
         });
 
+        let skip_reason = if !supports_target_triple {
+            "it doesn't declare this target"
+        } else if !dependencies_packaged {
+            "one of its dependencies doesn't support this target"
+        } else {
+            buildpacks.push(node);
+            continue;
+        };
+
+        eprintln!(
+            "⏭️ Skipping {} (./{}), {skip_reason} (for {target_triple})",
+            node.buildpack_id,
+            context.reporter.relative_path(&node.path)
+        );
+    }
+
+    buildpacks
+}
+
+/// Assembles multi-target buildpack directories from the buildpack directories packaged for each
+/// of the given target platforms. Buildpacks are only assembled for the platforms they were
+/// packaged for.
+fn assemble_multi_target_buildpack_dirs(
+    build_order: &[&BuildpackDependencyGraphNode],
+    target_platforms: &[TargetPlatform],
+    packaged_buildpack_dirs_per_target: &[BTreeMap<BuildpackId, PathBuf>],
+    multi_target_buildpack_dir_resolver: &impl Fn(&BuildpackId) -> PathBuf,
+    reporter: &Reporter,
+) -> Result<BTreeMap<BuildpackId, PathBuf>, Error> {
+    eprintln!("🧩 Assembling multi-target buildpack directories...");
+    let mut multi_target_buildpack_dirs = BTreeMap::new();
+    for node in build_order {
+        let buildpack_destination_dir = multi_target_buildpack_dir_resolver(&node.buildpack_id);
+
+        let packaged_buildpack_dirs = target_platforms
+            .iter()
+            .zip(packaged_buildpack_dirs_per_target)
+            .filter_map(|(target_platform, packaged_buildpack_dirs)| {
+                packaged_buildpack_dirs
+                    .get(&node.buildpack_id)
+                    .map(|dir| (target_platform.clone(), dir.clone()))
+            })
+            .collect::<Vec<_>>();
+
+        if packaged_buildpack_dirs.is_empty() {
+            continue;
+        }
+
+        assemble_multi_target_buildpack_directory(
+            &buildpack_destination_dir,
+            &packaged_buildpack_dirs,
+        )
+        .map_err(Error::CannotAssembleMultiTargetBuildpackDirectory)?;
+
+        eprintln!(
+            "Successfully wrote multi-target buildpack directory: {}",
+            reporter.relative_path(&buildpack_destination_dir)
+        );
+        reporter.multi_target_buildpack_assembled(&node.buildpack_id, &buildpack_destination_dir);
+
+        multi_target_buildpack_dirs.insert(node.buildpack_id.clone(), buildpack_destination_dir);
+    }
+
+    Ok(multi_target_buildpack_dirs)
+}
+
+/// Writes buildpackage archives for the given root buildpacks, for each of the given target
+/// platforms, next to their packaged buildpack directories.
+fn write_buildpackages(
+    root_nodes: &[&BuildpackDependencyGraphNode],
+    target_platforms: &[TargetPlatform],
+    packaged_buildpack_dirs_per_target: &[BTreeMap<BuildpackId, PathBuf>],
+    reporter: &Reporter,
+) -> Result<Vec<BTreeMap<BuildpackId, PathBuf>>, Error> {
+    eprintln!("🗃️ Writing buildpackage archives...");
+    let mut buildpackage_paths_per_target = Vec::new();
+    for (target_platform, packaged_buildpack_dirs) in target_platforms
+        .iter()
+        .zip(packaged_buildpack_dirs_per_target)
+    {
+        let mut buildpackage_paths = BTreeMap::new();
+        for node in root_nodes {
+            let Some(packaged_buildpack_dir) = packaged_buildpack_dirs.get(&node.buildpack_id)
+            else {
+                continue;
+            };
+
+            let buildpackage_path = buildpackage_archive_path(packaged_buildpack_dir);
+            write_buildpackage(packaged_buildpack_dir, target_platform, &buildpackage_path)
+                .map_err(Error::CannotWriteBuildpackage)?;
+
+            eprintln!(
+                "Successfully wrote buildpackage archive: {} ({target_platform})",
+                reporter.relative_path(&buildpackage_path)
+            );
+            reporter.buildpackage_written(
+                &node.buildpack_id,
+                &target_platform.to_string(),
+                &buildpackage_path,
+            );
+
+            buildpackage_paths.insert(node.buildpack_id.clone(), buildpackage_path);
+        }
+        buildpackage_paths_per_target.push(buildpackage_paths);
+    }
+
+    Ok(buildpackage_paths_per_target)
+}
+
+/// Determines the target triples from the `[[targets]]` of the given buildpacks, falling back to
+/// the default target triple if none of them declare targets. Also returns the target triples of
+/// each buildpack that declares targets, since buildpacks are only packaged for their own targets.
+fn determine_target_triples(
+    build_order: &[&BuildpackDependencyGraphNode],
+) -> Result<(Vec<String>, BuildpackTargetTriples), Error> {
+    let mut target_triples = Vec::new();
+    let mut buildpack_target_triples_by_id = BuildpackTargetTriples::new();
+
+    for node in build_order {
+        let buildpack_target_triples =
+            buildpack_target_triples(&node.path).map_err(Error::CannotDetermineBuildpackTargets)?;
+
+        for target_triple in &buildpack_target_triples {
+            if !target_triples.contains(target_triple) {
+                target_triples.push(target_triple.clone());
+            }
+        }
+
+        if !buildpack_target_triples.is_empty() {
+            buildpack_target_triples_by_id
+                .insert(node.buildpack_id.clone(), buildpack_target_triples);
+        }
+    }
+
+    if target_triples.is_empty() {
+        target_triples.push(String::from(DEFAULT_TARGET_TRIPLE));
+    }
+
+    Ok((target_triples, buildpack_target_triples_by_id))
+}
+
+fn cargo_build_env(
+    args: &PackageArgs,
+    target_triple: &str,
+) -> Result<Vec<(OsString, OsString)>, Error> {
+    eprintln!("🖥️ Gathering Cargo configuration (for {target_triple})");
+
+    let mut cargo_env = Vec::new();
+
+    if args.no_cross_compile_assistance {
+        return Ok(cargo_env);
+    }
+
+    match cross_compile_backend_assistance(args.backend.into(), target_triple) {
+        CrossCompileAssistance::Configuration {
+            cargo_env: cross_compile_cargo_env,
+        } => {
+            cargo_env.extend(cross_compile_cargo_env);
+            Ok(cargo_env)
+        }
+        CrossCompileAssistance::NoAssistance => {
+            eprintln!(
+                "Couldn't determine automatic cross-compile settings for target triple {target_triple}."
+            );
+            eprintln!("This is not an error, but without proper cross-compile settings in your Cargo manifest and locally installed toolchains, compilation might fail.");
+            eprintln!("To disable this warning, pass --no-cross-compile-assistance.");
+            Ok(cargo_env)
+        }
+        CrossCompileAssistance::HelpText(help_text) => {
+            eprintln!("{help_text}");
+            if args.backend == CrossCompileBackend::Cargo {
+                eprintln!("Alternatively, pass --backend zigbuild or --backend cross to cross-compile without a C cross-compiler installed.");
+            }
+            Err(Error::CannotConfigureCrossCompilation)
+        }
+    }
+}
+
+/// Warns about distributions declared in the `buildpack.toml` of the given buildpack that provide
+/// an older glibc than its packaged binaries need.
+fn eprint_glibc_warnings(
+    node: &BuildpackDependencyGraphNode,
+    target_triple: &str,
+    binary_audit_report: &BinaryAuditReport,
+) -> Result<(), Error> {
+    let Some(required_glibc_version) = binary_audit_report
+        .binaries
+        .values()
+        .filter_map(|binary_audit| binary_audit.required_glibc_version)
+        .max()
+    else {
+        return Ok(());
+    };
+
+    let distros = buildpack_target_distros(&node.path, target_triple)
+        .map_err(Error::CannotDetermineBuildpackTargets)?;
+
+    let glibc_incompatibilities = glibc_incompatibilities(required_glibc_version, &distros);
+    for glibc_incompatibility in &glibc_incompatibilities {
+        eprintln!("⚠️ {glibc_incompatibility} (for {target_triple})");
+    }
+
+    if !glibc_incompatibilities.is_empty() {
+        eprintln!("To run on these distributions, build on a machine with an older glibc or use a musl target instead.");
+    }
+
     Ok(())
 }
 
+const DEFAULT_TARGET_TRIPLE: &str = "x86_64-unknown-linux-musl";
+
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 fn eprint_pack_command_hint(
     packaged_buildpack_dirs: &BTreeMap<BuildpackId, PathBuf>,
-    current_dir: &Path,
+    reporter: &Reporter,
 ) {
-    eprintln!("✨ Packaging successfully finished!");
     eprintln!();
     eprintln!("💡 To test your buildpack locally with pack, run:");
     eprintln!("pack build my-image-name \\");
     for dir in packaged_buildpack_dirs.values() {
-        eprintln!(
-            "  --buildpack {} \\",
-            pathdiff::diff_paths(dir, current_dir)
-                .unwrap_or_else(|| dir.clone())
-                .to_string_lossy()
-        );
+        eprintln!("  --buildpack {} \\", reporter.relative_path(dir));
     }
     eprintln!("  --trust-extra-buildpacks \\");
     eprintln!("  --path /path/to/application");
     eprintln!();
 }
 
-fn eprint_compiled_buildpack_success(current_dir: &Path, target_dir: &Path) {
+fn eprint_compiled_buildpack_success(
+    reporter: &Reporter,
+    target_dir: &Path,
+    outcome: PackageBuildpackOutcome,
+) {
     let size_string = calculate_dir_size(target_dir)
-        .map(|size_in_bytes| {
-            // Precision will only be lost for sizes bigger than 52 bits (~4 Petabytes), and even
-            // then will only result in a less precise figure, so is not an issue.
-            #[allow(clippy::cast_precision_loss)]
-            let size_in_mib = size_in_bytes as f64 / (1024.0 * 1024.0);
-            format!("{size_in_mib:.2}")
-        })
+        .map(format_size_in_mib)
         .unwrap_or(String::from("<unknown>"));
 
-    let relative_output_path =
-        pathdiff::diff_paths(target_dir, current_dir).unwrap_or_else(|| target_dir.to_path_buf());
+    let message = match outcome {
+        PackageBuildpackOutcome::Packaged => "Successfully wrote buildpack directory",
+        PackageBuildpackOutcome::UpToDate => "Buildpack directory is up to date, skipped",
+    };
 
     eprintln!(
-        "Successfully wrote buildpack directory: {} ({size_string} MiB)",
-        relative_output_path.to_string_lossy(),
+        "{message}: {} ({size_string} MiB)",
+        reporter.relative_path(target_dir),
     );
 }
 
+fn eprint_binary_audit_report(binary_audit_report: &BinaryAuditReport) {
+    for (path, binary_audit) in &binary_audit_report.binaries {
+        let linkage = match binary_audit.linkage {
+            Linkage::Static => String::from("statically linked"),
+            Linkage::Dynamic if binary_audit.needed_libraries.is_empty() => {
+                String::from("dynamically linked")
+            }
+            Linkage::Dynamic => format!(
+                "dynamically linked against {}",
+                binary_audit.needed_libraries.join(", ")
+            ),
+        };
+
+        eprintln!(
+            "🔎 {path}: {} MiB, {linkage}, {}",
+            format_size_in_mib(binary_audit.size),
+            if binary_audit.stripped {
+                "stripped"
+            } else {
+                "not stripped"
+            }
+        );
+    }
+}
+
+fn format_size_in_mib(size_in_bytes: u64) -> String {
+    // Precision will only be lost for sizes bigger than 52 bits (~4 Petabytes), and even
+    // then will only result in a less precise figure, so is not an issue.
+    #[allow(clippy::cast_precision_loss)]
+    let size_in_mib = size_in_bytes as f64 / (1024.0 * 1024.0);
+    format!("{size_in_mib:.2}")
+}
+
 /// Recursively calculate the size of a directory and its contents in bytes.
 fn calculate_dir_size(path: impl AsRef<Path>) -> std::io::Result<u64> {
     let mut size_in_bytes = 0;
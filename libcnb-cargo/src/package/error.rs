@@ -1,6 +1,10 @@
 use libcnb_data::buildpack::BuildpackId;
 use libcnb_package::buildpack_dependency_graph::BuildBuildpackDependencyGraphError;
+use libcnb_package::buildpackage::WriteBuildpackageError;
 use libcnb_package::dependency_graph::GetDependenciesError;
+use libcnb_package::multi_target::{
+    AssembleMultiTargetBuildpackDirectoryError, BuildpackTargetTriplesError,
+};
 use libcnb_package::package::PackageBuildpackError;
 use std::path::PathBuf;
 
@@ -10,18 +14,30 @@ pub(crate) enum Error {
     CannotGetCurrentDir(#[source] std::io::Error),
     #[error("Failed to find Cargo workspace root: {0}")]
     CannotFindCargoWorkspaceRoot(#[source] libcnb_package::FindCargoWorkspaceRootError),
+    #[error("Failed to read Cargo metadata: {0}")]
+    CannotReadCargoMetadata(#[source] cargo_metadata::Error),
     #[error("Failed to create package directory {0}: {1}")]
     CannotCreatePackageDirectory(PathBuf, #[source] std::io::Error),
     #[error("Failed to create buildpack dependency graph: {0}")]
     CannotBuildBuildpackDependencyGraph(#[source] BuildBuildpackDependencyGraphError),
     #[error("Failed to get dependencies: {0}")]
     CannotGetDependencies(#[source] GetDependenciesError<BuildpackId>),
-    #[error("Failed to create buildpack package directory {0}: {1}")]
-    CannotCreateBuildpackDestinationDir(PathBuf, #[source] std::io::Error),
     #[error("Failed to package buildpack: {0}")]
     CannotPackageBuildpack(#[source] PackageBuildpackError),
     #[error("Failed to configure Cargo for cross-compilation")]
     CannotConfigureCrossCompilation,
+    #[error("Failed to determine buildpack targets: {0}")]
+    CannotDetermineBuildpackTargets(#[source] BuildpackTargetTriplesError),
+    #[error("Target triple {0} is not supported when packaging for multiple targets")]
+    UnsupportedMultiTargetTriple(String),
+    #[error("Failed to assemble multi-target buildpack directory: {0}")]
+    CannotAssembleMultiTargetBuildpackDirectory(
+        #[source] AssembleMultiTargetBuildpackDirectoryError,
+    ),
+    #[error("Target triple {0} is not supported when packaging buildpackage archives")]
+    UnsupportedBuildpackageTargetTriple(String),
+    #[error("Failed to write buildpackage archive: {0}")]
+    CannotWriteBuildpackage(#[source] WriteBuildpackageError),
     #[error("No buildpacks found!")]
     NoBuildpacksFound,
 }
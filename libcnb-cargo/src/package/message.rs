@@ -0,0 +1,154 @@
+use crate::cli::MessageFormat;
+use libcnb_data::buildpack::BuildpackId;
+use libcnb_package::binary_audit::BinaryAuditReport;
+use libcnb_package::buildpack_dependency_graph::BuildpackDependencyGraphNode;
+use libcnb_package::package::PackageBuildpackOutcome;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+/// Reports packaging progress. Human-readable progress is always written to stderr, with
+/// `--message-format json` machine-readable messages are additionally written to stdout, one JSON
+/// object per line.
+pub(crate) struct Reporter<'a> {
+    pub(crate) current_dir: &'a Path,
+    pub(crate) message_format: MessageFormat,
+    /// Whether to print output paths to stdout in human-readable mode, and the pack command hint.
+    pub(crate) print_outputs: bool,
+}
+
+impl Reporter<'_> {
+    /// The given path relative to the current directory, for human-readable output.
+    pub(crate) fn relative_path(&self, path: &Path) -> String {
+        pathdiff::diff_paths(path, self.current_dir)
+            .unwrap_or_else(|| path.to_path_buf())
+            .to_string_lossy()
+            .to_string()
+    }
+
+    pub(crate) fn dependency_graph_computed(
+        &self,
+        nodes: &[&BuildpackDependencyGraphNode],
+        build_order: &[&BuildpackDependencyGraphNode],
+    ) {
+        self.emit(&json!({
+            "reason": "dependency-graph-computed",
+            "buildpacks": nodes
+                .iter()
+                .map(|node| json!({
+                    "id": node.buildpack_id,
+                    "path": node.path,
+                    "dependencies": node.dependencies,
+                }))
+                .collect::<Vec<_>>(),
+            "build_order": build_order
+                .iter()
+                .map(|node| &node.buildpack_id)
+                .collect::<Vec<_>>(),
+        }));
+    }
+
+    pub(crate) fn buildpack_packaged(
+        &self,
+        buildpack_id: &BuildpackId,
+        target_triple: &str,
+        packaged_buildpack_dir: &Path,
+        outcome: PackageBuildpackOutcome,
+        binary_audit_report: Option<&BinaryAuditReport>,
+    ) {
+        let binaries = binary_audit_report
+            .map(|binary_audit_report| {
+                binary_audit_report
+                    .binaries
+                    .iter()
+                    .map(|(path, binary_audit)| {
+                        let mut binary = serde_json::to_value(binary_audit).unwrap_or_default();
+                        binary["path"] = json!(packaged_buildpack_dir.join(path));
+                        binary
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        self.emit(&json!({
+            "reason": "buildpack-packaged",
+            "buildpack_id": buildpack_id,
+            "target": target_triple,
+            "path": packaged_buildpack_dir,
+            "outcome": match outcome {
+                PackageBuildpackOutcome::Packaged => "packaged",
+                PackageBuildpackOutcome::UpToDate => "up-to-date",
+            },
+            "binaries": binaries,
+        }));
+    }
+
+    pub(crate) fn multi_target_buildpack_assembled(
+        &self,
+        buildpack_id: &BuildpackId,
+        multi_target_buildpack_dir: &Path,
+    ) {
+        self.emit(&json!({
+            "reason": "multi-target-buildpack-assembled",
+            "buildpack_id": buildpack_id,
+            "path": multi_target_buildpack_dir,
+        }));
+    }
+
+    pub(crate) fn buildpackage_written(
+        &self,
+        buildpack_id: &BuildpackId,
+        platform: &str,
+        buildpackage_path: &Path,
+    ) {
+        self.emit(&json!({
+            "reason": "buildpackage-written",
+            "buildpack_id": buildpack_id,
+            "platform": platform,
+            "path": buildpackage_path,
+        }));
+    }
+
+    /// Reports a packaged buildpack directory or buildpackage archive that is a result of
+    /// packaging. In human-readable mode, these paths are the only output on stdout.
+    pub(crate) fn output(&self, buildpack_id: &BuildpackId, path: &PathBuf) {
+        match self.message_format {
+            MessageFormat::Human if self.print_outputs => println!("{}", path.to_string_lossy()),
+            MessageFormat::Human => {}
+            MessageFormat::Json => emit_json_message(&json!({
+                "reason": "output",
+                "buildpack_id": buildpack_id,
+                "path": path,
+            })),
+        }
+    }
+
+    pub(crate) fn finished(&self) {
+        self.emit(&json!({
+            "reason": "package-finished",
+            "success": true,
+        }));
+    }
+
+    fn emit(&self, message: &Value) {
+        if self.message_format == MessageFormat::Json {
+            emit_json_message(message);
+        }
+    }
+}
+
+/// Reports that packaging failed. Only used with `--message-format json`, since the error is
+/// always written to stderr as well.
+pub(crate) fn emit_error_message(error: &impl std::fmt::Display) {
+    emit_json_message(&json!({
+        "reason": "error",
+        "message": error.to_string(),
+    }));
+    emit_json_message(&json!({
+        "reason": "package-finished",
+        "success": false,
+    }));
+}
+
+fn emit_json_message(message: &Value) {
+    println!("{message}");
+}
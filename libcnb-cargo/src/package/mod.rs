@@ -1,4 +1,8 @@
 mod command;
 mod error;
+mod message;
+mod watch;
 
-pub(crate) use command::execute;
+pub(crate) use command::{execute, package_root_buildpacks};
+pub(crate) use error::Error;
+pub(crate) use message::emit_error_message;
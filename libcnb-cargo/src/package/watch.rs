@@ -0,0 +1,223 @@
+use libcnb_data::buildpack::BuildpackId;
+use libcnb_package::buildpack_dependency_graph::BuildpackDependencyGraphNode;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// The modification time and size of each watched file, keyed by path.
+pub(crate) type FileSnapshot = BTreeMap<PathBuf, (SystemTime, u64)>;
+
+/// Takes a snapshot of the files in the directories of the given buildpacks.
+///
+/// Files are found the same way as buildpack assets: files ignored by `.gitignore` or similar files
+/// are skipped, as are the given excluded directories (such as the package directory). Files that
+/// can't be read, for example because they were removed while walking, are skipped as well.
+pub(crate) fn snapshot_buildpack_files(
+    build_order: &[&BuildpackDependencyGraphNode],
+    excluded_dirs: &[PathBuf],
+) -> FileSnapshot {
+    let mut snapshot = FileSnapshot::new();
+
+    for node in build_order {
+        let excluded_dirs = excluded_dirs.to_vec();
+
+        for entry in ignore::WalkBuilder::new(&node.path)
+            .hidden(false)
+            .filter_entry(move |entry| {
+                entry.file_name() != ".git"
+                    && !excluded_dirs
+                        .iter()
+                        .any(|excluded_dir| entry.path().starts_with(excluded_dir))
+            })
+            .build()
+            .filter_map(Result::ok)
+        {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+
+            if let (true, Ok(modified)) = (metadata.is_file(), metadata.modified()) {
+                snapshot.insert(entry.into_path(), (modified, metadata.len()));
+            }
+        }
+    }
+
+    snapshot
+}
+
+/// Determines the buildpacks whose files differ between the given snapshots, in build order.
+///
+/// Buildpack directories can be nested (for example a composite buildpack containing the
+/// buildpacks it references), so a changed file is attributed to the innermost buildpack
+/// directory containing it.
+pub(crate) fn changed_buildpacks(
+    build_order: &[&BuildpackDependencyGraphNode],
+    previous_snapshot: &FileSnapshot,
+    current_snapshot: &FileSnapshot,
+) -> Vec<BuildpackId> {
+    let changed_paths = previous_snapshot
+        .iter()
+        .filter(|(path, file)| current_snapshot.get(*path) != Some(*file))
+        .map(|(path, _)| path)
+        .chain(
+            current_snapshot
+                .keys()
+                .filter(|path| !previous_snapshot.contains_key(*path)),
+        )
+        .collect::<Vec<_>>();
+
+    let owning_buildpack_ids = changed_paths
+        .iter()
+        .filter_map(|path| owning_buildpack(build_order, path))
+        .collect::<Vec<_>>();
+
+    build_order
+        .iter()
+        .map(|node| &node.buildpack_id)
+        .filter(|buildpack_id| owning_buildpack_ids.contains(buildpack_id))
+        .cloned()
+        .collect()
+}
+
+fn owning_buildpack<'a>(
+    build_order: &[&'a BuildpackDependencyGraphNode],
+    path: &Path,
+) -> Option<&'a BuildpackId> {
+    build_order
+        .iter()
+        .filter(|node| path.starts_with(&node.path))
+        .max_by_key(|node| node.path.components().count())
+        .map(|node| &node.buildpack_id)
+}
+
+/// Determines the buildpacks that need to be repackaged after the given buildpacks changed: the
+/// changed buildpacks themselves and all buildpacks that (transitively) depend on them, in build
+/// order.
+pub(crate) fn affected_buildpacks<'a>(
+    build_order: &[&'a BuildpackDependencyGraphNode],
+    changed_buildpack_ids: &[BuildpackId],
+) -> Vec<&'a BuildpackDependencyGraphNode> {
+    let mut affected_buildpacks: Vec<&BuildpackDependencyGraphNode> = Vec::new();
+
+    // Dependencies always come before their dependents in the build order, so a single pass is
+    // enough to find transitive dependents.
+    for node in build_order {
+        if changed_buildpack_ids.contains(&node.buildpack_id)
+            || node.dependencies.iter().any(|dependency| {
+                affected_buildpacks
+                    .iter()
+                    .any(|affected_buildpack| affected_buildpack.buildpack_id == *dependency)
+            })
+        {
+            affected_buildpacks.push(node);
+        }
+    }
+
+    affected_buildpacks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libcnb_data::buildpack_id;
+    use std::time::Duration;
+
+    fn node(
+        buildpack_id: BuildpackId,
+        path: &str,
+        dependencies: Vec<BuildpackId>,
+    ) -> BuildpackDependencyGraphNode {
+        BuildpackDependencyGraphNode {
+            buildpack_id,
+            path: PathBuf::from(path),
+            dependencies,
+        }
+    }
+
+    #[test]
+    fn changed_buildpacks_are_attributed_to_innermost_directory() {
+        let a = node(buildpack_id!("a"), "/workspace/composite/a", Vec::new());
+        let b = node(buildpack_id!("b"), "/workspace/b", Vec::new());
+        let composite = node(
+            buildpack_id!("composite"),
+            "/workspace/composite",
+            vec![buildpack_id!("a"), buildpack_id!("b")],
+        );
+        let build_order = [&a, &b, &composite];
+
+        let previous_snapshot = FileSnapshot::from([
+            (
+                PathBuf::from("/workspace/composite/a/src/main.rs"),
+                (SystemTime::UNIX_EPOCH, 10),
+            ),
+            (
+                PathBuf::from("/workspace/b/src/main.rs"),
+                (SystemTime::UNIX_EPOCH, 10),
+            ),
+        ]);
+
+        let mut current_snapshot = previous_snapshot.clone();
+        current_snapshot.insert(
+            PathBuf::from("/workspace/composite/a/src/main.rs"),
+            (SystemTime::UNIX_EPOCH + Duration::from_secs(1), 10),
+        );
+
+        assert_eq!(
+            changed_buildpacks(&build_order, &previous_snapshot, &current_snapshot),
+            vec![buildpack_id!("a")]
+        );
+
+        current_snapshot.remove(&PathBuf::from("/workspace/b/src/main.rs"));
+        current_snapshot.insert(
+            PathBuf::from("/workspace/composite/package.toml"),
+            (SystemTime::UNIX_EPOCH, 10),
+        );
+
+        assert_eq!(
+            changed_buildpacks(&build_order, &previous_snapshot, &current_snapshot),
+            vec![
+                buildpack_id!("a"),
+                buildpack_id!("b"),
+                buildpack_id!("composite")
+            ]
+        );
+    }
+
+    #[test]
+    fn affected_buildpacks_include_transitive_dependents() {
+        let a = node(buildpack_id!("a"), "/workspace/a", Vec::new());
+        let b = node(buildpack_id!("b"), "/workspace/b", Vec::new());
+        let inner = node(
+            buildpack_id!("inner"),
+            "/workspace/inner",
+            vec![buildpack_id!("a")],
+        );
+        let outer = node(
+            buildpack_id!("outer"),
+            "/workspace/outer",
+            vec![buildpack_id!("inner"), buildpack_id!("b")],
+        );
+        let build_order = [&a, &b, &inner, &outer];
+
+        let buildpack_ids = |nodes: Vec<&BuildpackDependencyGraphNode>| {
+            nodes
+                .iter()
+                .map(|node| node.buildpack_id.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            buildpack_ids(affected_buildpacks(&build_order, &[buildpack_id!("a")])),
+            vec![
+                buildpack_id!("a"),
+                buildpack_id!("inner"),
+                buildpack_id!("outer")
+            ]
+        );
+
+        assert_eq!(
+            buildpack_ids(affected_buildpacks(&build_order, &[buildpack_id!("b")])),
+            vec![buildpack_id!("b"), buildpack_id!("outer")]
+        );
+    }
+}
@@ -0,0 +1,3 @@
+fn main() {
+    println!("amd64 buildpack");
+}
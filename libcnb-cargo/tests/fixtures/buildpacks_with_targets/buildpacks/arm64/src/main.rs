@@ -0,0 +1,3 @@
+fn main() {
+    println!("arm64 buildpack");
+}
@@ -8,7 +8,11 @@ use libcnb_common::toml_file::read_toml_file;
 use libcnb_data::buildpack::{BuildpackDescriptor, BuildpackId};
 use libcnb_data::buildpack_id;
 use libcnb_data::package_descriptor::{PackageDescriptor, PackageDescriptorDependency};
-use libcnb_package::output::create_packaged_buildpack_dir_resolver;
+use libcnb_package::output::{
+    buildpackage_archive_path, create_multi_target_packaged_buildpack_dir_resolver,
+    create_packaged_buildpack_dir_resolver,
+};
+use libcnb_package::reproducible::verify_buildpack_manifest;
 use libcnb_package::CargoProfile;
 use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
@@ -206,7 +210,7 @@ fn package_non_libcnb_buildpack_in_composite_buildpack_project() {
     assert_ne!(output.status.code(), Some(0));
     assert_eq!(
         String::from_utf8_lossy(&output.stderr),
-        "🚚 Preparing package directory...\n🖥\u{fe0f} Gathering Cargo configuration (for x86_64-unknown-linux-musl)\n🏗\u{fe0f} Building buildpack dependency graph...\n🔀 Determining build order...\n❌ No buildpacks found!\n"
+        "🚚 Preparing package directory...\n🏗\u{fe0f} Building buildpack dependency graph...\n🔀 Determining build order...\n❌ No buildpacks found!\n"
     );
 }
 
@@ -224,7 +228,7 @@ fn package_command_error_when_run_in_project_with_no_buildpacks() {
     assert_ne!(output.status.code(), Some(0));
     assert_eq!(
         String::from_utf8_lossy(&output.stderr),
-        "🚚 Preparing package directory...\n🖥\u{fe0f} Gathering Cargo configuration (for x86_64-unknown-linux-musl)\n🏗\u{fe0f} Building buildpack dependency graph...\n🔀 Determining build order...\n❌ No buildpacks found!\n"
+        "🚚 Preparing package directory...\n🏗\u{fe0f} Building buildpack dependency graph...\n🔀 Determining build order...\n❌ No buildpacks found!\n"
     );
 }
 
@@ -247,7 +251,7 @@ fn package_command_respects_ignore_files() {
     assert_ne!(output.status.code(), Some(0));
     assert_eq!(
         String::from_utf8_lossy(&output.stderr),
-        "🚚 Preparing package directory...\n🖥\u{fe0f} Gathering Cargo configuration (for x86_64-unknown-linux-musl)\n🏗\u{fe0f} Building buildpack dependency graph...\n🔀 Determining build order...\n❌ No buildpacks found!\n"
+        "🚚 Preparing package directory...\n🏗\u{fe0f} Building buildpack dependency graph...\n🔀 Determining build order...\n❌ No buildpacks found!\n"
     );
 
     fs::remove_file(ignore_file).unwrap();
@@ -271,7 +275,397 @@ fn package_command_respects_ignore_files() {
     assert_ne!(output.status.code(), Some(0));
     assert_eq!(
         String::from_utf8_lossy(&output.stderr),
-        "🚚 Preparing package directory...\n🖥\u{fe0f} Gathering Cargo configuration (for x86_64-unknown-linux-musl)\n🏗\u{fe0f} Building buildpack dependency graph...\n🔀 Determining build order...\n❌ No buildpacks found!\n"
+        "🚚 Preparing package directory...\n🏗\u{fe0f} Building buildpack dependency graph...\n🔀 Determining build order...\n❌ No buildpacks found!\n"
+    );
+}
+
+#[test]
+#[ignore = "integration test"]
+fn package_buildpack_for_multiple_targets() {
+    let fixture_dir = copy_fixture_to_temp_dir("single_buildpack").unwrap();
+    let buildpack_id = buildpack_id!("single-buildpack");
+
+    let output = Command::new(CARGO_LIBCNB_BINARY_UNDER_TEST)
+        .args([
+            "libcnb",
+            "package",
+            "--release",
+            "--target",
+            X86_64_UNKNOWN_LINUX_MUSL,
+            "--target",
+            AARCH64_UNKNOWN_LINUX_MUSL,
+        ])
+        .current_dir(&fixture_dir)
+        .output()
+        .unwrap();
+
+    let package_dir = fixture_dir.path().join(DEFAULT_PACKAGE_DIR_NAME);
+    let multi_target_buildpack_dir = create_multi_target_packaged_buildpack_dir_resolver(
+        &package_dir,
+        CargoProfile::Release,
+    )(&buildpack_id);
+
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        format!("{}\n", multi_target_buildpack_dir.to_string_lossy())
+    );
+
+    for target_triple in [X86_64_UNKNOWN_LINUX_MUSL, AARCH64_UNKNOWN_LINUX_MUSL] {
+        validate_packaged_buildpack(
+            &create_packaged_buildpack_dir_resolver(
+                &package_dir,
+                CargoProfile::Release,
+                target_triple,
+            )(&buildpack_id),
+            &buildpack_id,
+        );
+    }
+
+    validate_packaged_buildpack(
+        &multi_target_buildpack_dir.join("linux").join("amd64"),
+        &buildpack_id,
+    );
+    validate_packaged_buildpack(
+        &multi_target_buildpack_dir.join("linux").join("arm64"),
+        &buildpack_id,
+    );
+
+    let platforms =
+        read_toml_file::<PackageDescriptor>(multi_target_buildpack_dir.join("package.toml"))
+            .unwrap()
+            .platforms
+            .into_iter()
+            .map(|platform| format!("{}/{} {}", platform.os, platform.arch, platform.uri))
+            .collect::<Vec<_>>();
+
+    assert_eq!(
+        platforms,
+        ["linux/amd64 linux/amd64", "linux/arm64 linux/arm64"]
+    );
+}
+
+#[test]
+#[ignore = "integration test"]
+fn package_buildpacks_for_their_declared_targets() {
+    let fixture_dir = copy_fixture_to_temp_dir("buildpacks_with_targets").unwrap();
+
+    let output = Command::new(CARGO_LIBCNB_BINARY_UNDER_TEST)
+        .args(["libcnb", "package", "--release"])
+        .current_dir(&fixture_dir)
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let package_dir = fixture_dir.path().join(DEFAULT_PACKAGE_DIR_NAME);
+
+    for (buildpack_id, target_triples) in [
+        (
+            buildpack_id!("buildpacks-with-targets/amd64"),
+            vec![X86_64_UNKNOWN_LINUX_MUSL],
+        ),
+        (
+            buildpack_id!("buildpacks-with-targets/any-linux"),
+            vec![X86_64_UNKNOWN_LINUX_MUSL, AARCH64_UNKNOWN_LINUX_MUSL],
+        ),
+        (
+            buildpack_id!("buildpacks-with-targets/arm64"),
+            vec![AARCH64_UNKNOWN_LINUX_MUSL],
+        ),
+    ] {
+        for target_triple in [X86_64_UNKNOWN_LINUX_MUSL, AARCH64_UNKNOWN_LINUX_MUSL] {
+            let packaged_buildpack_dir = create_packaged_buildpack_dir_resolver(
+                &package_dir,
+                CargoProfile::Release,
+                target_triple,
+            )(&buildpack_id);
+
+            if target_triples.contains(&target_triple) {
+                validate_packaged_buildpack(&packaged_buildpack_dir, &buildpack_id);
+            } else {
+                assert!(!packaged_buildpack_dir.exists());
+            }
+        }
+
+        let multi_target_buildpack_dir = create_multi_target_packaged_buildpack_dir_resolver(
+            &package_dir,
+            CargoProfile::Release,
+        )(&buildpack_id);
+
+        let mut platforms =
+            read_toml_file::<PackageDescriptor>(multi_target_buildpack_dir.join("package.toml"))
+                .unwrap()
+                .platforms
+                .into_iter()
+                .map(|platform| format!("{}/{}", platform.os, platform.arch))
+                .collect::<Vec<_>>();
+        platforms.sort();
+
+        assert_eq!(
+            platforms,
+            target_triples
+                .iter()
+                .map(|target_triple| match *target_triple {
+                    X86_64_UNKNOWN_LINUX_MUSL => "linux/amd64",
+                    _ => "linux/arm64",
+                })
+                .collect::<Vec<_>>()
+        );
+    }
+}
+
+#[test]
+#[ignore = "integration test"]
+fn package_buildpack_as_buildpackage_archive() {
+    let fixture_dir = copy_fixture_to_temp_dir("single_buildpack").unwrap();
+    let buildpack_id = buildpack_id!("single-buildpack");
+
+    let output = Command::new(CARGO_LIBCNB_BINARY_UNDER_TEST)
+        .args(["libcnb", "package", "--release", "--format", "oci"])
+        .current_dir(&fixture_dir)
+        .output()
+        .unwrap();
+
+    let packaged_buildpack_dir = create_packaged_buildpack_dir_resolver(
+        &fixture_dir.path().join(DEFAULT_PACKAGE_DIR_NAME),
+        CargoProfile::Release,
+        X86_64_UNKNOWN_LINUX_MUSL,
+    )(&buildpack_id);
+    let buildpackage_path = buildpackage_archive_path(&packaged_buildpack_dir);
+
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        format!("{}\n", buildpackage_path.to_string_lossy())
+    );
+
+    validate_packaged_buildpack(&packaged_buildpack_dir, &buildpack_id);
+    assert!(fs::metadata(&buildpackage_path).unwrap().len() > 0);
+}
+
+#[test]
+#[ignore = "integration test"]
+fn package_buildpack_with_assets() {
+    let fixture_dir = copy_fixture_to_temp_dir("single_buildpack").unwrap();
+    let buildpack_id = buildpack_id!("single-buildpack");
+
+    let cargo_toml_path = fixture_dir.path().join("Cargo.toml");
+    fs::write(
+        &cargo_toml_path,
+        fs::read_to_string(&cargo_toml_path).unwrap()
+            + "\n[package.metadata.libcnb]\ninclude = [\"scripts/*\"]\nexclude = [\"**/*.bak\"]\n",
+    )
+    .unwrap();
+
+    fs::create_dir_all(fixture_dir.path().join("scripts")).unwrap();
+    fs::write(fixture_dir.path().join("scripts/setup.sh"), "#!/bin/sh\n").unwrap();
+    fs::write(fixture_dir.path().join("scripts/setup.sh.bak"), "").unwrap();
+
+    let output = Command::new(CARGO_LIBCNB_BINARY_UNDER_TEST)
+        .args(["libcnb", "package", "--release"])
+        .current_dir(&fixture_dir)
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+
+    let packaged_buildpack_dir = create_packaged_buildpack_dir_resolver(
+        &fixture_dir.path().join(DEFAULT_PACKAGE_DIR_NAME),
+        CargoProfile::Release,
+        X86_64_UNKNOWN_LINUX_MUSL,
+    )(&buildpack_id);
+
+    validate_packaged_buildpack(&packaged_buildpack_dir, &buildpack_id);
+    assert!(packaged_buildpack_dir.join("scripts/setup.sh").is_file());
+    assert!(!packaged_buildpack_dir.join("scripts/setup.sh.bak").exists());
+}
+
+#[test]
+#[ignore = "integration test"]
+fn package_buildpack_incrementally() {
+    let fixture_dir = copy_fixture_to_temp_dir("single_buildpack").unwrap();
+
+    let package = || {
+        Command::new(CARGO_LIBCNB_BINARY_UNDER_TEST)
+            .args(["libcnb", "package"])
+            .current_dir(&fixture_dir)
+            .output()
+            .unwrap()
+    };
+
+    let skipped_message = "Skipped repackaging 1 of 1 buildpacks";
+
+    let output = package();
+    assert_eq!(output.status.code(), Some(0));
+    assert!(!String::from_utf8_lossy(&output.stderr).contains(skipped_message));
+
+    let output = package();
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stderr).contains(skipped_message));
+
+    fs::write(
+        fixture_dir.path().join("buildpack.toml"),
+        fs::read_to_string(fixture_dir.path().join("buildpack.toml"))
+            .unwrap()
+            .replace("version = \"0.0.0\"", "version = \"0.0.1\""),
+    )
+    .unwrap();
+
+    let output = package();
+    assert_eq!(output.status.code(), Some(0));
+    assert!(!String::from_utf8_lossy(&output.stderr).contains(skipped_message));
+}
+
+#[test]
+#[ignore = "integration test"]
+fn package_buildpack_with_json_messages() {
+    let fixture_dir = copy_fixture_to_temp_dir("single_buildpack").unwrap();
+
+    let output = Command::new(CARGO_LIBCNB_BINARY_UNDER_TEST)
+        .args(["libcnb", "package", "--release", "--message-format", "json"])
+        .current_dir(&fixture_dir)
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+
+    let messages = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    let packaged_buildpack_dir = create_packaged_buildpack_dir_resolver(
+        &fixture_dir.path().join(DEFAULT_PACKAGE_DIR_NAME),
+        CargoProfile::Release,
+        X86_64_UNKNOWN_LINUX_MUSL,
+    )(&buildpack_id!("single-buildpack"));
+
+    assert!(messages.iter().any(|message| message["reason"] == "output"
+        && message["path"] == packaged_buildpack_dir.to_string_lossy().as_ref()));
+
+    assert_eq!(
+        messages.last().unwrap(),
+        &serde_json::json!({ "reason": "package-finished", "success": true })
+    );
+}
+
+#[test]
+#[ignore = "integration test"]
+fn check_buildpacks_in_monorepo_buildpack_project() {
+    let fixture_dir = copy_fixture_to_temp_dir("multiple_buildpacks").unwrap();
+
+    let output = Command::new(CARGO_LIBCNB_BINARY_UNDER_TEST)
+        .args(["libcnb", "check"])
+        .current_dir(&fixture_dir)
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "🔍 Checking buildpacks...\n✨ No problems found in 4 buildpack(s)\n"
+    );
+}
+
+#[test]
+#[ignore = "integration test"]
+fn check_command_reports_problems() {
+    let fixture_dir = copy_fixture_to_temp_dir("multiple_buildpacks").unwrap();
+
+    fs::write(
+        fixture_dir
+            .path()
+            .join("composite-buildpacks")
+            .join("composite-one")
+            .join("package.toml"),
+        "[buildpack]\nuri = \".\"\n\n[[dependencies]]\nuri = \"libcnb:multiple-buildpacks/missing\"\n",
+    )
+    .unwrap();
+
+    let output = Command::new(CARGO_LIBCNB_BINARY_UNDER_TEST)
+        .args(["libcnb", "check"])
+        .current_dir(&fixture_dir)
+        .output()
+        .unwrap();
+
+    assert_ne!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "🔍 Checking buildpacks...\ncomposite-buildpacks/composite-one/package.toml: Dependency libcnb:multiple-buildpacks/missing doesn't refer to a buildpack in this workspace\n❌ Found 1 problem(s)\n"
+    );
+}
+
+#[test]
+#[ignore = "integration test"]
+fn graph_command_prints_buildpack_dependency_tree() {
+    let fixture_dir = copy_fixture_to_temp_dir("multiple_buildpacks").unwrap();
+
+    let output = Command::new(CARGO_LIBCNB_BINARY_UNDER_TEST)
+        .args(["libcnb", "graph"])
+        .current_dir(&fixture_dir)
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "\
+multiple-buildpacks/composite-one (composite, composite-buildpacks/composite-one)
+├── docker://docker.io/heroku/example:1.2.3 (docker, 1.2.3)
+├── multiple-buildpacks/not-libcnb (other, buildpacks/not_libcnb)
+├── multiple-buildpacks/one (libcnb, buildpacks/one)
+└── multiple-buildpacks/two (libcnb, buildpacks/two)
+"
+    );
+}
+
+#[test]
+#[ignore = "integration test"]
+fn new_command_creates_buildpack_crate_that_compiles() {
+    let temp_dir = env::temp_dir().canonicalize().and_then(tempdir_in).unwrap();
+
+    let output = Command::new(CARGO_LIBCNB_BINARY_UNDER_TEST)
+        .args(["libcnb", "new", "example/new-buildpack"])
+        .current_dir(&temp_dir)
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim_end(),
+        temp_dir
+            .path()
+            .join("example-new-buildpack")
+            .to_string_lossy()
+    );
+
+    // The generated crate depends on the released libcnb crates, those in this workspace are used
+    // instead so that changes to them and the templates are checked together.
+    let workspace_root_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..");
+    fs::write(
+        temp_dir.path().join("Cargo.toml"),
+        format!(
+            "[workspace]\nmembers = [\"example-new-buildpack\"]\nresolver = \"2\"\n\n[patch.crates-io]\nlibcnb = {{ path = {:?} }}\nlibcnb-test = {{ path = {:?} }}\n",
+            workspace_root_dir.join("libcnb"),
+            workspace_root_dir.join("libcnb-test"),
+        ),
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO"))
+        .args(["check", "--all-targets"])
+        .current_dir(&temp_dir)
+        .output()
+        .unwrap();
+
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
     );
 }
 
@@ -282,6 +676,10 @@ fn validate_packaged_buildpack(packaged_buildpack_dir: &Path, buildpack_id: &Bui
     assert!(packaged_buildpack_dir.join("package.toml").exists());
     assert!(packaged_buildpack_dir.join("bin").join("build").exists());
     assert!(packaged_buildpack_dir.join("bin").join("detect").exists());
+    assert_eq!(
+        verify_buildpack_manifest(packaged_buildpack_dir).unwrap(),
+        Vec::<String>::new()
+    );
 
     assert_eq!(
         &read_toml_file::<BuildpackDescriptor>(packaged_buildpack_dir.join("buildpack.toml"))
@@ -357,5 +755,6 @@ fn copy_dir_recursively(source: &Path, destination: &Path) -> std::io::Result<()
 }
 
 const X86_64_UNKNOWN_LINUX_MUSL: &str = "x86_64-unknown-linux-musl";
+const AARCH64_UNKNOWN_LINUX_MUSL: &str = "aarch64-unknown-linux-musl";
 const CARGO_LIBCNB_BINARY_UNDER_TEST: &str = env!("CARGO_BIN_EXE_cargo-libcnb");
 const DEFAULT_PACKAGE_DIR_NAME: &str = "packaged";
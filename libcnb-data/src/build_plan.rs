@@ -77,14 +77,14 @@ impl BuildPlanBuilder {
 #[derive(Serialize, Debug)]
 pub struct Or {
     #[serde(skip_serializing_if = "Vec::is_empty")]
-    provides: Vec<Provide>,
+    pub provides: Vec<Provide>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
-    requires: Vec<Require>,
+    pub requires: Vec<Require>,
 }
 
 #[derive(Serialize, Debug)]
 pub struct Provide {
-    name: String,
+    pub name: String,
 }
 
 impl Provide {
@@ -1,11 +1,11 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize, Serializer};
 use std::fmt;
 use std::fmt::{Display, Formatter};
 
 /// The Buildpack API version.
 ///
 /// This MUST be in form `<major>.<minor>` or `<major>`, where `<major>` is equivalent to `<major>.0`.
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Deserialize, Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[serde(try_from = "String")]
 pub struct BuildpackApi {
     pub major: u64,
@@ -37,6 +37,16 @@ impl Display for BuildpackApi {
     }
 }
 
+// Serialized as a string, the same format that is accepted when deserializing.
+impl Serialize for BuildpackApi {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum BuildpackApiError {
     #[error("Invalid Buildpack API version: `{0}`")]
@@ -45,7 +55,7 @@ pub enum BuildpackApiError {
 
 #[cfg(test)]
 mod tests {
-    use serde_test::{assert_de_tokens, assert_de_tokens_error, Token};
+    use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_ser_tokens, Token};
 
     use super::*;
 
@@ -104,6 +114,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn serialize_api_versions() {
+        assert_ser_tokens(
+            &BuildpackApi {
+                major: 0,
+                minor: 10,
+            },
+            &[Token::Str("0.10")],
+        );
+        assert_ser_tokens(&BuildpackApi { major: 2, minor: 0 }, &[Token::Str("2.0")]);
+    }
+
+    #[test]
+    fn buildpack_api_ordering() {
+        assert!(
+            BuildpackApi { major: 0, minor: 9 }
+                < BuildpackApi {
+                    major: 0,
+                    minor: 10
+                }
+        );
+        assert!(
+            BuildpackApi {
+                major: 0,
+                minor: 10
+            } < BuildpackApi { major: 1, minor: 0 }
+        );
+        assert!(
+            BuildpackApi { major: 1, minor: 2 }
+                > BuildpackApi {
+                    major: 0,
+                    minor: 20
+                }
+        );
+    }
+
     #[test]
     fn buildpack_api_display() {
         assert_eq!(BuildpackApi { major: 1, minor: 0 }.to_string(), "1.0");
@@ -3,16 +3,18 @@ mod id;
 mod stack;
 mod target;
 mod version;
+mod version_req;
 
 use crate::generic::GenericMetadata;
 use crate::sbom::SbomFormat;
 pub use api::*;
 pub use id::*;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize, Serializer};
 pub use stack::*;
 use std::collections::HashSet;
 pub use target::*;
 pub use version::*;
+pub use version_req::*;
 
 /// Data structures for the Buildpack descriptor (buildpack.toml).
 ///
@@ -55,7 +57,7 @@ pub use version::*;
 ///     }
 /// };
 /// ```
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(untagged)]
 pub enum BuildpackDescriptor<BM = GenericMetadata> {
     Component(ComponentBuildpackDescriptor<BM>),
@@ -116,7 +118,7 @@ impl<BM> BuildpackDescriptor<BM> {
 ///     }]
 /// );
 /// ```
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct ComponentBuildpackDescriptor<BM = GenericMetadata> {
     pub api: BuildpackApi,
@@ -172,7 +174,7 @@ pub struct ComponentBuildpackDescriptor<BM = GenericMetadata> {
 ///     toml::from_str::<CompositeBuildpackDescriptor>(toml_str).unwrap();
 /// assert_eq!(buildpack_descriptor.buildpack.id, buildpack_id!("foo/bar"));
 /// ```
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct CompositeBuildpackDescriptor<BM = GenericMetadata> {
     pub api: BuildpackApi,
@@ -185,14 +187,18 @@ pub struct CompositeBuildpackDescriptor<BM = GenericMetadata> {
     // We believe this to be a spec error and libcnb.rs does intentionally not support this.
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct Buildpack {
     pub id: BuildpackId,
     pub name: Option<String>,
     pub version: BuildpackVersion,
     pub homepage: Option<String>,
-    #[serde(default, rename = "clear-env")]
+    #[serde(
+        default,
+        rename = "clear-env",
+        skip_serializing_if = "std::ops::Not::not"
+    )]
     pub clear_env: bool,
     pub description: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
@@ -202,33 +208,331 @@ pub struct Buildpack {
     #[serde(
         default,
         rename = "sbom-formats",
-        skip_serializing_if = "HashSet::is_empty"
+        skip_serializing_if = "HashSet::is_empty",
+        serialize_with = "serialize_sbom_formats"
     )]
     pub sbom_formats: HashSet<SbomFormat>,
 }
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+// The iteration order of a `HashSet` is not stable, so the formats are sorted to ensure the
+// serialized buildpack descriptor does not change between runs.
+fn serialize_sbom_formats<S>(
+    sbom_formats: &HashSet<SbomFormat>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut sbom_formats = sbom_formats.iter().collect::<Vec<_>>();
+    sbom_formats.sort();
+    sbom_formats.serialize(serializer)
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct License {
     pub r#type: Option<String>,
     pub uri: Option<String>,
 }
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct Order {
     pub group: Vec<Group>,
 }
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct Group {
     pub id: BuildpackId,
     pub version: BuildpackVersion,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub optional: bool,
 }
 
+/// A non-consuming builder for [`Buildpack`] values.
+///
+/// # Examples
+/// ```
+/// use libcnb_data::buildpack::{BuildpackBuilder, BuildpackVersion, License};
+/// use libcnb_data::buildpack_id;
+///
+/// let buildpack = BuildpackBuilder::new(buildpack_id!("foo/bar"), BuildpackVersion::new(1, 0, 0))
+///     .name("Bar Buildpack")
+///     .keywords(["foo", "bar"])
+///     .license(License {
+///         r#type: Some(String::from("BSD-3-Clause")),
+///         uri: None,
+///     })
+///     .build();
+///
+/// assert_eq!(buildpack.name, Some(String::from("Bar Buildpack")));
+/// ```
+pub struct BuildpackBuilder {
+    buildpack: Buildpack,
+}
+
+impl BuildpackBuilder {
+    #[must_use]
+    pub fn new(id: BuildpackId, version: BuildpackVersion) -> Self {
+        Self {
+            buildpack: Buildpack {
+                id,
+                name: None,
+                version,
+                homepage: None,
+                clear_env: false,
+                description: None,
+                keywords: Vec::new(),
+                licenses: Vec::new(),
+                sbom_formats: HashSet::new(),
+            },
+        }
+    }
+
+    /// Sets the human-readable name of the buildpack.
+    pub fn name(&mut self, name: impl Into<String>) -> &mut Self {
+        self.buildpack.name = Some(name.into());
+        self
+    }
+
+    /// Sets the version of the buildpack.
+    pub fn version(&mut self, version: BuildpackVersion) -> &mut Self {
+        self.buildpack.version = version;
+        self
+    }
+
+    /// Sets the homepage URL of the buildpack.
+    pub fn homepage(&mut self, homepage: impl Into<String>) -> &mut Self {
+        self.buildpack.homepage = Some(homepage.into());
+        self
+    }
+
+    /// Sets whether the buildpack wants the user-provided environment variables to be cleared.
+    pub fn clear_env(&mut self, value: bool) -> &mut Self {
+        self.buildpack.clear_env = value;
+        self
+    }
+
+    /// Sets the description of the buildpack.
+    pub fn description(&mut self, description: impl Into<String>) -> &mut Self {
+        self.buildpack.description = Some(description.into());
+        self
+    }
+
+    /// Adds a keyword to the buildpack.
+    pub fn keyword(&mut self, keyword: impl Into<String>) -> &mut Self {
+        self.buildpack.keywords.push(keyword.into());
+        self
+    }
+
+    /// Adds multiple keywords to the buildpack.
+    pub fn keywords(&mut self, keywords: impl IntoIterator<Item = impl Into<String>>) -> &mut Self {
+        for keyword in keywords {
+            self.keyword(keyword);
+        }
+
+        self
+    }
+
+    /// Adds a license to the buildpack.
+    pub fn license(&mut self, license: License) -> &mut Self {
+        self.buildpack.licenses.push(license);
+        self
+    }
+
+    /// Adds multiple licenses to the buildpack.
+    pub fn licenses(&mut self, licenses: impl IntoIterator<Item = License>) -> &mut Self {
+        for license in licenses {
+            self.license(license);
+        }
+
+        self
+    }
+
+    /// Adds an SBOM format the buildpack can output.
+    pub fn sbom_format(&mut self, sbom_format: SbomFormat) -> &mut Self {
+        self.buildpack.sbom_formats.insert(sbom_format);
+        self
+    }
+
+    /// Adds multiple SBOM formats the buildpack can output.
+    pub fn sbom_formats(
+        &mut self,
+        sbom_formats: impl IntoIterator<Item = SbomFormat>,
+    ) -> &mut Self {
+        for sbom_format in sbom_formats {
+            self.sbom_format(sbom_format);
+        }
+
+        self
+    }
+
+    /// Builds the `Buildpack` based on the configuration of this builder.
+    #[must_use]
+    pub fn build(&self) -> Buildpack {
+        self.buildpack.clone()
+    }
+}
+
+/// A non-consuming builder for [`ComponentBuildpackDescriptor`] values.
+///
+/// # Examples
+/// ```
+/// use libcnb_data::buildpack::{
+///     BuildpackApi, BuildpackBuilder, BuildpackTarget, BuildpackVersion,
+///     ComponentBuildpackDescriptor, ComponentBuildpackDescriptorBuilder,
+/// };
+/// use libcnb_data::buildpack_id;
+///
+/// let buildpack_descriptor: ComponentBuildpackDescriptor = ComponentBuildpackDescriptorBuilder::new(
+///     BuildpackApi {
+///         major: 0,
+///         minor: 10,
+///     },
+///     BuildpackBuilder::new(buildpack_id!("foo/bar"), BuildpackVersion::new(1, 0, 0)).build(),
+/// )
+/// .target(BuildpackTarget {
+///     os: Some(String::from("linux")),
+///     arch: Some(String::from("amd64")),
+///     variant: None,
+///     distros: Vec::new(),
+/// })
+/// .build();
+///
+/// assert_eq!(
+///     toml::to_string(&buildpack_descriptor).unwrap(),
+///     r#"api = "0.10"
+///
+/// [buildpack]
+/// id = "foo/bar"
+/// version = "1.0.0"
+///
+/// [[targets]]
+/// os = "linux"
+/// arch = "amd64"
+/// "#
+/// );
+/// ```
+pub struct ComponentBuildpackDescriptorBuilder<BM = GenericMetadata> {
+    descriptor: ComponentBuildpackDescriptor<BM>,
+}
+
+impl<BM: Default + Clone> ComponentBuildpackDescriptorBuilder<BM> {
+    #[must_use]
+    pub fn new(api: BuildpackApi, buildpack: Buildpack) -> Self {
+        Self {
+            descriptor: ComponentBuildpackDescriptor {
+                api,
+                buildpack,
+                stacks: Vec::new(),
+                targets: Vec::new(),
+                metadata: BM::default(),
+            },
+        }
+    }
+
+    /// Adds a target to the buildpack descriptor.
+    pub fn target(&mut self, target: BuildpackTarget) -> &mut Self {
+        self.descriptor.targets.push(target);
+        self
+    }
+
+    /// Adds multiple targets to the buildpack descriptor.
+    pub fn targets(&mut self, targets: impl IntoIterator<Item = BuildpackTarget>) -> &mut Self {
+        for target in targets {
+            self.target(target);
+        }
+
+        self
+    }
+
+    /// Sets the buildpack specific metadata of the buildpack descriptor.
+    pub fn metadata(&mut self, metadata: BM) -> &mut Self {
+        self.descriptor.metadata = metadata;
+        self
+    }
+
+    /// Builds the `ComponentBuildpackDescriptor` based on the configuration of this builder.
+    #[must_use]
+    pub fn build(&self) -> ComponentBuildpackDescriptor<BM> {
+        self.descriptor.clone()
+    }
+}
+
+/// A non-consuming builder for [`CompositeBuildpackDescriptor`] values.
+///
+/// # Examples
+/// ```
+/// use libcnb_data::buildpack::{
+///     BuildpackApi, BuildpackBuilder, BuildpackVersion, CompositeBuildpackDescriptor,
+///     CompositeBuildpackDescriptorBuilder, Group, Order,
+/// };
+/// use libcnb_data::buildpack_id;
+///
+/// let buildpack_descriptor: CompositeBuildpackDescriptor = CompositeBuildpackDescriptorBuilder::new(
+///     BuildpackApi {
+///         major: 0,
+///         minor: 10,
+///     },
+///     BuildpackBuilder::new(buildpack_id!("foo/bar"), BuildpackVersion::new(1, 0, 0)).build(),
+/// )
+/// .order(Order {
+///     group: vec![Group {
+///         id: buildpack_id!("foo/baz"),
+///         version: BuildpackVersion::new(2, 0, 0),
+///         optional: false,
+///     }],
+/// })
+/// .build();
+///
+/// assert_eq!(buildpack_descriptor.order.len(), 1);
+/// ```
+pub struct CompositeBuildpackDescriptorBuilder<BM = GenericMetadata> {
+    descriptor: CompositeBuildpackDescriptor<BM>,
+}
+
+impl<BM: Default + Clone> CompositeBuildpackDescriptorBuilder<BM> {
+    #[must_use]
+    pub fn new(api: BuildpackApi, buildpack: Buildpack) -> Self {
+        Self {
+            descriptor: CompositeBuildpackDescriptor {
+                api,
+                buildpack,
+                order: Vec::new(),
+                metadata: BM::default(),
+            },
+        }
+    }
+
+    /// Adds an order to the buildpack descriptor.
+    pub fn order(&mut self, order: Order) -> &mut Self {
+        self.descriptor.order.push(order);
+        self
+    }
+
+    /// Adds multiple orders to the buildpack descriptor.
+    pub fn orders(&mut self, orders: impl IntoIterator<Item = Order>) -> &mut Self {
+        for order in orders {
+            self.order(order);
+        }
+
+        self
+    }
+
+    /// Sets the buildpack specific metadata of the buildpack descriptor.
+    pub fn metadata(&mut self, metadata: BM) -> &mut Self {
+        self.descriptor.metadata = metadata;
+        self
+    }
+
+    /// Builds the `CompositeBuildpackDescriptor` based on the configuration of this builder.
+    #[must_use]
+    pub fn build(&self) -> CompositeBuildpackDescriptor<BM> {
+        self.descriptor.clone()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -668,6 +972,132 @@ version = "0.0.1"
         ));
     }
 
+    #[test]
+    fn serialize_component_buildpack() {
+        let toml_str = r#"api = "0.10"
+
+[buildpack]
+id = "foo/bar"
+name = "Bar Buildpack"
+version = "0.0.1"
+homepage = "https://example.tld"
+clear-env = true
+description = "A buildpack for Foo Bar"
+keywords = ["foo", "bar"]
+sbom-formats = ["application/vnd.cyclonedx+json", "application/spdx+json", "application/vnd.syft+json"]
+
+[[buildpack.licenses]]
+type = "BSD-3-Clause"
+
+[[buildpack.licenses]]
+uri = "https://example.tld/my-license"
+
+[[targets]]
+os = "linux"
+arch = "arm"
+variant = "v8"
+
+[[targets.distros]]
+name = "ubuntu"
+version = "24.04"
+
+[metadata]
+checksum = "abc123"
+"#;
+
+        let buildpack_descriptor =
+            toml::from_str::<ComponentBuildpackDescriptor>(toml_str).unwrap();
+
+        assert_eq!(toml::to_string(&buildpack_descriptor).unwrap(), toml_str);
+    }
+
+    #[test]
+    fn serialize_built_composite_buildpack() {
+        let buildpack_descriptor = CompositeBuildpackDescriptorBuilder::<GenericMetadata>::new(
+            BuildpackApi {
+                major: 0,
+                minor: 10,
+            },
+            BuildpackBuilder::new("foo/bar".parse().unwrap(), BuildpackVersion::new(0, 0, 1))
+                .sbom_formats([SbomFormat::SyftJson, SbomFormat::CycloneDxJson])
+                .build(),
+        )
+        .order(Order {
+            group: vec![
+                Group {
+                    id: "foo/baz".parse().unwrap(),
+                    version: BuildpackVersion::new(0, 1, 0),
+                    optional: false,
+                },
+                Group {
+                    id: "foo/qux".parse().unwrap(),
+                    version: BuildpackVersion::new(1, 0, 0),
+                    optional: true,
+                },
+            ],
+        })
+        .build();
+
+        assert_eq!(
+            toml::to_string(&BuildpackDescriptor::Composite(buildpack_descriptor)).unwrap(),
+            r#"api = "0.10"
+
+[buildpack]
+id = "foo/bar"
+version = "0.0.1"
+sbom-formats = ["application/vnd.cyclonedx+json", "application/vnd.syft+json"]
+
+[[order]]
+
+[[order.group]]
+id = "foo/baz"
+version = "0.1.0"
+
+[[order.group]]
+id = "foo/qux"
+version = "1.0.0"
+optional = true
+"#
+        );
+    }
+
+    #[test]
+    fn rewrite_buildpack_descriptor() {
+        let toml_str = r#"
+api = "0.10"
+
+[buildpack]
+id = "foo/bar"
+version = "0.0.1"
+        "#;
+
+        let mut buildpack_descriptor = toml::from_str::<BuildpackDescriptor>(toml_str).unwrap();
+
+        if let BuildpackDescriptor::Component(descriptor) = &mut buildpack_descriptor {
+            descriptor.buildpack.version = BuildpackVersion::new(0, 1, 0);
+            descriptor.targets.push(BuildpackTarget {
+                os: Some(String::from("linux")),
+                arch: Some(String::from("arm64")),
+                variant: None,
+                distros: Vec::new(),
+            });
+        }
+
+        assert_eq!(
+            toml::to_string(&buildpack_descriptor).unwrap(),
+            r#"api = "0.10"
+
+[buildpack]
+id = "foo/bar"
+version = "0.1.0"
+
+[[targets]]
+os = "linux"
+arch = "arm64"
+"#
+        );
+    }
+
     #[test]
     fn reject_buildpack_with_both_targets_and_order() {
         let toml_str = r#"
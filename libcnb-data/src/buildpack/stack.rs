@@ -1,9 +1,9 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 // Stacks are deprecated in Buildpack API 0.10, and libcnb.rs effectively
 // ignores them. However, they are still supported by the Buildpack API, so
 // libcnb should continue to allow them to exist in buildpack.toml.
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct Stack {
     pub id: String,
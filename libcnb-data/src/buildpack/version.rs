@@ -1,4 +1,4 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize, Serializer};
 use std::fmt;
 use std::fmt::{Display, Formatter};
 
@@ -6,7 +6,9 @@ use std::fmt::{Display, Formatter};
 ///
 /// This MUST be in the form `<X>.<Y>.<Z>` where `X`, `Y`, and `Z` are non-negative integers
 /// and must not contain leading zeros.
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+///
+/// Versions are ordered by their major, minor and patch parts, in that order of precedence.
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[serde(try_from = "String")]
 pub struct BuildpackVersion {
     pub major: u64,
@@ -23,6 +25,30 @@ impl BuildpackVersion {
             patch,
         }
     }
+
+    /// Returns the next major version, with minor and patch parts reset to zero, or `None` if the
+    /// major part would overflow.
+    #[must_use]
+    pub fn bump_major(&self) -> Option<Self> {
+        Some(Self::new(self.major.checked_add(1)?, 0, 0))
+    }
+
+    /// Returns the next minor version, with the patch part reset to zero, or `None` if the minor
+    /// part would overflow.
+    #[must_use]
+    pub fn bump_minor(&self) -> Option<Self> {
+        Some(Self::new(self.major, self.minor.checked_add(1)?, 0))
+    }
+
+    /// Returns the next patch version, or `None` if the patch part would overflow.
+    #[must_use]
+    pub fn bump_patch(&self) -> Option<Self> {
+        Some(Self::new(
+            self.major,
+            self.minor,
+            self.patch.checked_add(1)?,
+        ))
+    }
 }
 
 impl TryFrom<String> for BuildpackVersion {
@@ -57,6 +83,16 @@ impl Display for BuildpackVersion {
     }
 }
 
+// Serialized as a string, the same format that is accepted when deserializing.
+impl Serialize for BuildpackVersion {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum BuildpackVersionError {
     #[error("Invalid buildpack version: `{0}`")]
@@ -65,7 +101,7 @@ pub enum BuildpackVersionError {
 
 #[cfg(test)]
 mod tests {
-    use serde_test::{assert_de_tokens, assert_de_tokens_error, Token};
+    use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_ser_tokens, Token};
 
     use super::*;
 
@@ -182,6 +218,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn serialize_versions() {
+        assert_ser_tokens(&BuildpackVersion::new(1, 3, 4), &[Token::Str("1.3.4")]);
+        assert_ser_tokens(&BuildpackVersion::new(0, 0, 0), &[Token::Str("0.0.0")]);
+    }
+
+    #[test]
+    fn buildpack_version_ordering() {
+        assert!(BuildpackVersion::new(1, 2, 3) < BuildpackVersion::new(1, 2, 4));
+        assert!(BuildpackVersion::new(1, 2, 9) < BuildpackVersion::new(1, 10, 0));
+        assert!(BuildpackVersion::new(1, 99, 99) < BuildpackVersion::new(2, 0, 0));
+        assert_eq!(
+            [
+                BuildpackVersion::new(0, 10, 0),
+                BuildpackVersion::new(0, 9, 1),
+                BuildpackVersion::new(0, 9, 0),
+            ]
+            .iter()
+            .max(),
+            Some(&BuildpackVersion::new(0, 10, 0))
+        );
+    }
+
+    #[test]
+    fn buildpack_version_bump() {
+        let version = BuildpackVersion::new(1, 2, 3);
+
+        assert_eq!(version.bump_major(), Some(BuildpackVersion::new(2, 0, 0)));
+        assert_eq!(version.bump_minor(), Some(BuildpackVersion::new(1, 3, 0)));
+        assert_eq!(version.bump_patch(), Some(BuildpackVersion::new(1, 2, 4)));
+
+        let version = BuildpackVersion::new(u64::MAX, u64::MAX, u64::MAX);
+
+        assert_eq!(version.bump_major(), None);
+        assert_eq!(version.bump_minor(), None);
+        assert_eq!(version.bump_patch(), None);
+    }
+
     #[test]
     fn buildpack_version_display() {
         assert_eq!(
@@ -0,0 +1,407 @@
+use crate::buildpack::BuildpackVersion;
+use serde::{Deserialize, Serialize, Serializer};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/// A requirement on a [`BuildpackVersion`], such as `^1.2` or `>=1.0, <2`.
+///
+/// A requirement consists of one or more comma separated comparators, all of which must match for
+/// a version to satisfy the requirement. Each comparator is made up of an optional operator and a
+/// (possibly partial) version:
+///
+/// | Operator    | Example  | Matches             |
+/// |-------------|----------|---------------------|
+/// | `=` or none | `=1.2`   | `>=1.2.0, <1.3.0`   |
+/// | `>`         | `>1.2`   | `>=1.3.0`           |
+/// | `>=`        | `>=1.2`  | `>=1.2.0`           |
+/// | `<`         | `<1.2`   | `<1.2.0`            |
+/// | `<=`        | `<=1.2`  | `<1.3.0`            |
+/// | `~`         | `~1.2.3` | `>=1.2.3, <1.3.0`   |
+/// | `^`         | `^1.2.3` | `>=1.2.3, <2.0.0`   |
+///
+/// Caret requirements follow the usual semver convention that a zero major (or zero major and
+/// minor) version is treated as incompatible with any other version, i.e. `^0.2.3` matches
+/// `>=0.2.3, <0.3.0`. The requirement `*` matches any version.
+///
+/// Since an order group entry in `buildpack.toml` refers to an exact version, a
+/// [`BuildpackVersion`] can be converted into an equivalent requirement with [`From`].
+///
+/// # Example
+/// ```
+/// use libcnb_data::buildpack::{BuildpackVersion, BuildpackVersionReq};
+///
+/// let requirement: BuildpackVersionReq = ">=1.0, <2".parse().unwrap();
+///
+/// assert!(requirement.matches(&BuildpackVersion::new(1, 4, 2)));
+/// assert!(!requirement.matches(&BuildpackVersion::new(2, 0, 0)));
+/// ```
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash)]
+#[serde(try_from = "String")]
+pub struct BuildpackVersionReq {
+    comparators: Vec<Comparator>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+struct Comparator {
+    op: Op,
+    major: u64,
+    minor: Option<u64>,
+    patch: Option<u64>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+enum Op {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Tilde,
+    Caret,
+}
+
+impl BuildpackVersionReq {
+    /// A requirement that matches any version.
+    pub const STAR: Self = Self {
+        comparators: Vec::new(),
+    };
+
+    /// Returns whether the given version satisfies all comparators of this requirement.
+    #[must_use]
+    pub fn matches(&self, version: &BuildpackVersion) -> bool {
+        self.comparators
+            .iter()
+            .all(|comparator| comparator.matches(version))
+    }
+}
+
+impl Comparator {
+    fn matches(&self, version: &BuildpackVersion) -> bool {
+        match self.op {
+            Op::Exact => self.matches_exact(version),
+            Op::Greater => self.matches_greater(version),
+            Op::GreaterEq => self.matches_exact(version) || self.matches_greater(version),
+            Op::Less => self.matches_less(version),
+            Op::LessEq => self.matches_exact(version) || self.matches_less(version),
+            Op::Tilde => {
+                version.major == self.major
+                    && self.minor.map_or(true, |minor| version.minor == minor)
+                    && self.patch.map_or(true, |patch| version.patch >= patch)
+            }
+            Op::Caret => self.matches_caret(version),
+        }
+    }
+
+    fn matches_exact(&self, version: &BuildpackVersion) -> bool {
+        version.major == self.major
+            && self.minor.map_or(true, |minor| version.minor == minor)
+            && self.patch.map_or(true, |patch| version.patch == patch)
+    }
+
+    fn matches_greater(&self, version: &BuildpackVersion) -> bool {
+        if version.major != self.major {
+            return version.major > self.major;
+        }
+
+        match self.minor {
+            None => false,
+            Some(minor) if version.minor != minor => version.minor > minor,
+            Some(_) => self.patch.is_some_and(|patch| version.patch > patch),
+        }
+    }
+
+    fn matches_less(&self, version: &BuildpackVersion) -> bool {
+        if version.major != self.major {
+            return version.major < self.major;
+        }
+
+        match self.minor {
+            None => false,
+            Some(minor) if version.minor != minor => version.minor < minor,
+            Some(_) => self.patch.is_some_and(|patch| version.patch < patch),
+        }
+    }
+
+    fn matches_caret(&self, version: &BuildpackVersion) -> bool {
+        if version.major != self.major {
+            return false;
+        }
+
+        let Some(minor) = self.minor else {
+            return true;
+        };
+
+        let Some(patch) = self.patch else {
+            return if self.major > 0 {
+                version.minor >= minor
+            } else {
+                version.minor == minor
+            };
+        };
+
+        if self.major > 0 {
+            (version.minor, version.patch) >= (minor, patch)
+        } else if minor > 0 {
+            version.minor == minor && version.patch >= patch
+        } else {
+            version.minor == minor && version.patch == patch
+        }
+    }
+}
+
+impl TryFrom<String> for BuildpackVersionReq {
+    type Error = BuildpackVersionReqError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl std::str::FromStr for BuildpackVersionReq {
+    type Err = BuildpackVersionReqError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.trim() == "*" {
+            return Ok(Self::STAR);
+        }
+
+        value
+            .split(',')
+            .map(|comparator| parse_comparator(comparator.trim()))
+            .collect::<Option<Vec<_>>>()
+            .map(|comparators| Self { comparators })
+            .ok_or_else(|| BuildpackVersionReqError::InvalidBuildpackVersionReq(value.to_string()))
+    }
+}
+
+fn parse_comparator(value: &str) -> Option<Comparator> {
+    let (op, version) = [
+        (">=", Op::GreaterEq),
+        ("<=", Op::LessEq),
+        (">", Op::Greater),
+        ("<", Op::Less),
+        ("=", Op::Exact),
+        ("~", Op::Tilde),
+        ("^", Op::Caret),
+    ]
+    .into_iter()
+    .find_map(|(prefix, op)| {
+        value
+            .strip_prefix(prefix)
+            .map(|rest| (op, rest.trim_start()))
+    })
+    .unwrap_or((Op::Exact, value));
+
+    // Same rules as for `BuildpackVersion`, except that the minor and patch parts are optional.
+    let parts = version
+        .split('.')
+        .map(|s| {
+            if s.starts_with('0') && s != "0" {
+                None
+            } else {
+                s.parse().ok()
+            }
+        })
+        .collect::<Option<Vec<u64>>>()?;
+
+    match *parts.as_slice() {
+        [major] => Some((major, None, None)),
+        [major, minor] => Some((major, Some(minor), None)),
+        [major, minor, patch] => Some((major, Some(minor), Some(patch))),
+        _ => None,
+    }
+    .map(|(major, minor, patch)| Comparator {
+        op,
+        major,
+        minor,
+        patch,
+    })
+}
+
+impl From<&BuildpackVersion> for BuildpackVersionReq {
+    fn from(version: &BuildpackVersion) -> Self {
+        Self {
+            comparators: vec![Comparator {
+                op: Op::Exact,
+                major: version.major,
+                minor: Some(version.minor),
+                patch: Some(version.patch),
+            }],
+        }
+    }
+}
+
+impl From<BuildpackVersion> for BuildpackVersionReq {
+    fn from(version: BuildpackVersion) -> Self {
+        Self::from(&version)
+    }
+}
+
+impl Display for BuildpackVersionReq {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        if self.comparators.is_empty() {
+            return formatter.write_str("*");
+        }
+
+        for (index, comparator) in self.comparators.iter().enumerate() {
+            if index > 0 {
+                formatter.write_str(", ")?;
+            }
+
+            write!(formatter, "{comparator}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Comparator {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        let op = match self.op {
+            Op::Exact => "=",
+            Op::Greater => ">",
+            Op::GreaterEq => ">=",
+            Op::Less => "<",
+            Op::LessEq => "<=",
+            Op::Tilde => "~",
+            Op::Caret => "^",
+        };
+
+        write!(formatter, "{op}{}", self.major)?;
+
+        if let Some(minor) = self.minor {
+            write!(formatter, ".{minor}")?;
+        }
+
+        if let Some(patch) = self.patch {
+            write!(formatter, ".{patch}")?;
+        }
+
+        Ok(())
+    }
+}
+
+// Serialized as a string, the same format that is accepted when deserializing.
+impl Serialize for BuildpackVersionReq {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum BuildpackVersionReqError {
+    #[error("Invalid buildpack version requirement: `{0}`")]
+    InvalidBuildpackVersionReq(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+
+    use super::*;
+
+    fn matches(requirement: &str, version: &str) -> bool {
+        requirement
+            .parse::<BuildpackVersionReq>()
+            .unwrap()
+            .matches(&BuildpackVersion::try_from(version.to_string()).unwrap())
+    }
+
+    #[test]
+    fn exact_requirements() {
+        assert!(matches("1.2.3", "1.2.3"));
+        assert!(matches("=1.2.3", "1.2.3"));
+        assert!(!matches("=1.2.3", "1.2.4"));
+        assert!(matches("=1.2", "1.2.9"));
+        assert!(!matches("=1.2", "1.3.0"));
+        assert!(matches("=1", "1.9.9"));
+    }
+
+    #[test]
+    fn comparison_requirements() {
+        assert!(matches(">1.2.3", "1.2.4"));
+        assert!(!matches(">1.2.3", "1.2.3"));
+        assert!(matches(">1.2", "1.3.0"));
+        assert!(!matches(">1.2", "1.2.9"));
+        assert!(matches(">=1.2", "1.2.0"));
+        assert!(!matches(">=1.2", "1.1.9"));
+        assert!(matches("<2", "1.9.9"));
+        assert!(!matches("<2", "2.0.0"));
+        assert!(matches("<=1.2", "1.2.9"));
+        assert!(!matches("<=1.2", "1.3.0"));
+    }
+
+    #[test]
+    fn tilde_requirements() {
+        assert!(matches("~1.2.3", "1.2.3"));
+        assert!(matches("~1.2.3", "1.2.9"));
+        assert!(!matches("~1.2.3", "1.3.0"));
+        assert!(!matches("~1.2.3", "1.2.2"));
+        assert!(matches("~1", "1.9.0"));
+    }
+
+    #[test]
+    fn caret_requirements() {
+        assert!(matches("^1.2", "1.2.0"));
+        assert!(matches("^1.2", "1.9.0"));
+        assert!(!matches("^1.2", "1.1.9"));
+        assert!(!matches("^1.2", "2.0.0"));
+        assert!(matches("^1.2.3", "1.3.0"));
+        assert!(!matches("^1.2.3", "1.2.2"));
+        assert!(matches("^0.2.3", "0.2.9"));
+        assert!(!matches("^0.2.3", "0.3.0"));
+        assert!(matches("^0.0.3", "0.0.3"));
+        assert!(!matches("^0.0.3", "0.0.4"));
+        assert!(matches("^0.0", "0.0.7"));
+        assert!(!matches("^0.0", "0.1.0"));
+    }
+
+    #[test]
+    fn compound_requirements() {
+        assert!(matches(">=1.0, <2", "1.0.0"));
+        assert!(matches(">=1.0, <2", "1.99.0"));
+        assert!(!matches(">=1.0, <2", "2.0.0"));
+        assert!(!matches(">=1.0, <2", "0.9.0"));
+        assert!(matches("*", "0.0.1"));
+    }
+
+    #[test]
+    fn from_buildpack_version() {
+        let requirement = BuildpackVersionReq::from(BuildpackVersion::new(1, 2, 3));
+
+        assert!(requirement.matches(&BuildpackVersion::new(1, 2, 3)));
+        assert!(!requirement.matches(&BuildpackVersion::new(1, 2, 4)));
+        assert_eq!(requirement.to_string(), "=1.2.3");
+    }
+
+    #[test]
+    fn serde_roundtrip() {
+        assert_tokens(
+            &">= 1.0, <2".parse::<BuildpackVersionReq>().unwrap(),
+            &[Token::Str(">=1.0, <2")],
+        );
+        assert_tokens(&BuildpackVersionReq::STAR, &[Token::Str("*")]);
+    }
+
+    #[test]
+    fn reject_invalid_requirements() {
+        for invalid in [
+            "",
+            "1.2.3.4",
+            ">=",
+            "^1.02",
+            "1.x",
+            ">=1.0,",
+            "!1.0",
+            "1.2.3-dev",
+        ] {
+            assert_de_tokens_error::<BuildpackVersionReq>(
+                &[Token::Str(invalid)],
+                &format!("Invalid buildpack version requirement: `{invalid}`"),
+            );
+        }
+    }
+}
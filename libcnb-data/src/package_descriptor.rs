@@ -1,3 +1,4 @@
+use crate::buildpack::{BuildpackTarget, BuildpackVersionReq};
 use crate::package_descriptor::PlatformOs::Linux;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::path::PathBuf;
@@ -27,6 +28,15 @@ use uriparse::{URIReference, URIReferenceError};
 ///
 /// [platform]
 /// os = "windows"
+///
+/// [[targets]]
+/// os = "linux"
+/// arch = "arm64"
+/// variant = "v8"
+///
+/// [[targets.distros]]
+/// name = "ubuntu"
+/// version = "24.04"
 /// "#;
 ///
 /// toml::from_str::<PackageDescriptor>(toml_str).unwrap();
@@ -46,6 +56,18 @@ pub struct PackageDescriptor {
     /// The expected runtime environment for the packaged buildpack.
     #[serde(default)]
     pub platform: Platform,
+
+    /// The targets (OS, architecture and optionally variant and distributions) the packaged
+    /// buildpack supports, using the same schema as `[[targets]]` in `buildpack.toml`.
+    ///
+    /// For composite buildpacks, the buildpacks it depends on must support all of these targets.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<BuildpackTarget>,
+
+    /// Locations of the per-platform packages of a buildpack that was packaged for multiple
+    /// targets.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub platforms: Vec<PackageDescriptorPlatform>,
 }
 
 impl Default for PackageDescriptor {
@@ -55,6 +77,8 @@ impl Default for PackageDescriptor {
                 .expect("a package.toml with buildpack.uri=\".\" should be valid"),
             dependencies: Vec::new(),
             platform: Platform::default(),
+            targets: Vec::new(),
+            platforms: Vec::new(),
         }
     }
 }
@@ -95,6 +119,13 @@ pub struct PackageDescriptorDependency {
     #[serde(deserialize_with = "deserialize_uri_reference")]
     #[serde(serialize_with = "serialize_uri_reference")]
     pub uri: URIReference<'static>,
+
+    /// A requirement on the version of the buildpack, such as `^1.2` or `>=1.0, <2`.
+    ///
+    /// This is an extension of libcnb.rs that is only supported for `libcnb:` dependencies, whose
+    /// version is checked against the buildpack in the workspace. It's removed when packaging.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<BuildpackVersionReq>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -116,7 +147,7 @@ impl TryFrom<&str> for PackageDescriptorDependency {
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         try_uri_from_str(value)
-            .map(|uri| PackageDescriptorDependency { uri })
+            .map(|uri| PackageDescriptorDependency { uri, version: None })
             .map_err(|_| PackageDescriptorDependencyError::InvalidUri(value.to_string()))
     }
 }
@@ -125,6 +156,25 @@ fn try_uri_from_str(value: &str) -> Result<URIReference<'static>, URIReferenceEr
     URIReference::try_from(value).map(URIReference::into_owned)
 }
 
+/// The location of the package for a single platform of a buildpack packaged for multiple targets.
+#[derive(Debug, Deserialize, Serialize, Eq, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PackageDescriptorPlatform {
+    /// The operating system of the platform, e.g. `linux`.
+    pub os: String,
+    /// The CPU architecture of the platform, e.g. `amd64` or `arm64`.
+    pub arch: String,
+    /// The CPU architecture variant of the platform, e.g. `v8`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+    /// A path to the directory containing the package for this platform.
+    ///
+    /// If the `uri` field is a relative path it will be relative to the `package.toml` file.
+    #[serde(deserialize_with = "deserialize_uri_reference")]
+    #[serde(serialize_with = "serialize_uri_reference")]
+    pub uri: URIReference<'static>,
+}
+
 /// The expected runtime environment for the packaged buildpack.
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(deny_unknown_fields)]
@@ -231,6 +281,27 @@ os = "windows"
         );
     }
 
+    #[test]
+    fn it_parses_dependency_version_requirements() {
+        let toml_str = r#"
+[buildpack]
+uri = "."
+
+[[dependencies]]
+uri = "libcnb:buildpack-id"
+version = ">=1.0, <2"
+"#;
+
+        let package_descriptor = toml::from_str::<PackageDescriptor>(toml_str).unwrap();
+        assert_eq!(
+            package_descriptor.dependencies,
+            [PackageDescriptorDependency {
+                version: Some(">=1.0, <2".parse().unwrap()),
+                ..PackageDescriptorDependency::try_from("libcnb:buildpack-id").unwrap()
+            }]
+        );
+    }
+
     #[test]
     fn it_serializes() {
         let package_descriptor = PackageDescriptor {
@@ -243,6 +314,8 @@ os = "windows"
                     .unwrap(),
             ],
             platform: Platform::default(),
+            targets: Vec::new(),
+            platforms: Vec::new(),
         };
 
         let package_descriptor_contents = toml::to_string(&package_descriptor).unwrap();
@@ -270,4 +343,101 @@ os = "linux"
             .trim_start()
         );
     }
+
+    #[test]
+    fn it_roundtrips_targets() {
+        let toml_str = r#"
+[buildpack]
+uri = "."
+
+[platform]
+os = "linux"
+
+[[targets]]
+os = "linux"
+arch = "amd64"
+
+[[targets]]
+os = "linux"
+arch = "arm64"
+variant = "v8"
+
+[[targets.distros]]
+name = "ubuntu"
+version = "24.04"
+"#
+        .trim_start();
+
+        let package_descriptor = toml::from_str::<PackageDescriptor>(toml_str).unwrap();
+        assert_eq!(
+            package_descriptor.targets,
+            [
+                BuildpackTarget {
+                    os: Some(String::from("linux")),
+                    arch: Some(String::from("amd64")),
+                    variant: None,
+                    distros: Vec::new(),
+                },
+                BuildpackTarget {
+                    os: Some(String::from("linux")),
+                    arch: Some(String::from("arm64")),
+                    variant: Some(String::from("v8")),
+                    distros: vec![crate::buildpack::Distro {
+                        name: String::from("ubuntu"),
+                        version: String::from("24.04"),
+                    }],
+                }
+            ]
+        );
+        assert_eq!(
+            toml::to_string(&package_descriptor).unwrap(),
+            format!("dependencies = []\n\n{toml_str}")
+        );
+    }
+
+    #[test]
+    fn it_roundtrips_platforms() {
+        let toml_str = r#"
+[buildpack]
+uri = "."
+
+[platform]
+os = "linux"
+
+[[platforms]]
+os = "linux"
+arch = "amd64"
+uri = "linux/amd64"
+
+[[platforms]]
+os = "linux"
+arch = "arm64"
+variant = "v8"
+uri = "linux/arm64/v8"
+"#
+        .trim_start();
+
+        let package_descriptor = toml::from_str::<PackageDescriptor>(toml_str).unwrap();
+        assert_eq!(
+            package_descriptor.platforms,
+            [
+                PackageDescriptorPlatform {
+                    os: String::from("linux"),
+                    arch: String::from("amd64"),
+                    variant: None,
+                    uri: URIReference::try_from("linux/amd64").unwrap(),
+                },
+                PackageDescriptorPlatform {
+                    os: String::from("linux"),
+                    arch: String::from("arm64"),
+                    variant: Some(String::from("v8")),
+                    uri: URIReference::try_from("linux/arm64/v8").unwrap(),
+                }
+            ]
+        );
+        assert_eq!(
+            toml::to_string(&package_descriptor).unwrap(),
+            format!("dependencies = []\n\n{toml_str}")
+        );
+    }
 }
@@ -0,0 +1,251 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Finds the files in a libcnb.rs buildpack's crate directory that should be copied into the
+/// packaged buildpack, as declared by the `include` and `exclude` globs in
+/// `[package.metadata.libcnb]` (see [`crate::cargo::LibcnbCargoMetadata`]).
+///
+/// Globs are matched against paths relative to the crate directory, using `/` as the separator.
+/// Files ignored by `.gitignore` or similar files are never included, the same way they're not
+/// considered when locating buildpacks. The given excluded directories, such as the Cargo target
+/// directory and the package directory, are skipped as well, even if they aren't ignored. Returns
+/// the relative paths of all matching files, sorted.
+///
+/// # Errors
+///
+/// Will return `Err` if a glob is invalid, a matching file would overwrite a file libcnb.rs writes
+/// into the packaged buildpack or an I/O error occurred while traversing the crate directory.
+pub fn find_buildpack_assets(
+    buildpack_dir: &Path,
+    include: &[String],
+    exclude: &[String],
+    excluded_dirs: &[PathBuf],
+) -> Result<Vec<PathBuf>, FindBuildpackAssetsError> {
+    if include.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let include = glob_set(include)?;
+    let exclude = glob_set(exclude)?;
+
+    // Paths are compared canonicalized, since the excluded directories might be given in another
+    // form than the crate directory. Directories that don't exist yet can't contain any files.
+    let buildpack_dir = fs::canonicalize(buildpack_dir).unwrap_or(buildpack_dir.to_path_buf());
+    let excluded_dirs = excluded_dirs
+        .iter()
+        .filter_map(|dir| fs::canonicalize(dir).ok())
+        .collect::<Vec<_>>();
+
+    let mut asset_paths = Vec::new();
+
+    // Hidden files are included, since assets such as `.defaults.toml` are common, but Git's own
+    // directory never contains assets.
+    for entry in ignore::WalkBuilder::new(&buildpack_dir)
+        .hidden(false)
+        .filter_entry(move |entry| {
+            entry.file_name() != ".git"
+                && !excluded_dirs
+                    .iter()
+                    .any(|excluded_dir| entry.path() == excluded_dir)
+        })
+        .build()
+    {
+        let entry = entry.map_err(FindBuildpackAssetsError::FindFiles)?;
+
+        if !entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+        {
+            continue;
+        }
+
+        let Ok(relative_path) = entry.path().strip_prefix(&buildpack_dir) else {
+            continue;
+        };
+
+        if include.is_match(relative_path) && !exclude.is_match(relative_path) {
+            if is_reserved_path(relative_path) {
+                return Err(FindBuildpackAssetsError::ReservedPath(
+                    relative_path.to_path_buf(),
+                ));
+            }
+
+            asset_paths.push(relative_path.to_path_buf());
+        }
+    }
+
+    asset_paths.sort();
+    Ok(asset_paths)
+}
+
+/// Copies the given assets, relative to the crate directory, into the packaged buildpack directory.
+///
+/// File permissions are copied as well, so executable files stay executable.
+pub(crate) fn copy_buildpack_assets(
+    buildpack_dir: &Path,
+    asset_paths: &[PathBuf],
+    destination: &Path,
+) -> std::io::Result<()> {
+    for asset_path in asset_paths {
+        let destination_path = destination.join(asset_path);
+
+        if let Some(parent) = destination_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::copy(buildpack_dir.join(asset_path), destination_path)?;
+    }
+
+    Ok(())
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum FindBuildpackAssetsError {
+    #[error("Invalid glob: {0}")]
+    InvalidGlob(globset::Error),
+    #[error("Error while finding asset files: {0}")]
+    FindFiles(ignore::Error),
+    #[error("Asset {} would overwrite a file written by libcnb.rs", .0.display())]
+    ReservedPath(PathBuf),
+}
+
+fn glob_set(globs: &[String]) -> Result<GlobSet, FindBuildpackAssetsError> {
+    let mut builder = GlobSetBuilder::new();
+
+    for glob in globs {
+        builder.add(Glob::new(glob).map_err(FindBuildpackAssetsError::InvalidGlob)?);
+    }
+
+    builder
+        .build()
+        .map_err(FindBuildpackAssetsError::InvalidGlob)
+}
+
+// Files and directories in the packaged buildpack directory that are written by libcnb.rs itself.
+fn is_reserved_path(relative_path: &Path) -> bool {
+    relative_path == Path::new("buildpack.toml")
+        || relative_path == Path::new("package.toml")
+        || relative_path.starts_with("bin")
+        || relative_path.starts_with("exec.d")
+        || relative_path.starts_with(".libcnb-cargo")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_and_copy_buildpack_assets() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let buildpack_dir = temp_dir.path().join("buildpack");
+        let destination = temp_dir.path().join("packaged");
+
+        for path in [
+            "scripts/setup.sh",
+            "scripts/setup.sh.bak",
+            "templates/config.toml",
+            "templates/nested/.defaults.toml",
+            "src/main.rs",
+        ] {
+            let path = buildpack_dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        #[cfg(target_family = "unix")]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(
+                buildpack_dir.join("scripts/setup.sh"),
+                fs::Permissions::from_mode(0o755),
+            )
+            .unwrap();
+        }
+
+        let asset_paths = find_buildpack_assets(
+            &buildpack_dir,
+            &[String::from("scripts/*"), String::from("templates/**")],
+            &[String::from("**/*.bak")],
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(
+            asset_paths,
+            [
+                PathBuf::from("scripts/setup.sh"),
+                PathBuf::from("templates/config.toml"),
+                PathBuf::from("templates/nested/.defaults.toml"),
+            ]
+        );
+
+        copy_buildpack_assets(&buildpack_dir, &asset_paths, &destination).unwrap();
+
+        assert!(destination
+            .join("templates/nested/.defaults.toml")
+            .is_file());
+
+        #[cfg(target_family = "unix")]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |path: &str| {
+                fs::metadata(destination.join(path))
+                    .unwrap()
+                    .permissions()
+                    .mode()
+                    & 0o111
+            };
+
+            assert_ne!(mode("scripts/setup.sh"), 0);
+            assert_eq!(mode("templates/config.toml"), 0);
+        }
+    }
+
+    #[test]
+    fn find_buildpack_assets_skips_excluded_dirs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        for path in [
+            "templates/config.toml",
+            "target/debug/build",
+            "packaged/x86_64-unknown-linux-musl/debug/buildpack/buildpack.toml",
+        ] {
+            let path = temp_dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        assert_eq!(
+            find_buildpack_assets(
+                temp_dir.path(),
+                &[String::from("**")],
+                &[],
+                &[
+                    temp_dir.path().join("target"),
+                    temp_dir.path().join("packaged"),
+                    temp_dir.path().join("does-not-exist"),
+                ],
+            )
+            .unwrap(),
+            [PathBuf::from("templates/config.toml")]
+        );
+    }
+
+    #[test]
+    fn find_buildpack_assets_rejects_reserved_paths() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join("bin")).unwrap();
+        fs::write(temp_dir.path().join("bin").join("build"), "").unwrap();
+
+        assert!(matches!(
+            find_buildpack_assets(temp_dir.path(), &[String::from("bin/*")], &[], &[]),
+            Err(FindBuildpackAssetsError::ReservedPath(path)) if path == Path::new("bin/build")
+        ));
+
+        assert!(matches!(
+            find_buildpack_assets(temp_dir.path(), &[String::from("[")], &[], &[]),
+            Err(FindBuildpackAssetsError::InvalidGlob(_))
+        ));
+    }
+}
@@ -0,0 +1,489 @@
+use crate::build::BuildpackBinaries;
+use crate::cross_compile::GlibcVersion;
+use crate::output::packaging_metadata_dir;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The results of auditing the binaries of a packaged libcnb.rs buildpack, keyed by their path
+/// within the packaged buildpack directory.
+///
+/// [`crate::package::package_buildpack`] writes this report as JSON to `binary-audit.json` in the
+/// packaging metadata directory of packaged libcnb.rs buildpacks (see
+/// [`crate::output::packaging_metadata_dir`]), see [`read_binary_audit_report`].
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BinaryAuditReport {
+    pub binaries: BTreeMap<String, BinaryAudit>,
+}
+
+/// Properties of an ELF binary.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BinaryAudit {
+    /// The size of the binary in bytes.
+    pub size: u64,
+    pub linkage: Linkage,
+    /// The dynamic linker requested by the binary, such as `/lib64/ld-linux-x86-64.so.2`.
+    pub interpreter: Option<String>,
+    /// The shared libraries the binary depends on, such as `libc.so.6`.
+    pub needed_libraries: Vec<String>,
+    /// Whether the binary has no symbol table.
+    pub stripped: bool,
+    /// The highest glibc version whose symbols the binary references (the highest `GLIBC_x.y`
+    /// symbol version it needs), or `None` if it doesn't link against glibc.
+    #[serde(default)]
+    pub required_glibc_version: Option<GlibcVersion>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Linkage {
+    /// The binary doesn't need a dynamic linker or any shared libraries. This includes static-pie
+    /// binaries, which is what Rust produces for musl targets.
+    Static,
+    /// The binary needs a dynamic linker or shared libraries to run.
+    Dynamic,
+}
+
+/// Audits the ELF binary at the given path.
+///
+/// Returns `None` if the file isn't an ELF binary, for example when building for a non-Linux
+/// target.
+///
+/// # Errors
+///
+/// Will return `Err` if the file couldn't be read.
+pub fn audit_binary(path: &Path) -> std::io::Result<Option<BinaryAudit>> {
+    let data = fs::read(path)?;
+
+    Ok(ElfFile::parse(&data).and_then(|elf_file| {
+        let interpreter = elf_file.interpreter(&elf_file.program_headers()?);
+        let needed_libraries = elf_file.needed_libraries()?;
+
+        Some(BinaryAudit {
+            size: data.len() as u64,
+            linkage: if interpreter.is_none() && needed_libraries.is_empty() {
+                Linkage::Static
+            } else {
+                Linkage::Dynamic
+            },
+            interpreter,
+            needed_libraries,
+            stripped: !elf_file.has_section_of_type(SHT_SYMTAB)?,
+            required_glibc_version: elf_file
+                .needed_versions()?
+                .iter()
+                .filter_map(|version_name| glibc_version_from_version_name(version_name))
+                .max(),
+        })
+    }))
+}
+
+/// Audits the given buildpack binaries, using the paths they are packaged at as keys.
+pub(crate) fn audit_buildpack_binaries(
+    buildpack_binaries: &BuildpackBinaries,
+) -> std::io::Result<BinaryAuditReport> {
+    let mut binaries = BTreeMap::new();
+
+    if let Some(binary_audit) = audit_binary(&buildpack_binaries.buildpack_target_binary_path)? {
+        binaries.insert(String::from("bin/build"), binary_audit);
+    }
+
+    for (binary_target_name, binary_path) in &buildpack_binaries.additional_target_binary_paths {
+        let packaged_path = buildpack_binaries
+            .additional_binary_packaged_paths
+            .get(binary_target_name)
+            .map_or_else(
+                || format!(".libcnb-cargo/additional-bin/{binary_target_name}"),
+                |packaged_path| packaged_path.to_string_lossy().to_string(),
+            );
+
+        if let Some(binary_audit) = audit_binary(binary_path)? {
+            binaries.insert(packaged_path, binary_audit);
+        }
+    }
+
+    Ok(BinaryAuditReport { binaries })
+}
+
+pub(crate) fn write_binary_audit_report(
+    packaged_buildpack_dir: &Path,
+    binary_audit_report: &BinaryAuditReport,
+) -> std::io::Result<()> {
+    let report_path = binary_audit_report_path(packaged_buildpack_dir);
+
+    if let Some(parent) = report_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut contents = serde_json::to_string_pretty(binary_audit_report)?;
+    contents.push('\n');
+    fs::write(report_path, contents)
+}
+
+/// Reads the binary audit report of the libcnb.rs buildpack packaged in the given directory.
+///
+/// # Errors
+///
+/// Will return `Err` if the report doesn't exist or couldn't be read.
+pub fn read_binary_audit_report(
+    packaged_buildpack_dir: &Path,
+) -> std::io::Result<BinaryAuditReport> {
+    let contents = fs::read_to_string(binary_audit_report_path(packaged_buildpack_dir))?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+fn binary_audit_report_path(packaged_buildpack_dir: &Path) -> PathBuf {
+    packaging_metadata_dir(packaged_buildpack_dir).join("binary-audit.json")
+}
+
+// A minimal reader for the parts of ELF files needed for auditing. All accessors return `None`
+// for malformed files instead of panicking.
+struct ElfFile<'a> {
+    data: &'a [u8],
+    is_64_bit: bool,
+    is_little_endian: bool,
+}
+
+impl<'a> ElfFile<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        if !data.starts_with(b"\x7fELF") {
+            return None;
+        }
+
+        let is_64_bit = match data.get(4)? {
+            1 => false,
+            2 => true,
+            _ => return None,
+        };
+
+        let is_little_endian = match data.get(5)? {
+            1 => true,
+            2 => false,
+            _ => return None,
+        };
+
+        Some(Self {
+            data,
+            is_64_bit,
+            is_little_endian,
+        })
+    }
+
+    fn interpreter(&self, program_headers: &[(u32, usize, usize)]) -> Option<String> {
+        program_headers
+            .iter()
+            .find(|(program_type, _, _)| *program_type == PT_INTERP)
+            .and_then(|(_, offset, size)| self.bytes(*offset, *size))
+            .map(|bytes| {
+                String::from_utf8_lossy(bytes)
+                    .trim_end_matches('\0')
+                    .to_string()
+            })
+    }
+
+    fn needed_libraries(&self) -> Option<Vec<String>> {
+        let sections = self.section_headers()?;
+        let mut needed_libraries = Vec::new();
+
+        for section in sections
+            .iter()
+            .filter(|section| section.kind == SHT_DYNAMIC)
+        {
+            let string_table = sections.get(usize::try_from(section.link).ok()?)?;
+            let entry_size = if self.is_64_bit { 16 } else { 8 };
+
+            for entry_offset in
+                (section.offset..section.offset.checked_add(section.size)?).step_by(entry_size)
+            {
+                let tag = self.word(entry_offset)?;
+                let value = self.word(entry_offset.checked_add(entry_size / 2)?)?;
+
+                match tag {
+                    DT_NULL => break,
+                    DT_NEEDED => needed_libraries.push(
+                        self.string(
+                            string_table
+                                .offset
+                                .checked_add(usize::try_from(value).ok()?)?,
+                        )?,
+                    ),
+                    _ => {}
+                }
+            }
+        }
+
+        Some(needed_libraries)
+    }
+
+    // Returns the names of the symbol versions the binary needs from shared libraries, such as
+    // `GLIBC_2.17`, which are listed in the version needs section (`.gnu.version_r`).
+    fn needed_versions(&self) -> Option<Vec<String>> {
+        let sections = self.section_headers()?;
+        let mut needed_versions = Vec::new();
+
+        for section in sections
+            .iter()
+            .filter(|section| section.kind == SHT_GNU_VERNEED)
+        {
+            let string_table = sections.get(usize::try_from(section.link).ok()?)?;
+            let mut need_offset = section.offset;
+
+            // Every entry is 16 bytes and points to the next one with a relative offset, which is
+            // zero for the last one. Limiting the number of entries to what fits into the section
+            // guards against cycles in malformed files.
+            for _ in 0..section.size / VERNEED_ENTRY_SIZE {
+                let aux_count = self.half(need_offset.checked_add(0x02)?)?;
+                let mut aux_offset = need_offset.checked_add(
+                    usize::try_from(self.u32(need_offset.checked_add(0x08)?)?).ok()?,
+                )?;
+
+                for _ in 0..aux_count {
+                    let name_offset = self.u32(aux_offset.checked_add(0x08)?)?;
+                    needed_versions.push(
+                        self.string(
+                            string_table
+                                .offset
+                                .checked_add(usize::try_from(name_offset).ok()?)?,
+                        )?,
+                    );
+
+                    aux_offset = aux_offset.checked_add(
+                        usize::try_from(self.u32(aux_offset.checked_add(0x0c)?)?).ok()?,
+                    )?;
+                }
+
+                match self.u32(need_offset.checked_add(0x0c)?)? {
+                    0 => break,
+                    next_offset => {
+                        need_offset =
+                            need_offset.checked_add(usize::try_from(next_offset).ok()?)?;
+                    }
+                }
+            }
+        }
+
+        Some(needed_versions)
+    }
+
+    fn has_section_of_type(&self, kind: u32) -> Option<bool> {
+        Some(
+            self.section_headers()?
+                .iter()
+                .any(|section| section.kind == kind),
+        )
+    }
+
+    // Returns the type, file offset and file size of all program headers.
+    fn program_headers(&self) -> Option<Vec<(u32, usize, usize)>> {
+        let (table_offset, entry_size, count) = if self.is_64_bit {
+            (self.word(0x20)?, self.half(0x36)?, self.half(0x38)?)
+        } else {
+            (self.word(0x1c)?, self.half(0x2a)?, self.half(0x2c)?)
+        };
+
+        (0..usize::from(count))
+            .map(|index| {
+                let offset = table_entry_offset(table_offset, entry_size, index)?;
+                let (file_offset, file_size) = if self.is_64_bit {
+                    (
+                        self.word(offset.checked_add(0x08)?)?,
+                        self.word(offset.checked_add(0x20)?)?,
+                    )
+                } else {
+                    (
+                        self.word(offset.checked_add(0x04)?)?,
+                        self.word(offset.checked_add(0x10)?)?,
+                    )
+                };
+
+                Some((
+                    self.u32(offset)?,
+                    usize::try_from(file_offset).ok()?,
+                    usize::try_from(file_size).ok()?,
+                ))
+            })
+            .collect()
+    }
+
+    fn section_headers(&self) -> Option<Vec<SectionHeader>> {
+        let (table_offset, entry_size, count) = if self.is_64_bit {
+            (self.word(0x28)?, self.half(0x3a)?, self.half(0x3c)?)
+        } else {
+            (self.word(0x20)?, self.half(0x2e)?, self.half(0x30)?)
+        };
+
+        (0..usize::from(count))
+            .map(|index| {
+                let offset = table_entry_offset(table_offset, entry_size, index)?;
+                let (file_offset, size, link) = if self.is_64_bit {
+                    (
+                        self.word(offset.checked_add(0x18)?)?,
+                        self.word(offset.checked_add(0x20)?)?,
+                        self.u32(offset.checked_add(0x28)?)?,
+                    )
+                } else {
+                    (
+                        self.word(offset.checked_add(0x10)?)?,
+                        self.word(offset.checked_add(0x14)?)?,
+                        self.u32(offset.checked_add(0x18)?)?,
+                    )
+                };
+
+                Some(SectionHeader {
+                    kind: self.u32(offset.checked_add(0x04)?)?,
+                    offset: usize::try_from(file_offset).ok()?,
+                    size: usize::try_from(size).ok()?,
+                    link,
+                })
+            })
+            .collect()
+    }
+
+    fn bytes(&self, offset: usize, size: usize) -> Option<&'a [u8]> {
+        self.data.get(offset..offset.checked_add(size)?)
+    }
+
+    fn string(&self, offset: usize) -> Option<String> {
+        let bytes = self.data.get(offset..)?;
+        let length = bytes.iter().position(|byte| *byte == 0)?;
+        Some(String::from_utf8_lossy(&bytes[..length]).to_string())
+    }
+
+    fn half(&self, offset: usize) -> Option<u16> {
+        let bytes = self.bytes(offset, 2)?.try_into().ok()?;
+
+        Some(if self.is_little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    fn u32(&self, offset: usize) -> Option<u32> {
+        let bytes = self.bytes(offset, 4)?.try_into().ok()?;
+
+        Some(if self.is_little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    // Reads an address, offset or size, which are 64 bits wide in 64-bit files and 32 bits wide
+    // otherwise.
+    fn word(&self, offset: usize) -> Option<u64> {
+        if self.is_64_bit {
+            let bytes = self.bytes(offset, 8)?.try_into().ok()?;
+
+            Some(if self.is_little_endian {
+                u64::from_le_bytes(bytes)
+            } else {
+                u64::from_be_bytes(bytes)
+            })
+        } else {
+            self.u32(offset).map(u64::from)
+        }
+    }
+}
+
+// Parses symbol version names such as `GLIBC_2.17` or `GLIBC_2.2.5`. Other names, such as
+// `GLIBC_PRIVATE` or versions of other libraries, return `None`.
+fn glibc_version_from_version_name(version_name: &str) -> Option<GlibcVersion> {
+    version_name
+        .strip_prefix("GLIBC_")
+        .and_then(GlibcVersion::parse)
+}
+
+// Offsets in malformed files can be arbitrarily large, so the offset of a table entry is
+// calculated with checked arithmetic.
+fn table_entry_offset(table_offset: u64, entry_size: u16, index: usize) -> Option<usize> {
+    usize::try_from(table_offset)
+        .ok()?
+        .checked_add(index.checked_mul(usize::from(entry_size))?)
+}
+
+struct SectionHeader {
+    kind: u32,
+    offset: usize,
+    size: usize,
+    link: u32,
+}
+
+const PT_INTERP: u32 = 3;
+const SHT_SYMTAB: u32 = 2;
+const SHT_DYNAMIC: u32 = 6;
+const SHT_GNU_VERNEED: u32 = 0x6fff_fffe;
+const VERNEED_ENTRY_SIZE: usize = 16;
+const DT_NULL: u64 = 0;
+const DT_NEEDED: u64 = 1;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audit_non_elf_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("script.sh");
+        fs::write(&path, "#!/usr/bin/env bash\n").unwrap();
+
+        assert_eq!(audit_binary(&path).unwrap(), None);
+    }
+
+    #[test]
+    fn audit_malformed_elf_file() {
+        // A 64-bit little-endian ELF header whose section header table offset and entry size
+        // would overflow when calculating the offsets of its entries.
+        let mut data = vec![0; 0x40];
+        data[..6].copy_from_slice(b"\x7fELF\x02\x01");
+        data[0x28..0x30].copy_from_slice(&u64::MAX.to_le_bytes());
+        data[0x3a..0x3c].copy_from_slice(&u16::MAX.to_le_bytes());
+        data[0x3c..0x3e].copy_from_slice(&2u16.to_le_bytes());
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("malformed");
+        fs::write(&path, data).unwrap();
+
+        assert_eq!(audit_binary(&path).unwrap(), None);
+    }
+
+    #[test]
+    fn glibc_versions_from_version_names() {
+        assert_eq!(
+            glibc_version_from_version_name("GLIBC_2.17"),
+            Some(GlibcVersion {
+                major: 2,
+                minor: 17
+            })
+        );
+        assert_eq!(
+            glibc_version_from_version_name("GLIBC_2.2.5"),
+            Some(GlibcVersion { major: 2, minor: 2 })
+        );
+        assert_eq!(glibc_version_from_version_name("GLIBC_PRIVATE"), None);
+        assert_eq!(glibc_version_from_version_name("GCC_3.0"), None);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn audit_dynamically_linked_binary() {
+        // The test binary itself is dynamically linked when built for a glibc target.
+        let binary_audit = audit_binary(&std::env::current_exe().unwrap())
+            .unwrap()
+            .unwrap();
+
+        if cfg!(target_env = "gnu") {
+            assert_eq!(binary_audit.linkage, Linkage::Dynamic);
+            assert!(binary_audit.interpreter.is_some());
+            assert!(binary_audit
+                .needed_libraries
+                .iter()
+                .any(|library| library.starts_with("libc.so")));
+            assert!(binary_audit
+                .required_glibc_version
+                .is_some_and(|glibc_version| glibc_version.major == 2));
+        }
+        assert!(binary_audit.size > 0);
+    }
+}
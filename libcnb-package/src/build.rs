@@ -1,18 +1,21 @@
 use crate::cargo::{
-    cargo_binary_target_names, determine_buildpack_cargo_target_name,
-    DetermineBuildpackCargoTargetNameError,
+    additional_binary_packaged_paths, cargo_binary_target_names,
+    determine_buildpack_cargo_target_name, DetermineBuildpackCargoTargetNameError,
+    InvalidAdditionalBinaryError, LibcnbCargoMetadata,
 };
+use crate::cross_compile::CrossCompileBackend;
 use crate::CargoProfile;
 use cargo_metadata::Metadata;
-use std::collections::HashMap;
-use std::ffi::OsString;
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::{OsStr, OsString};
 use std::path::{Path, PathBuf};
 use std::process::{Command, ExitStatus};
 
 /// Builds all buildpack binary targets using Cargo.
 ///
 /// It uses libcnb configuration metadata in the Crate's `Cargo.toml` to determine which binary is
-/// the main buildpack binary and which are additional ones.
+/// the main buildpack binary and which are additional ones, and where additional binaries are
+/// placed in the packaged buildpack.
 ///
 /// See [`build_binary`] for details around the build process.
 ///
@@ -23,20 +26,41 @@ use std::process::{Command, ExitStatus};
 pub(crate) fn build_buildpack_binaries(
     project_path: impl AsRef<Path>,
     cargo_metadata: &Metadata,
+    libcnb_cargo_metadata: &LibcnbCargoMetadata,
     cargo_profile: CargoProfile,
+    cargo_build_options: &CargoBuildOptions,
     cargo_env: &[(OsString, OsString)],
     target_triple: impl AsRef<str>,
 ) -> Result<BuildpackBinaries, BuildBinariesError> {
+    let cargo_build_options = buildpack_cargo_build_options(
+        |feature| {
+            cargo_metadata
+                .root_package()
+                .is_some_and(|root_package| root_package.features.contains_key(feature))
+        },
+        libcnb_cargo_metadata,
+        cargo_build_options,
+    );
+
     let binary_target_names = cargo_binary_target_names(cargo_metadata);
-    let buildpack_cargo_target = determine_buildpack_cargo_target_name(cargo_metadata)
-        .map_err(BuildBinariesError::CannotDetermineBuildpackCargoTargetName)?;
+    let buildpack_cargo_target =
+        determine_buildpack_cargo_target_name(cargo_metadata, libcnb_cargo_metadata)
+            .map_err(BuildBinariesError::CannotDetermineBuildpackCargoTargetName)?;
+
+    let additional_binary_packaged_paths = additional_binary_packaged_paths(
+        libcnb_cargo_metadata,
+        &binary_target_names,
+        &buildpack_cargo_target,
+    )
+    .map_err(BuildBinariesError::InvalidAdditionalBinary)?;
 
     let buildpack_target_binary_path = if binary_target_names.contains(&buildpack_cargo_target) {
         build_binary(
             project_path.as_ref(),
             cargo_metadata,
             cargo_profile,
-            cargo_env.to_owned(),
+            &cargo_build_options,
+            cargo_env,
             target_triple.as_ref(),
             &buildpack_cargo_target,
         )
@@ -58,7 +82,8 @@ pub(crate) fn build_buildpack_binaries(
                 project_path.as_ref(),
                 cargo_metadata,
                 cargo_profile,
-                cargo_env.to_owned(),
+                &cargo_build_options,
+                cargo_env,
                 target_triple.as_ref(),
                 additional_binary_target_name,
             )
@@ -71,9 +96,71 @@ pub(crate) fn build_buildpack_binaries(
     Ok(BuildpackBinaries {
         buildpack_target_binary_path,
         additional_target_binary_paths,
+        additional_binary_packaged_paths,
     })
 }
 
+/// Options for building buildpack binaries in addition to the [`CargoProfile`].
+///
+/// When packaging, these are combined with the options in `[package.metadata.libcnb]` of each
+/// buildpack's `Cargo.toml` (see [`LibcnbCargoMetadata`]).
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct CargoBuildOptions {
+    /// Cargo features to enable. Only features a buildpack crate defines are enabled for it, so
+    /// features of some buildpacks can be enabled when packaging a whole workspace.
+    pub features: Vec<String>,
+    /// The Cargo profile to build with, instead of `dev` or `release` depending on the
+    /// [`CargoProfile`]. This doesn't change where packaged buildpacks are written to.
+    pub profile: Option<String>,
+    /// Flags passed to the compiler in addition to the ones in the `CARGO_ENCODED_RUSTFLAGS` or
+    /// `RUSTFLAGS` environment variables. Each flag is passed as is, so it may contain spaces.
+    ///
+    /// The flags are passed via `CARGO_ENCODED_RUSTFLAGS`, which makes Cargo ignore
+    /// `build.rustflags` and `target.<triple>.rustflags` in `.cargo/config.toml`. Flags configured
+    /// there have to be repeated here when using this option.
+    pub rustflags: Vec<String>,
+    /// Requires `Cargo.lock` to be up to date, like `cargo build --locked`.
+    pub locked: bool,
+    /// Keeps symbols (and, for the `dev` profile, debug info) in the binaries instead of stripping
+    /// them. This applies to whichever profile the binaries are built with.
+    pub no_strip: bool,
+    /// The tool that builds the binaries, `cargo build` by default.
+    pub cross_compile_backend: CrossCompileBackend,
+}
+
+// Combines the given options with the ones in the buildpack's Cargo metadata. Options given by the
+// caller take precedence, or are added to the buildpack's options where they can be combined.
+// Features given by the caller are only enabled if `is_crate_feature` returns true for them.
+fn buildpack_cargo_build_options(
+    is_crate_feature: impl Fn(&str) -> bool,
+    libcnb_cargo_metadata: &LibcnbCargoMetadata,
+    cargo_build_options: &CargoBuildOptions,
+) -> CargoBuildOptions {
+    let mut features = libcnb_cargo_metadata.features.clone();
+    for feature in &cargo_build_options.features {
+        if is_crate_feature(feature) && !features.contains(feature) {
+            features.push(feature.clone());
+        }
+    }
+
+    CargoBuildOptions {
+        features,
+        profile: cargo_build_options
+            .profile
+            .clone()
+            .or_else(|| libcnb_cargo_metadata.profile.clone()),
+        rustflags: libcnb_cargo_metadata
+            .rustflags
+            .iter()
+            .chain(&cargo_build_options.rustflags)
+            .cloned()
+            .collect(),
+        locked: libcnb_cargo_metadata.locked || cargo_build_options.locked,
+        no_strip: cargo_build_options.no_strip,
+        cross_compile_backend: cargo_build_options.cross_compile_backend,
+    }
+}
+
 /// Builds a binary using Cargo.
 ///
 /// It is designed to handle cross-compilation without requiring custom configuration in the Cargo
@@ -83,13 +170,19 @@ pub(crate) fn build_buildpack_binaries(
 /// Depending on the host platform, this function will try to set the required cross compilation
 /// settings automatically. Please note that only selected host platforms and targets are supported.
 /// For other combinations, compilation might fail, surfacing cross-compile related errors to the
-/// user.
+/// user. Depending on [`CargoBuildOptions::cross_compile_backend`], `cargo zigbuild` or
+/// `cross build` is run instead of `cargo build`, with the same arguments.
 ///
 /// In many cases, cross-compilation requires external tools such as compilers and linkers to be
 /// installed on the user's machine. When a tool is missing, a `BuildError::CrossCompileError` is
 /// returned which provides additional information. Use the `cross_compile::cross_compile_help`
 /// function to obtain human-readable instructions on how to setup the required tools.
 ///
+/// Binaries are stripped by default, unless [`CargoBuildOptions::no_strip`] is set. Values for the
+/// `CARGO_PROFILE_<PROFILE>_STRIP` and `CARGO_PROFILE_DEV_DEBUG` variables in `cargo_env` take
+/// precedence over these defaults. Custom profiles selected via [`CargoBuildOptions::profile`] are
+/// used as configured, apart from disabling stripping.
+///
 /// This function will write Cargo's output to stdout and stderr.
 ///
 /// # Errors
@@ -99,20 +192,49 @@ fn build_binary(
     project_path: impl AsRef<Path>,
     cargo_metadata: &Metadata,
     cargo_profile: CargoProfile,
-    mut cargo_env: Vec<(OsString, OsString)>,
+    cargo_build_options: &CargoBuildOptions,
+    cargo_env: &[(OsString, OsString)],
     target_triple: impl AsRef<str>,
     target_name: impl AsRef<str>,
 ) -> Result<PathBuf, BuildError> {
-    let mut cargo_args = vec!["build", "--target", target_triple.as_ref()];
-    match cargo_profile {
-        CargoProfile::Dev => {
+    let (program, build_args) = cargo_build_options.cross_compile_backend.build_command();
+
+    let mut cargo_args = build_args
+        .iter()
+        .map(|arg| String::from(*arg))
+        .collect::<Vec<_>>();
+    cargo_args.extend([
+        String::from("--target"),
+        String::from(target_triple.as_ref()),
+    ]);
+
+    if !cargo_build_options.features.is_empty() {
+        cargo_args.push(String::from("--features"));
+        cargo_args.push(cargo_build_options.features.join(","));
+    }
+
+    if cargo_build_options.locked {
+        cargo_args.push(String::from("--locked"));
+    }
+
+    match (&cargo_build_options.profile, cargo_profile) {
+        (Some(profile), _) => cargo_args.extend([String::from("--profile"), profile.clone()]),
+        (None, CargoProfile::Dev) => {}
+        (None, CargoProfile::Release) => cargo_args.push(String::from("--release")),
+    }
+
+    let default_cargo_env = match (&cargo_build_options.profile, cargo_profile) {
+        (Some(profile), _) if cargo_build_options.no_strip => no_strip_cargo_env(profile),
+        (Some(_), _) => Vec::new(),
+        (None, CargoProfile::Dev) if cargo_build_options.no_strip => no_strip_cargo_env("dev"),
+        (None, CargoProfile::Dev) => {
             // We enable stripping for dev builds too, since debug builds are extremely
             // large and can otherwise take a long time to be Docker copied into the
             // ephemeral builder image created by `pack build` for local development
             // and integration testing workflows. Since we are stripping the builds,
             // we also disable debug symbols to improve performance slightly, since
             // they will only be stripped out at the end of the build anyway.
-            cargo_env.append(&mut vec![
+            vec![
                 (
                     OsString::from("CARGO_PROFILE_DEV_DEBUG"),
                     OsString::from("false"),
@@ -121,18 +243,22 @@ fn build_binary(
                     OsString::from("CARGO_PROFILE_DEV_STRIP"),
                     OsString::from("true"),
                 ),
-            ]);
+            ]
         }
-        CargoProfile::Release => {
-            cargo_args.push("--release");
-            cargo_env.push((
-                OsString::from("CARGO_PROFILE_RELEASE_STRIP"),
-                OsString::from("true"),
-            ));
+        (None, CargoProfile::Release) if cargo_build_options.no_strip => {
+            no_strip_cargo_env("release")
         }
-    }
+        (None, CargoProfile::Release) => vec![(
+            OsString::from("CARGO_PROFILE_RELEASE_STRIP"),
+            OsString::from("true"),
+        )],
+    };
+
+    let cargo_env = cargo_build_env(default_cargo_env, cargo_env, cargo_build_options, |key| {
+        std::env::var_os(key)
+    });
 
-    let exit_status = Command::new("cargo")
+    let exit_status = Command::new(program)
         .args(cargo_args)
         .envs(cargo_env)
         .current_dir(&project_path)
@@ -144,10 +270,13 @@ fn build_binary(
         let binary_path = cargo_metadata
             .target_directory
             .join(target_triple.as_ref())
-            .join(match cargo_profile {
-                CargoProfile::Dev => "debug",
-                CargoProfile::Release => "release",
-            })
+            .join(
+                match (cargo_build_options.profile.as_deref(), cargo_profile) {
+                    (Some("dev" | "test"), _) | (None, CargoProfile::Dev) => "debug",
+                    (Some("release" | "bench"), _) | (None, CargoProfile::Release) => "release",
+                    (Some(profile), _) => profile,
+                },
+            )
             .join(target_name.as_ref())
             .into_std_path_buf();
 
@@ -157,12 +286,118 @@ fn build_binary(
     }
 }
 
+const CROSS_BUILD_ENV_PASSTHROUGH: &str = "CROSS_BUILD_ENV_PASSTHROUGH";
+const RUSTFLAGS_SEPARATOR: &str = "\u{1f}";
+
+// Assembles the environment variables for building with Cargo from the defaults for the profile,
+// the variables given by the caller and the build options. Values of variables that are already
+// set in the environment of this process are looked up with `inherited_env`.
+fn cargo_build_env(
+    default_cargo_env: Vec<(OsString, OsString)>,
+    cargo_env: &[(OsString, OsString)],
+    cargo_build_options: &CargoBuildOptions,
+    inherited_env: impl Fn(&str) -> Option<OsString>,
+) -> Vec<(OsString, OsString)> {
+    // Later values for the same variable win, so the defaults are only added when they aren't
+    // already set by the caller.
+    let mut cargo_env = default_cargo_env
+        .into_iter()
+        .filter(|(default_key, _)| !cargo_env.iter().any(|(key, _)| key == default_key))
+        .chain(cargo_env.iter().cloned())
+        .collect::<Vec<_>>();
+
+    if !cargo_build_options.rustflags.is_empty() {
+        let env_value = |key: &str| {
+            cargo_env
+                .iter()
+                .rev()
+                .find(|(env_key, _)| env_key == key)
+                .map(|(_, value)| value.clone())
+                .or_else(|| inherited_env(key))
+        };
+
+        // Cargo uses `CARGO_ENCODED_RUSTFLAGS` instead of `RUSTFLAGS` if both are set. The encoded
+        // variant separates flags with the ASCII unit separator, so flags can contain spaces.
+        let existing_rustflags = env_value("CARGO_ENCODED_RUSTFLAGS")
+            .map(|value| {
+                value
+                    .to_string_lossy()
+                    .split(RUSTFLAGS_SEPARATOR)
+                    .map(String::from)
+                    .collect::<Vec<_>>()
+            })
+            .or_else(|| {
+                env_value("RUSTFLAGS").map(|value| {
+                    value
+                        .to_string_lossy()
+                        .split_whitespace()
+                        .map(String::from)
+                        .collect()
+                })
+            })
+            .unwrap_or_default();
+
+        let rustflags = existing_rustflags
+            .iter()
+            .chain(&cargo_build_options.rustflags)
+            .filter(|flag| !flag.is_empty())
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(RUSTFLAGS_SEPARATOR);
+
+        cargo_env.push((
+            OsString::from("CARGO_ENCODED_RUSTFLAGS"),
+            OsString::from(rustflags),
+        ));
+    }
+
+    if cargo_build_options.cross_compile_backend == CrossCompileBackend::Cross {
+        // cross runs Cargo in a container, which only sees the environment variables it is told
+        // to pass through.
+        let passthrough = inherited_env(CROSS_BUILD_ENV_PASSTHROUGH)
+            .into_iter()
+            .chain(cargo_env.iter().map(|(key, _)| key.clone()))
+            .filter(|value| !value.is_empty())
+            .collect::<Vec<_>>()
+            .join(OsStr::new(" "));
+
+        cargo_env.push((OsString::from(CROSS_BUILD_ENV_PASSTHROUGH), passthrough));
+    }
+
+    cargo_env
+}
+
+// Cargo environment variables that disable stripping (and, for the dev profile, enable debug
+// info) for the profile with the given name, as used with `cargo build --profile`.
+fn no_strip_cargo_env(cargo_profile_name: &str) -> Vec<(OsString, OsString)> {
+    let env_profile_name = cargo_profile_name.to_uppercase().replace('-', "_");
+    let strip = (
+        OsString::from(format!("CARGO_PROFILE_{env_profile_name}_STRIP")),
+        OsString::from("false"),
+    );
+
+    if cargo_profile_name == "dev" {
+        vec![
+            (
+                OsString::from("CARGO_PROFILE_DEV_DEBUG"),
+                OsString::from("true"),
+            ),
+            strip,
+        ]
+    } else {
+        vec![strip]
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct BuildpackBinaries {
     /// The path to the main buildpack binary
     pub(crate) buildpack_target_binary_path: PathBuf,
     /// Paths to additional binaries from the buildpack
     pub(crate) additional_target_binary_paths: HashMap<String, PathBuf>,
+    /// Paths of the additional binaries configured in Cargo metadata within the packaged
+    /// buildpack, relative to its root
+    pub(crate) additional_binary_packaged_paths: BTreeMap<String, PathBuf>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -181,4 +416,243 @@ pub enum BuildBinariesError {
     BuildError(String, #[source] BuildError),
     #[error("Binary target {0} couldn't be found")]
     MissingBuildpackTarget(String),
+    #[error("Invalid additional binary configuration: {0}")]
+    InvalidAdditionalBinary(#[source] InvalidAdditionalBinaryError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        buildpack_cargo_build_options, cargo_build_env, no_strip_cargo_env, CargoBuildOptions,
+    };
+    use crate::cargo::LibcnbCargoMetadata;
+    use crate::cross_compile::CrossCompileBackend;
+    use std::ffi::OsString;
+
+    fn env(variables: &[(&str, &str)]) -> Vec<(OsString, OsString)> {
+        variables
+            .iter()
+            .map(|(key, value)| (OsString::from(key), OsString::from(value)))
+            .collect()
+    }
+
+    #[test]
+    fn no_strip_cargo_env_for_profiles() {
+        assert_eq!(
+            no_strip_cargo_env("dev"),
+            vec![
+                (
+                    OsString::from("CARGO_PROFILE_DEV_DEBUG"),
+                    OsString::from("true")
+                ),
+                (
+                    OsString::from("CARGO_PROFILE_DEV_STRIP"),
+                    OsString::from("false")
+                ),
+            ]
+        );
+
+        assert_eq!(
+            no_strip_cargo_env("release"),
+            vec![(
+                OsString::from("CARGO_PROFILE_RELEASE_STRIP"),
+                OsString::from("false")
+            )]
+        );
+
+        assert_eq!(
+            no_strip_cargo_env("release-lto"),
+            vec![(
+                OsString::from("CARGO_PROFILE_RELEASE_LTO_STRIP"),
+                OsString::from("false")
+            )]
+        );
+    }
+
+    #[test]
+    fn cargo_build_env_prefers_caller_variables_over_defaults() {
+        assert_eq!(
+            cargo_build_env(
+                env(&[
+                    ("CARGO_PROFILE_DEV_DEBUG", "false"),
+                    ("CARGO_PROFILE_DEV_STRIP", "true")
+                ]),
+                &env(&[("CARGO_PROFILE_DEV_STRIP", "false")]),
+                &CargoBuildOptions::default(),
+                |_| None,
+            ),
+            env(&[
+                ("CARGO_PROFILE_DEV_DEBUG", "false"),
+                ("CARGO_PROFILE_DEV_STRIP", "false")
+            ])
+        );
+    }
+
+    #[test]
+    fn cargo_build_env_passes_variables_through_to_cross() {
+        let cargo_build_options = CargoBuildOptions {
+            cross_compile_backend: CrossCompileBackend::Cross,
+            ..CargoBuildOptions::default()
+        };
+
+        assert_eq!(
+            cargo_build_env(
+                env(&[("CARGO_PROFILE_RELEASE_STRIP", "true")]),
+                &env(&[("CC_aarch64_unknown_linux_musl", "aarch64-linux-gnu-gcc")]),
+                &cargo_build_options,
+                |key| (key == "CROSS_BUILD_ENV_PASSTHROUGH").then(|| OsString::from("FOO BAR")),
+            ),
+            env(&[
+                ("CARGO_PROFILE_RELEASE_STRIP", "true"),
+                ("CC_aarch64_unknown_linux_musl", "aarch64-linux-gnu-gcc"),
+                (
+                    "CROSS_BUILD_ENV_PASSTHROUGH",
+                    "FOO BAR CARGO_PROFILE_RELEASE_STRIP CC_aarch64_unknown_linux_musl"
+                ),
+            ])
+        );
+
+        // An empty value in the environment isn't passed on.
+        assert_eq!(
+            cargo_build_env(
+                Vec::new(),
+                &env(&[("CARGO_PROFILE_RELEASE_STRIP", "false")]),
+                &cargo_build_options,
+                |key| (key == "CROSS_BUILD_ENV_PASSTHROUGH").then(OsString::new),
+            ),
+            env(&[
+                ("CARGO_PROFILE_RELEASE_STRIP", "false"),
+                ("CROSS_BUILD_ENV_PASSTHROUGH", "CARGO_PROFILE_RELEASE_STRIP"),
+            ])
+        );
+    }
+
+    #[test]
+    fn cargo_build_env_without_cross_has_no_passthrough() {
+        assert_eq!(
+            cargo_build_env(
+                Vec::new(),
+                &env(&[("FOO", "bar")]),
+                &CargoBuildOptions::default(),
+                |_| Some(OsString::from("BAZ")),
+            ),
+            env(&[("FOO", "bar")])
+        );
+    }
+
+    #[test]
+    fn buildpack_cargo_build_options_combine_caller_and_metadata_options() {
+        let libcnb_cargo_metadata = LibcnbCargoMetadata {
+            features: vec![String::from("trace")],
+            profile: Some(String::from("release-lto")),
+            rustflags: vec![String::from("-C"), String::from("target-cpu=x86-64-v3")],
+            locked: true,
+            ..LibcnbCargoMetadata::default()
+        };
+
+        let cargo_build_options = CargoBuildOptions {
+            features: vec![
+                String::from("trace"),
+                String::from("metrics"),
+                String::from("undefined"),
+            ],
+            profile: Some(String::from("release-debug")),
+            rustflags: vec![String::from("-Dwarnings")],
+            locked: false,
+            no_strip: true,
+            cross_compile_backend: CrossCompileBackend::Zigbuild,
+        };
+
+        assert_eq!(
+            buildpack_cargo_build_options(
+                |feature| ["trace", "metrics"].contains(&feature),
+                &libcnb_cargo_metadata,
+                &cargo_build_options,
+            ),
+            CargoBuildOptions {
+                // Features the crate doesn't define are silently left out.
+                features: vec![String::from("trace"), String::from("metrics")],
+                profile: Some(String::from("release-debug")),
+                rustflags: vec![
+                    String::from("-C"),
+                    String::from("target-cpu=x86-64-v3"),
+                    String::from("-Dwarnings"),
+                ],
+                locked: true,
+                no_strip: true,
+                cross_compile_backend: CrossCompileBackend::Zigbuild,
+            }
+        );
+    }
+
+    #[test]
+    fn buildpack_cargo_build_options_fall_back_to_metadata_options() {
+        let libcnb_cargo_metadata = LibcnbCargoMetadata {
+            profile: Some(String::from("release-lto")),
+            ..LibcnbCargoMetadata::default()
+        };
+
+        let cargo_build_options = buildpack_cargo_build_options(
+            |_| true,
+            &libcnb_cargo_metadata,
+            &CargoBuildOptions {
+                locked: true,
+                ..CargoBuildOptions::default()
+            },
+        );
+
+        assert_eq!(
+            cargo_build_options.profile,
+            Some(String::from("release-lto"))
+        );
+        assert!(cargo_build_options.locked);
+    }
+
+    #[test]
+    fn cargo_build_env_appends_rustflags() {
+        let cargo_build_options = CargoBuildOptions {
+            rustflags: vec![
+                String::from("-C"),
+                String::from("link-arg=-Wl,--as-needed x"),
+            ],
+            ..CargoBuildOptions::default()
+        };
+
+        // Flags in RUSTFLAGS are split at whitespace, the configured flags are kept intact.
+        assert_eq!(
+            cargo_build_env(Vec::new(), &[], &cargo_build_options, |key| {
+                (key == "RUSTFLAGS").then(|| OsString::from("-Dwarnings  -Cdebuginfo=1"))
+            }),
+            env(&[(
+                "CARGO_ENCODED_RUSTFLAGS",
+                "-Dwarnings\u{1f}-Cdebuginfo=1\u{1f}-C\u{1f}link-arg=-Wl,--as-needed x"
+            )])
+        );
+
+        // Variables given by the caller take precedence over the ones of this process, and
+        // CARGO_ENCODED_RUSTFLAGS takes precedence over RUSTFLAGS, like in Cargo.
+        assert_eq!(
+            cargo_build_env(
+                Vec::new(),
+                &env(&[("RUSTFLAGS", "-Dwarnings")]),
+                &cargo_build_options,
+                |key| (key == "CARGO_ENCODED_RUSTFLAGS").then(|| OsString::from("-Ca b\u{1f}-Cc")),
+            ),
+            env(&[
+                ("RUSTFLAGS", "-Dwarnings"),
+                (
+                    "CARGO_ENCODED_RUSTFLAGS",
+                    "-Ca b\u{1f}-Cc\u{1f}-C\u{1f}link-arg=-Wl,--as-needed x"
+                ),
+            ])
+        );
+
+        // Without configured flags, the environment is left alone.
+        assert_eq!(
+            cargo_build_env(Vec::new(), &[], &CargoBuildOptions::default(), |_| Some(
+                OsString::from("-Dwarnings")
+            ),),
+            Vec::new()
+        );
+    }
 }
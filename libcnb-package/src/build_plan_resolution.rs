@@ -0,0 +1,456 @@
+use libcnb_data::build_plan::{BuildPlan, Provide, Require};
+use libcnb_data::buildpack::{BuildpackId, Group};
+use libcnb_data::buildpack_plan::{BuildpackPlan, Entry};
+use std::collections::HashMap;
+
+/// Resolves the build plans of the buildpacks of a group the same way the lifecycle does.
+///
+/// The given buildpacks are expected to be those of a group that passed detection, in group order.
+/// Every combination of build plan alternatives (the primary plan, followed by any `[[or]]`
+/// alternatives) is tried in order, until one resolves:
+///
+/// - Every require must be matched by a provide of the same or a preceding buildpack.
+/// - Every provide must be matched by a require of the same or a subsequent buildpack.
+///
+/// Optional buildpacks with unmatched provides or requires are removed, required ones cause the
+/// combination to fail. If no combination resolves, the errors of the last combination are
+/// returned.
+///
+/// Each remaining buildpack receives a [`BuildpackPlan`] with an entry for each require matched by
+/// one of its provides.
+///
+/// # Example
+/// ```
+/// use libcnb_data::build_plan::BuildPlanBuilder;
+/// use libcnb_data::buildpack::{BuildpackVersion, Group};
+/// use libcnb_data::buildpack_id;
+/// use libcnb_package::build_plan_resolution::resolve_build_plans;
+///
+/// let group = |id| Group {
+///     id,
+///     version: BuildpackVersion::new(1, 0, 0),
+///     optional: false,
+/// };
+///
+/// let nodejs_engine = group(buildpack_id!("example/nodejs-engine"));
+/// let npm = group(buildpack_id!("example/npm"));
+///
+/// let nodejs_engine_build_plan = BuildPlanBuilder::new().provides("node").build();
+/// let npm_build_plan = BuildPlanBuilder::new().requires("node").build();
+///
+/// let resolved_build_plans = resolve_build_plans(&[
+///     (&nodejs_engine, &nodejs_engine_build_plan),
+///     (&npm, &npm_build_plan),
+/// ])
+/// .unwrap();
+///
+/// let buildpack_plan = &resolved_build_plans.buildpack_plans[&nodejs_engine.id];
+/// assert_eq!(buildpack_plan.entries.len(), 1);
+/// assert_eq!(buildpack_plan.entries[0].name, "node");
+/// ```
+///
+/// # Errors
+///
+/// Will return `Err` if no combination of build plan alternatives resolves.
+pub fn resolve_build_plans(
+    buildpacks: &[(&Group, &BuildPlan)],
+) -> Result<ResolvedBuildPlans, Vec<BuildPlanResolutionError>> {
+    let options = buildpacks
+        .iter()
+        .map(|(group_entry, build_plan)| {
+            std::iter::once((
+                build_plan.provides.as_slice(),
+                build_plan.requires.as_slice(),
+            ))
+            .chain(
+                build_plan
+                    .or
+                    .iter()
+                    .map(|or| (or.provides.as_slice(), or.requires.as_slice())),
+            )
+            .map(|(provides, requires)| TrialEntry {
+                group_entry,
+                provides,
+                requires,
+            })
+            .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let trial = run_trials(&options, &mut Vec::new())?;
+
+    let mut buildpack_plans = trial
+        .iter()
+        .map(|entry| {
+            (
+                entry.group_entry.id.clone(),
+                BuildpackPlan {
+                    entries: Vec::new(),
+                },
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    for (name, dependency) in DependencyMap::new(&trial).dependencies {
+        for provider_index in dependency.providers {
+            if let Some(buildpack_plan) =
+                buildpack_plans.get_mut(&trial[provider_index].group_entry.id)
+            {
+                buildpack_plan
+                    .entries
+                    .extend(dependency.requires.iter().map(|require| Entry {
+                        name: String::from(name),
+                        metadata: require.metadata.clone(),
+                    }));
+            }
+        }
+    }
+
+    Ok(ResolvedBuildPlans {
+        buildpacks: trial
+            .iter()
+            .map(|entry| entry.group_entry.clone())
+            .collect(),
+        skipped_buildpacks: buildpacks
+            .iter()
+            .filter(|(group_entry, _)| {
+                !trial
+                    .iter()
+                    .any(|entry| entry.group_entry.id == group_entry.id)
+            })
+            .map(|(group_entry, _)| group_entry.id.clone())
+            .collect(),
+        buildpack_plans,
+    })
+}
+
+/// The result of [`resolve_build_plans`].
+#[derive(Debug)]
+pub struct ResolvedBuildPlans {
+    /// The buildpacks that take part in the build, in order.
+    pub buildpacks: Vec<Group>,
+    /// The optional buildpacks that were removed because of unmatched provides or requires.
+    pub skipped_buildpacks: Vec<BuildpackId>,
+    /// The buildpack plan each buildpack in [`buildpacks`](Self::buildpacks) receives.
+    pub buildpack_plans: HashMap<BuildpackId, BuildpackPlan>,
+}
+
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
+pub enum BuildPlanResolutionError {
+    #[error("Required buildpack {buildpack_id} requires `{name}`, which is not provided by it or a preceding buildpack")]
+    UnmetRequire {
+        buildpack_id: BuildpackId,
+        name: String,
+    },
+    #[error("Required buildpack {buildpack_id} provides `{name}`, which is not required by it or a subsequent buildpack")]
+    UnmetProvide {
+        buildpack_id: BuildpackId,
+        name: String,
+    },
+    #[error("No buildpacks in the group passed detection")]
+    NoViableBuildpacks,
+}
+
+/// A single buildpack with one of its build plan alternatives.
+#[derive(Clone)]
+struct TrialEntry<'a> {
+    group_entry: &'a Group,
+    provides: &'a [Provide],
+    requires: &'a [Require],
+}
+
+// Tries all combinations of build plan alternatives depth-first, in the same order as the
+// lifecycle, returning the first combination that resolves or the errors of the last one.
+fn run_trials<'a>(
+    options: &[Vec<TrialEntry<'a>>],
+    prefix: &mut Vec<TrialEntry<'a>>,
+) -> Result<Vec<TrialEntry<'a>>, Vec<BuildPlanResolutionError>> {
+    let Some((first, rest)) = options.split_first() else {
+        return run_trial(prefix.clone());
+    };
+
+    let mut last_errors = Vec::new();
+
+    for option in first {
+        prefix.push(option.clone());
+        let result = run_trials(rest, prefix);
+        prefix.pop();
+
+        match result {
+            Ok(trial) => return Ok(trial),
+            Err(errors) => last_errors = errors,
+        }
+    }
+
+    Err(last_errors)
+}
+
+fn run_trial(
+    mut trial: Vec<TrialEntry<'_>>,
+) -> Result<Vec<TrialEntry<'_>>, Vec<BuildPlanResolutionError>> {
+    loop {
+        let unmet = DependencyMap::new(&trial).unmet(&trial);
+
+        if unmet.is_empty() {
+            break;
+        }
+
+        let errors = unmet
+            .iter()
+            .filter(|(index, _)| !trial[*index].group_entry.optional)
+            .map(|(_, error)| error.clone())
+            .collect::<Vec<_>>();
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        // Only optional buildpacks are left with unmet provides or requires, remove them and retry
+        // since their removal might cause other buildpacks to have unmet provides or requires.
+        let mut index = 0;
+        trial.retain(|_| {
+            let keep = !unmet.iter().any(|(unmet_index, _)| *unmet_index == index);
+            index += 1;
+            keep
+        });
+    }
+
+    if trial.is_empty() {
+        Err(vec![BuildPlanResolutionError::NoViableBuildpacks])
+    } else {
+        Ok(trial)
+    }
+}
+
+/// The provides and requires of a trial, grouped by name. Mirrors the lifecycle's `depMap`.
+struct DependencyMap<'a> {
+    // Kept in order of first appearance so that buildpack plan entries have a stable order.
+    dependencies: Vec<(&'a str, Dependency<'a>)>,
+}
+
+#[derive(Default)]
+struct Dependency<'a> {
+    // Indices of buildpacks whose provides have been matched by a require.
+    providers: Vec<usize>,
+    // Indices of buildpacks whose provides have not (yet) been matched by a require.
+    extra_provides: Vec<usize>,
+    // Indices of buildpacks that required the dependency before anything provided it.
+    early_requires: Vec<usize>,
+    // Requires that were matched by a preceding provide.
+    requires: Vec<&'a Require>,
+}
+
+impl<'a> DependencyMap<'a> {
+    fn new(trial: &[TrialEntry<'a>]) -> Self {
+        let mut dependency_map = Self {
+            dependencies: Vec::new(),
+        };
+
+        for (index, entry) in trial.iter().enumerate() {
+            for provide in entry.provides {
+                dependency_map
+                    .dependency_mut(&provide.name)
+                    .extra_provides
+                    .push(index);
+            }
+
+            for require in entry.requires {
+                let dependency = dependency_map.dependency_mut(&require.name);
+                let extra_provides = std::mem::take(&mut dependency.extra_provides);
+                dependency.providers.extend(extra_provides);
+
+                if dependency.providers.is_empty() {
+                    dependency.early_requires.push(index);
+                } else {
+                    dependency.requires.push(require);
+                }
+            }
+        }
+
+        dependency_map
+    }
+
+    fn dependency_mut(&mut self, name: &'a str) -> &mut Dependency<'a> {
+        let position = self
+            .dependencies
+            .iter()
+            .position(|(dependency_name, _)| *dependency_name == name)
+            .unwrap_or_else(|| {
+                self.dependencies.push((name, Dependency::default()));
+                self.dependencies.len() - 1
+            });
+
+        &mut self.dependencies[position].1
+    }
+
+    // Returns the unmet provides and requires, together with the index of the buildpack they
+    // belong to, ordered by that index.
+    fn unmet(&self, trial: &[TrialEntry<'_>]) -> Vec<(usize, BuildPlanResolutionError)> {
+        let mut unmet = Vec::new();
+
+        for (name, dependency) in &self.dependencies {
+            for index in &dependency.early_requires {
+                unmet.push((
+                    *index,
+                    BuildPlanResolutionError::UnmetRequire {
+                        buildpack_id: trial[*index].group_entry.id.clone(),
+                        name: String::from(*name),
+                    },
+                ));
+            }
+
+            for index in &dependency.extra_provides {
+                unmet.push((
+                    *index,
+                    BuildPlanResolutionError::UnmetProvide {
+                        buildpack_id: trial[*index].group_entry.id.clone(),
+                        name: String::from(*name),
+                    },
+                ));
+            }
+        }
+
+        // Sorting is stable, so within a buildpack the unmet requires come first.
+        unmet.sort_by_key(|(index, error)| {
+            (
+                *index,
+                !matches!(error, BuildPlanResolutionError::UnmetRequire { .. }),
+            )
+        });
+        unmet
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libcnb_data::build_plan::BuildPlanBuilder;
+    use libcnb_data::buildpack::BuildpackVersion;
+    use libcnb_data::buildpack_id;
+
+    fn group(id: BuildpackId, optional: bool) -> Group {
+        Group {
+            id,
+            version: BuildpackVersion::new(1, 0, 0),
+            optional,
+        }
+    }
+
+    fn entry_names(resolved: &ResolvedBuildPlans, id: &BuildpackId) -> Vec<String> {
+        resolved.buildpack_plans[id]
+            .entries
+            .iter()
+            .map(|entry| entry.name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn requires_are_passed_to_providers() {
+        let a = group(buildpack_id!("a"), false);
+        let b = group(buildpack_id!("b"), false);
+        let c = group(buildpack_id!("c"), false);
+
+        let mut require = Require::new("node");
+        require
+            .metadata(HashMap::from([("version", "22.x")]))
+            .unwrap();
+        let require_metadata = require.metadata.clone();
+
+        let a_build_plan = BuildPlanBuilder::new().provides("node").build();
+        let b_build_plan = BuildPlanBuilder::new().requires(require).build();
+        let c_build_plan = BuildPlanBuilder::new()
+            .provides("yarn")
+            .requires("node")
+            .requires("yarn")
+            .build();
+
+        let resolved = resolve_build_plans(&[
+            (&a, &a_build_plan),
+            (&b, &b_build_plan),
+            (&c, &c_build_plan),
+        ])
+        .unwrap();
+
+        assert_eq!(entry_names(&resolved, &a.id), vec!["node", "node"]);
+        assert_eq!(
+            resolved.buildpack_plans[&a.id].entries[0].metadata,
+            require_metadata
+        );
+        assert!(entry_names(&resolved, &b.id).is_empty());
+        assert_eq!(entry_names(&resolved, &c.id), vec!["yarn"]);
+        assert_eq!(resolved.buildpacks, vec![a, b, c]);
+        assert!(resolved.skipped_buildpacks.is_empty());
+    }
+
+    #[test]
+    fn or_alternatives_are_tried_in_order() {
+        let a = group(buildpack_id!("a"), false);
+        let b = group(buildpack_id!("b"), false);
+
+        // The first alternative of `a` doesn't resolve, since nothing requires `node`.
+        let a_build_plan = BuildPlanBuilder::new()
+            .provides("node")
+            .or()
+            .provides("node")
+            .requires("node")
+            .build();
+        let b_build_plan = BuildPlan::new();
+
+        let resolved = resolve_build_plans(&[(&a, &a_build_plan), (&b, &b_build_plan)]).unwrap();
+
+        assert_eq!(entry_names(&resolved, &a.id), vec!["node"]);
+    }
+
+    #[test]
+    fn unmet_requires_and_provides_are_reported() {
+        let a = group(buildpack_id!("a"), false);
+        let b = group(buildpack_id!("b"), false);
+
+        let a_build_plan = BuildPlanBuilder::new().requires("node").build();
+        let b_build_plan = BuildPlanBuilder::new().provides("node").build();
+
+        assert_eq!(
+            resolve_build_plans(&[(&a, &a_build_plan), (&b, &b_build_plan)]).unwrap_err(),
+            vec![
+                BuildPlanResolutionError::UnmetRequire {
+                    buildpack_id: a.id.clone(),
+                    name: String::from("node"),
+                },
+                BuildPlanResolutionError::UnmetProvide {
+                    buildpack_id: b.id.clone(),
+                    name: String::from("node"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn optional_buildpacks_with_unmet_entries_are_skipped() {
+        let a = group(buildpack_id!("a"), false);
+        let b = group(buildpack_id!("b"), true);
+
+        let a_build_plan = BuildPlanBuilder::new()
+            .provides("node")
+            .requires("node")
+            .build();
+        let b_build_plan = BuildPlanBuilder::new().requires("python").build();
+
+        let resolved = resolve_build_plans(&[(&a, &a_build_plan), (&b, &b_build_plan)]).unwrap();
+
+        assert_eq!(resolved.buildpacks, vec![a.clone()]);
+        assert_eq!(resolved.skipped_buildpacks, vec![b.id.clone()]);
+        assert!(!resolved.buildpack_plans.contains_key(&b.id));
+        assert_eq!(entry_names(&resolved, &a.id), vec!["node"]);
+    }
+
+    #[test]
+    fn no_viable_buildpacks() {
+        let a = group(buildpack_id!("a"), true);
+        let a_build_plan = BuildPlanBuilder::new().requires("node").build();
+
+        assert_eq!(
+            resolve_build_plans(&[(&a, &a_build_plan)]).unwrap_err(),
+            vec![BuildPlanResolutionError::NoViableBuildpacks]
+        );
+    }
+}
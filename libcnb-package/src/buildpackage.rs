@@ -0,0 +1,664 @@
+use crate::multi_target::TargetPlatform;
+use crate::output::default_buildpack_directory_name;
+use crate::reproducible::{normalized_file_mode, NORMALIZED_TIMESTAMP};
+use libcnb_common::toml_file::{read_toml_file, TomlFileError};
+use libcnb_data::buildpack::{
+    BuildpackApi, BuildpackDescriptor, BuildpackId, BuildpackTarget, BuildpackVersion, Order, Stack,
+};
+use libcnb_data::package_descriptor::PackageDescriptor;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Writes a buildpackage archive (`.cnb` file) for a packaged buildpack directory.
+///
+/// The archive contains an [OCI image layout](https://github.com/opencontainers/image-spec/blob/main/image-layout.md)
+/// with a single image, the same format `pack buildpack package --format file` produces. The image
+/// contains one layer per buildpack (the given buildpack and, for composite buildpacks, all
+/// buildpacks referenced in its `package.toml`) and the `io.buildpacks.buildpackage.metadata` and
+/// `io.buildpacks.buildpack.layers` labels.
+///
+/// Dependencies of composite buildpacks must refer to local packaged buildpack directories, as is
+/// the case for composite buildpacks packaged with [`crate::package::package_buildpack`].
+///
+/// # Errors
+///
+/// Will return `Err` if reading the buildpacks, building the layers or writing the archive failed.
+pub fn write_buildpackage(
+    packaged_buildpack_dir: &Path,
+    target_platform: &TargetPlatform,
+    destination: &Path,
+) -> Result<(), WriteBuildpackageError> {
+    let mut buildpacks = Vec::new();
+    collect_buildpacks(packaged_buildpack_dir, &mut buildpacks)?;
+
+    let root_buildpack_descriptor = buildpacks
+        .last()
+        .map(|(_, buildpack_descriptor)| buildpack_descriptor.clone())
+        .ok_or(WriteBuildpackageError::NoBuildpacks)?;
+
+    let mut blobs = Vec::new();
+    let mut layer_descriptors = Vec::new();
+    let mut diff_ids = Vec::new();
+    let mut layers_label: BTreeMap<String, BTreeMap<String, LayerMetadata>> = BTreeMap::new();
+
+    for (buildpack_dir, buildpack_descriptor) in &buildpacks {
+        let buildpack = buildpack_descriptor.buildpack();
+
+        let layer = create_buildpack_layer(buildpack_dir, &buildpack.id, &buildpack.version)
+            .map_err(|error| {
+                WriteBuildpackageError::CannotCreateLayer(buildpack_dir.clone(), error)
+            })?;
+
+        let layer_digest = sha256_digest(&layer);
+        layer_descriptors.push(OciDescriptor {
+            media_type: OCI_LAYER_MEDIA_TYPE,
+            digest: layer_digest.clone(),
+            size: layer.len(),
+        });
+        // Layers are not compressed, so the diff ID is the same as the layer digest.
+        diff_ids.push(layer_digest.clone());
+        blobs.push((layer_digest.clone(), layer));
+
+        layers_label
+            .entry(buildpack.id.to_string())
+            .or_default()
+            .insert(
+                buildpack.version.to_string(),
+                layer_metadata(buildpack_descriptor, layer_digest),
+            );
+    }
+
+    let config = create_image_config(
+        &root_buildpack_descriptor,
+        target_platform,
+        &layers_label,
+        diff_ids,
+    )
+    .map_err(WriteBuildpackageError::CannotSerializeJson)?;
+
+    let config_digest = sha256_digest(&config);
+    let manifest = serde_json::to_vec(&OciManifest {
+        schema_version: 2,
+        media_type: OCI_MANIFEST_MEDIA_TYPE,
+        config: OciDescriptor {
+            media_type: OCI_CONFIG_MEDIA_TYPE,
+            digest: config_digest.clone(),
+            size: config.len(),
+        },
+        layers: layer_descriptors,
+    })
+    .map_err(WriteBuildpackageError::CannotSerializeJson)?;
+    blobs.push((config_digest, config));
+
+    let manifest_digest = sha256_digest(&manifest);
+    let index = serde_json::to_vec(&OciIndex {
+        schema_version: 2,
+        manifests: vec![OciDescriptor {
+            media_type: OCI_MANIFEST_MEDIA_TYPE,
+            digest: manifest_digest.clone(),
+            size: manifest.len(),
+        }],
+    })
+    .map_err(WriteBuildpackageError::CannotSerializeJson)?;
+    blobs.push((manifest_digest, manifest));
+
+    write_oci_layout_archive(destination, &index, &blobs)
+        .map_err(WriteBuildpackageError::CannotWriteArchive)
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum WriteBuildpackageError {
+    #[error("Couldn't read buildpack.toml: {0}")]
+    CannotReadBuildpackDescriptor(TomlFileError),
+    #[error("Couldn't read package.toml: {0}")]
+    CannotReadPackageDescriptor(TomlFileError),
+    #[error("Dependency {0} is not supported, only local packaged buildpack directories can be included in a buildpackage")]
+    UnsupportedDependency(String),
+    #[error("No buildpacks to include in buildpackage")]
+    NoBuildpacks,
+    #[error("Couldn't create layer for buildpack {}: {1}", .0.display())]
+    CannotCreateLayer(PathBuf, std::io::Error),
+    #[error("Couldn't serialize JSON: {0}")]
+    CannotSerializeJson(serde_json::Error),
+    #[error("Couldn't write buildpackage archive: {0}")]
+    CannotWriteArchive(std::io::Error),
+}
+
+// Collects the buildpack in the given directory and its dependencies, with dependencies first.
+// Buildpacks that are referenced more than once are only included once.
+fn collect_buildpacks(
+    buildpack_dir: &Path,
+    buildpacks: &mut Vec<(PathBuf, BuildpackDescriptor)>,
+) -> Result<(), WriteBuildpackageError> {
+    let buildpack_descriptor =
+        read_toml_file::<BuildpackDescriptor>(buildpack_dir.join("buildpack.toml"))
+            .map_err(WriteBuildpackageError::CannotReadBuildpackDescriptor)?;
+
+    if let BuildpackDescriptor::Composite(_) = buildpack_descriptor {
+        let package_descriptor =
+            read_toml_file::<PackageDescriptor>(buildpack_dir.join("package.toml"))
+                .map_err(WriteBuildpackageError::CannotReadPackageDescriptor)?;
+
+        for dependency in &package_descriptor.dependencies {
+            if dependency.uri.scheme().is_some() {
+                return Err(WriteBuildpackageError::UnsupportedDependency(
+                    dependency.uri.to_string(),
+                ));
+            }
+
+            collect_buildpacks(
+                &buildpack_dir.join(dependency.uri.path().to_string()),
+                buildpacks,
+            )?;
+        }
+    }
+
+    let buildpack = buildpack_descriptor.buildpack();
+    let already_collected = buildpacks.iter().any(|(_, collected)| {
+        collected.buildpack().id == buildpack.id
+            && collected.buildpack().version == buildpack.version
+    });
+
+    if !already_collected {
+        buildpacks.push((buildpack_dir.to_path_buf(), buildpack_descriptor));
+    }
+
+    Ok(())
+}
+
+fn layer_metadata(
+    buildpack_descriptor: &BuildpackDescriptor,
+    layer_diff_id: String,
+) -> LayerMetadata {
+    let buildpack = buildpack_descriptor.buildpack();
+
+    match buildpack_descriptor {
+        BuildpackDescriptor::Component(descriptor) => LayerMetadata {
+            api: descriptor.api,
+            stacks: descriptor.stacks.clone(),
+            targets: descriptor.targets.clone(),
+            order: None,
+            layer_diff_id,
+            homepage: buildpack.homepage.clone(),
+            name: buildpack.name.clone(),
+        },
+        BuildpackDescriptor::Composite(descriptor) => LayerMetadata {
+            api: descriptor.api,
+            stacks: Vec::new(),
+            targets: Vec::new(),
+            order: Some(descriptor.order.clone()),
+            layer_diff_id,
+            homepage: buildpack.homepage.clone(),
+            name: buildpack.name.clone(),
+        },
+    }
+}
+
+fn create_image_config(
+    root_buildpack_descriptor: &BuildpackDescriptor,
+    target_platform: &TargetPlatform,
+    layers_label: &BTreeMap<String, BTreeMap<String, LayerMetadata>>,
+    diff_ids: Vec<String>,
+) -> Result<Vec<u8>, serde_json::Error> {
+    let root_buildpack = root_buildpack_descriptor.buildpack();
+    let buildpackage_metadata_label = BuildpackageMetadata {
+        id: root_buildpack.id.to_string(),
+        name: root_buildpack.name.clone(),
+        version: root_buildpack.version.to_string(),
+        homepage: root_buildpack.homepage.clone(),
+        stacks: match root_buildpack_descriptor {
+            BuildpackDescriptor::Component(descriptor) => descriptor.stacks.clone(),
+            BuildpackDescriptor::Composite(_) => Vec::new(),
+        },
+    };
+
+    serde_json::to_vec(&OciImageConfig {
+        architecture: target_platform.arch.clone(),
+        os: target_platform.os.clone(),
+        variant: target_platform.variant.clone(),
+        created: NORMALIZED_TIMESTAMP_RFC3339,
+        config: OciImageConfigConfig {
+            labels: BTreeMap::from([
+                (
+                    BUILDPACKAGE_METADATA_LABEL,
+                    serde_json::to_string(&buildpackage_metadata_label)?,
+                ),
+                (BUILDPACK_LAYERS_LABEL, serde_json::to_string(layers_label)?),
+            ]),
+        },
+        rootfs: OciRootfs {
+            r#type: "layers",
+            diff_ids,
+        },
+    })
+}
+
+// Creates an uncompressed tar layer that contains the buildpack at
+// `/cnb/buildpacks/<id>/<version>`, the location the lifecycle expects buildpacks at.
+fn create_buildpack_layer(
+    buildpack_dir: &Path,
+    buildpack_id: &BuildpackId,
+    buildpack_version: &BuildpackVersion,
+) -> std::io::Result<Vec<u8>> {
+    let mut builder = tar::Builder::new(Vec::new());
+    let buildpack_layer_path = PathBuf::from("cnb")
+        .join("buildpacks")
+        .join(default_buildpack_directory_name(buildpack_id))
+        .join(buildpack_version.to_string());
+
+    for ancestor in buildpack_layer_path
+        .ancestors()
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .skip(1)
+    {
+        append_directory(&mut builder, ancestor)?;
+    }
+
+    append_directory_contents(&mut builder, buildpack_dir, &buildpack_layer_path, true)?;
+
+    builder.into_inner()
+}
+
+fn append_directory_contents(
+    builder: &mut tar::Builder<Vec<u8>>,
+    source_dir: &Path,
+    archive_dir: &Path,
+    is_buildpack_root: bool,
+) -> std::io::Result<()> {
+    // Sorted to ensure the layer, and therefore its digest, is the same for identical contents.
+    let mut entries = fs::read_dir(source_dir)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(fs::DirEntry::file_name);
+
+    for entry in entries {
+        // The package descriptor is only used during packaging and is not part of the buildpack.
+        if is_buildpack_root && entry.file_name() == "package.toml" {
+            continue;
+        }
+
+        let archive_path = archive_dir.join(entry.file_name());
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            append_directory(builder, &archive_path)?;
+            append_directory_contents(builder, &entry.path(), &archive_path, false)?;
+        } else if file_type.is_symlink() {
+            let mut header = normalized_header(tar::EntryType::Symlink, 0o777);
+            builder.append_link(&mut header, &archive_path, fs::read_link(entry.path())?)?;
+        } else {
+            let contents = fs::read(entry.path())?;
+            let mut header = normalized_header(
+                tar::EntryType::Regular,
+                normalized_file_mode(&entry.metadata()?),
+            );
+            header.set_size(contents.len() as u64);
+            builder.append_data(&mut header, &archive_path, contents.as_slice())?;
+        }
+    }
+
+    Ok(())
+}
+
+fn append_directory(builder: &mut tar::Builder<Vec<u8>>, path: &Path) -> std::io::Result<()> {
+    let mut header = normalized_header(tar::EntryType::Directory, 0o755);
+    builder.append_data(&mut header, path, std::io::empty())
+}
+
+// Headers with fixed ownership and modification times, so that archives only differ when the
+// contents differ.
+fn normalized_header(entry_type: tar::EntryType, mode: u32) -> tar::Header {
+    let mut header = tar::Header::new_ustar();
+    header.set_entry_type(entry_type);
+    header.set_mode(mode);
+    header.set_uid(0);
+    header.set_gid(0);
+    header.set_mtime(NORMALIZED_TIMESTAMP);
+    header.set_size(0);
+    header
+}
+
+fn write_oci_layout_archive(
+    destination: &Path,
+    index: &[u8],
+    blobs: &[(String, Vec<u8>)],
+) -> std::io::Result<()> {
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut builder = tar::Builder::new(fs::File::create(destination)?);
+
+    let mut append_file = |path: &str, contents: &[u8]| {
+        let mut header = normalized_header(tar::EntryType::Regular, 0o644);
+        header.set_size(contents.len() as u64);
+        builder.append_data(&mut header, path, contents)
+    };
+
+    append_file("oci-layout", OCI_LAYOUT_FILE_CONTENTS.as_bytes())?;
+    append_file("index.json", index)?;
+
+    for (digest, contents) in blobs {
+        append_file(&format!("blobs/{}", digest.replacen(':', "/", 1)), contents)?;
+    }
+
+    builder.into_inner()?.flush()
+}
+
+fn sha256_digest(contents: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(contents))
+}
+
+#[derive(Serialize)]
+struct BuildpackageMetadata {
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    homepage: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stacks: Vec<Stack>,
+}
+
+#[derive(Serialize)]
+struct LayerMetadata {
+    api: BuildpackApi,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stacks: Vec<Stack>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    targets: Vec<BuildpackTarget>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order: Option<Vec<Order>>,
+    #[serde(rename = "layerDiffID")]
+    layer_diff_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    homepage: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+}
+
+#[derive(Serialize)]
+struct OciImageConfig {
+    architecture: String,
+    os: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    variant: Option<String>,
+    created: &'static str,
+    config: OciImageConfigConfig,
+    rootfs: OciRootfs,
+}
+
+#[derive(Serialize)]
+struct OciImageConfigConfig {
+    #[serde(rename = "Labels")]
+    labels: BTreeMap<&'static str, String>,
+}
+
+#[derive(Serialize)]
+struct OciRootfs {
+    r#type: &'static str,
+    diff_ids: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OciManifest {
+    schema_version: u32,
+    media_type: &'static str,
+    config: OciDescriptor,
+    layers: Vec<OciDescriptor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OciIndex {
+    schema_version: u32,
+    manifests: Vec<OciDescriptor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OciDescriptor {
+    media_type: &'static str,
+    digest: String,
+    size: usize,
+}
+
+const BUILDPACKAGE_METADATA_LABEL: &str = "io.buildpacks.buildpackage.metadata";
+const BUILDPACK_LAYERS_LABEL: &str = "io.buildpacks.buildpack.layers";
+
+const OCI_LAYOUT_FILE_CONTENTS: &str = r#"{"imageLayoutVersion":"1.0.0"}"#;
+const OCI_MANIFEST_MEDIA_TYPE: &str = "application/vnd.oci.image.manifest.v1+json";
+const OCI_CONFIG_MEDIA_TYPE: &str = "application/vnd.oci.image.config.v1+json";
+const OCI_LAYER_MEDIA_TYPE: &str = "application/vnd.oci.image.layer.v1.tar";
+
+// The RFC 3339 representation of `NORMALIZED_TIMESTAMP`.
+const NORMALIZED_TIMESTAMP_RFC3339: &str = "1980-01-01T00:00:01Z";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn write_buildpack(dir: &Path, buildpack_toml: &str) {
+        fs::create_dir_all(dir.join("bin")).unwrap();
+        fs::write(dir.join("buildpack.toml"), buildpack_toml).unwrap();
+        fs::write(dir.join("bin").join("build"), "#!/usr/bin/env bash\n").unwrap();
+        fs::write(dir.join("package.toml"), "[buildpack]\nuri = \".\"\n").unwrap();
+    }
+
+    fn read_archive(path: &Path) -> BTreeMap<String, Vec<u8>> {
+        let mut archive = tar::Archive::new(fs::File::open(path).unwrap());
+        archive
+            .entries()
+            .unwrap()
+            .map(|entry| {
+                let mut entry = entry.unwrap();
+                let mut contents = Vec::new();
+                entry.read_to_end(&mut contents).unwrap();
+                (
+                    entry.path().unwrap().to_string_lossy().to_string(),
+                    contents,
+                )
+            })
+            .collect()
+    }
+
+    fn blob<'a>(archive: &'a BTreeMap<String, Vec<u8>>, digest: &str) -> &'a [u8] {
+        &archive[&format!("blobs/{}", digest.replacen(':', "/", 1))]
+    }
+
+    fn read_manifest_and_config(
+        archive: &BTreeMap<String, Vec<u8>>,
+    ) -> (serde_json::Value, serde_json::Value) {
+        let index = serde_json::from_slice::<serde_json::Value>(&archive["index.json"]).unwrap();
+        let manifest = serde_json::from_slice::<serde_json::Value>(blob(
+            archive,
+            index["manifests"][0]["digest"].as_str().unwrap(),
+        ))
+        .unwrap();
+        let config = serde_json::from_slice::<serde_json::Value>(blob(
+            archive,
+            manifest["config"]["digest"].as_str().unwrap(),
+        ))
+        .unwrap();
+
+        (manifest, config)
+    }
+
+    #[test]
+    fn write_composite_buildpackage() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let component_dir = temp_dir.path().join("component");
+        let composite_dir = temp_dir.path().join("composite");
+
+        write_buildpack(
+            &component_dir,
+            r#"
+api = "0.10"
+
+[buildpack]
+id = "example/component"
+version = "1.0.0"
+
+[[targets]]
+os = "linux"
+arch = "amd64"
+"#,
+        );
+
+        fs::create_dir_all(&composite_dir).unwrap();
+        fs::write(
+            composite_dir.join("buildpack.toml"),
+            r#"
+api = "0.10"
+
+[buildpack]
+id = "example/composite"
+version = "2.0.0"
+homepage = "https://example.com"
+
+[[order]]
+[[order.group]]
+id = "example/component"
+version = "1.0.0"
+"#,
+        )
+        .unwrap();
+        fs::write(
+            composite_dir.join("package.toml"),
+            format!(
+                "[buildpack]\nuri = \".\"\n\n[[dependencies]]\nuri = \"{}\"\n",
+                component_dir.display()
+            ),
+        )
+        .unwrap();
+
+        let destination = temp_dir.path().join("composite.cnb");
+        let target_platform =
+            TargetPlatform::from_target_triple("x86_64-unknown-linux-musl").unwrap();
+
+        write_buildpackage(&composite_dir, &target_platform, &destination).unwrap();
+
+        let archive = read_archive(&destination);
+        assert_eq!(
+            archive["oci-layout"],
+            br#"{"imageLayoutVersion":"1.0.0"}"#.to_vec()
+        );
+
+        let (manifest, config) = read_manifest_and_config(&archive);
+
+        assert_eq!(config["os"], "linux");
+        assert_eq!(config["architecture"], "amd64");
+        assert_eq!(manifest["layers"].as_array().unwrap().len(), 2);
+
+        let buildpackage_metadata = serde_json::from_str::<serde_json::Value>(
+            config["config"]["Labels"][BUILDPACKAGE_METADATA_LABEL]
+                .as_str()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            buildpackage_metadata,
+            serde_json::json!({
+                "id": "example/composite",
+                "version": "2.0.0",
+                "homepage": "https://example.com"
+            })
+        );
+
+        let layers = serde_json::from_str::<serde_json::Value>(
+            config["config"]["Labels"][BUILDPACK_LAYERS_LABEL]
+                .as_str()
+                .unwrap(),
+        )
+        .unwrap();
+        let component_layer_digest = manifest["layers"][0]["digest"].as_str().unwrap();
+        assert_eq!(
+            layers["example/component"]["1.0.0"],
+            serde_json::json!({
+                "api": "0.10",
+                "targets": [{"os": "linux", "arch": "amd64", "variant": null}],
+                "layerDiffID": component_layer_digest
+            })
+        );
+        assert_eq!(
+            layers["example/composite"]["2.0.0"]["order"],
+            serde_json::json!([{"group": [{"id": "example/component", "version": "1.0.0"}]}])
+        );
+
+        let component_layer = read_layer(blob(&archive, component_layer_digest));
+        assert_eq!(
+            component_layer,
+            [
+                "cnb/",
+                "cnb/buildpacks/",
+                "cnb/buildpacks/example_component/",
+                "cnb/buildpacks/example_component/1.0.0/",
+                "cnb/buildpacks/example_component/1.0.0/bin/",
+                "cnb/buildpacks/example_component/1.0.0/bin/build",
+                "cnb/buildpacks/example_component/1.0.0/buildpack.toml",
+            ]
+        );
+    }
+
+    #[test]
+    fn write_buildpackage_is_reproducible() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let buildpack_dir = temp_dir.path().join("buildpack");
+
+        write_buildpack(
+            &buildpack_dir,
+            r#"
+api = "0.10"
+
+[buildpack]
+id = "example/component"
+version = "1.0.0"
+"#,
+        );
+
+        let target_platform =
+            TargetPlatform::from_target_triple("aarch64-unknown-linux-musl").unwrap();
+
+        write_buildpackage(
+            &buildpack_dir,
+            &target_platform,
+            &temp_dir.path().join("a.cnb"),
+        )
+        .unwrap();
+        write_buildpackage(
+            &buildpack_dir,
+            &target_platform,
+            &temp_dir.path().join("b.cnb"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs::read(temp_dir.path().join("a.cnb")).unwrap(),
+            fs::read(temp_dir.path().join("b.cnb")).unwrap()
+        );
+    }
+
+    fn read_layer(layer: &[u8]) -> Vec<String> {
+        tar::Archive::new(layer)
+            .entries()
+            .unwrap()
+            .map(|entry| {
+                let entry = entry.unwrap();
+                let path = entry.path().unwrap().to_string_lossy().to_string();
+
+                if entry.header().entry_type().is_dir() {
+                    format!("{path}/")
+                } else {
+                    path
+                }
+            })
+            .collect()
+    }
+}
@@ -1,5 +1,10 @@
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+
 pub(crate) fn determine_buildpack_cargo_target_name(
     cargo_metadata: &cargo_metadata::Metadata,
+    libcnb_cargo_metadata: &LibcnbCargoMetadata,
 ) -> Result<String, DetermineBuildpackCargoTargetNameError> {
     let root_package = cargo_metadata
         .root_package()
@@ -8,6 +13,17 @@ pub(crate) fn determine_buildpack_cargo_target_name(
     let mut binary_target_names: Vec<String> =
         cargo_binary_target_names_from_root_package(root_package);
 
+    if let Some(buildpack_target) = &libcnb_cargo_metadata.buildpack_target {
+        return binary_target_names
+            .contains(buildpack_target)
+            .then(|| buildpack_target.clone())
+            .ok_or_else(|| {
+                DetermineBuildpackCargoTargetNameError::UnknownBuildpackTarget(
+                    buildpack_target.clone(),
+                )
+            });
+    }
+
     match binary_target_names.len() {
         0 | 1 => binary_target_names
             .pop()
@@ -25,8 +41,164 @@ pub enum DetermineBuildpackCargoTargetNameError {
     NoRootPackage,
     #[error("No binary targets could be found in Cargo metadata")]
     NoBinTargets,
-    #[error("Ambiguous binary targets found in Cargo metadata")]
+    #[error("Ambiguous binary targets found in Cargo metadata, select the buildpack binary with buildpack-target in [package.metadata.libcnb]")]
     AmbiguousBinTargets,
+    #[error("Binary target {0} selected with buildpack-target doesn't exist")]
+    UnknownBuildpackTarget(String),
+}
+
+/// The `[package.metadata.libcnb]` table of a libcnb.rs buildpack's `Cargo.toml`.
+///
+/// ```toml
+/// [package.metadata.libcnb]
+/// buildpack-target = "my-buildpack"
+/// include = ["scripts/*.sh", "templates/**"]
+/// exclude = ["templates/**/*.bak"]
+/// features = ["trace"]
+/// profile = "release-lto"
+/// rustflags = ["-C", "target-cpu=x86-64-v3"]
+/// locked = true
+///
+/// [package.metadata.libcnb.additional-binaries.export_env]
+/// kind = "exec-d"
+/// name = "export-env"
+/// ```
+#[derive(Debug, Default, Clone, Eq, PartialEq, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct LibcnbCargoMetadata {
+    /// The binary target that is packaged as `bin/build` and `bin/detect`. Only needed if the
+    /// crate has multiple binary targets and none of them has the name of the package.
+    pub buildpack_target: Option<String>,
+    /// Globs of files, relative to the crate directory, to copy into the packaged buildpack.
+    pub include: Vec<String>,
+    /// Globs of files to leave out, even if they match an `include` glob.
+    pub exclude: Vec<String>,
+    /// How additional binary targets are packaged, by binary target name.
+    ///
+    /// Additional binaries are always available via `additional_buildpack_binary_path!`,
+    /// binaries listed here are additionally placed where their kind requires.
+    pub additional_binaries: BTreeMap<String, AdditionalBinary>,
+    /// Cargo features to enable when packaging.
+    pub features: Vec<String>,
+    /// The Cargo profile to build with, instead of `dev` or `release`.
+    pub profile: Option<String>,
+    /// Flags passed to the compiler in addition to the ones in `RUSTFLAGS`, see
+    /// [`crate::build::CargoBuildOptions::rustflags`].
+    pub rustflags: Vec<String>,
+    /// Requires `Cargo.lock` to be up to date when packaging.
+    pub locked: bool,
+}
+
+/// How an additional binary target is packaged, see [`LibcnbCargoMetadata::additional_binaries`].
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AdditionalBinary {
+    pub kind: AdditionalBinaryKind,
+    /// The file name in the packaged buildpack, defaults to the binary target name.
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AdditionalBinaryKind {
+    /// An [exec.d](https://github.com/buildpacks/spec/blob/main/buildpack.md#execd) program,
+    /// packaged as `exec.d/<name>` so it can be added to layers as is.
+    ExecD,
+    /// A tool used by the buildpack itself, packaged as `bin/<name>`.
+    Helper,
+}
+
+/// Determines where the additional binaries configured in [`LibcnbCargoMetadata`] are placed in
+/// the packaged buildpack, relative to its root, by binary target name.
+///
+/// # Errors
+///
+/// Will return `Err` if a configured binary doesn't exist, is the buildpack binary itself or has a
+/// name that is invalid or used more than once.
+pub(crate) fn additional_binary_packaged_paths(
+    libcnb_cargo_metadata: &LibcnbCargoMetadata,
+    binary_target_names: &[String],
+    buildpack_target_name: &str,
+) -> Result<BTreeMap<String, PathBuf>, InvalidAdditionalBinaryError> {
+    let mut packaged_paths = BTreeMap::new();
+    let mut used_paths = HashSet::new();
+
+    for (binary_target_name, additional_binary) in &libcnb_cargo_metadata.additional_binaries {
+        if binary_target_name == buildpack_target_name {
+            return Err(InvalidAdditionalBinaryError::BuildpackTarget(
+                binary_target_name.clone(),
+            ));
+        }
+
+        if !binary_target_names.contains(binary_target_name) {
+            return Err(InvalidAdditionalBinaryError::UnknownBinaryTarget(
+                binary_target_name.clone(),
+            ));
+        }
+
+        let name = additional_binary
+            .name
+            .as_ref()
+            .unwrap_or(binary_target_name);
+
+        let is_valid_name = !name.is_empty()
+            && name != "."
+            && name != ".."
+            && !name.contains(['/', '\\'])
+            && !(additional_binary.kind == AdditionalBinaryKind::Helper
+                && RESERVED_BIN_NAMES.contains(&name.as_str()));
+
+        if !is_valid_name {
+            return Err(InvalidAdditionalBinaryError::InvalidName(name.clone()));
+        }
+
+        let packaged_path = match additional_binary.kind {
+            AdditionalBinaryKind::ExecD => PathBuf::from("exec.d").join(name),
+            AdditionalBinaryKind::Helper => PathBuf::from("bin").join(name),
+        };
+
+        if !used_paths.insert(packaged_path.clone()) {
+            return Err(InvalidAdditionalBinaryError::DuplicateName(name.clone()));
+        }
+
+        packaged_paths.insert(binary_target_name.clone(), packaged_path);
+    }
+
+    Ok(packaged_paths)
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum InvalidAdditionalBinaryError {
+    #[error("Additional binary {0} is not a binary target of this crate")]
+    UnknownBinaryTarget(String),
+    #[error("Binary target {0} is the buildpack binary and can't be an additional binary")]
+    BuildpackTarget(String),
+    #[error("Additional binary name {0} is invalid or reserved")]
+    InvalidName(String),
+    #[error("Additional binary name {0} is used more than once")]
+    DuplicateName(String),
+}
+
+// Executables in the buildpack's `bin` directory that are run by the lifecycle.
+const RESERVED_BIN_NAMES: [&str; 3] = ["build", "detect", "generate"];
+
+/// Reads the `[package.metadata.libcnb]` table of the root package from the given Cargo metadata.
+///
+/// Returns the default (empty) metadata if the table doesn't exist.
+///
+/// # Errors
+///
+/// Will return `Err` if the table can't be deserialized.
+pub(crate) fn libcnb_cargo_metadata(
+    cargo_metadata: &cargo_metadata::Metadata,
+) -> Result<LibcnbCargoMetadata, serde_json::Error> {
+    cargo_metadata
+        .root_package()
+        .and_then(|root_package| root_package.metadata.get("libcnb"))
+        .map_or_else(
+            || Ok(LibcnbCargoMetadata::default()),
+            |value| serde_json::from_value(value.clone()),
+        )
 }
 
 /// Determines the names of all binary targets from the given Cargo metadata.
@@ -50,3 +222,91 @@ fn cargo_binary_target_names_from_root_package(
 fn is_binary_target(target: &cargo_metadata::Target) -> bool {
     target.kind.contains(&String::from("bin"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_metadata(value: serde_json::Value) -> LibcnbCargoMetadata {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn additional_binary_packaged_paths_by_kind() {
+        let libcnb_cargo_metadata = parse_metadata(serde_json::json!({
+            "buildpack-target": "buildpack",
+            "additional-binaries": {
+                "export_env": { "kind": "exec-d", "name": "export-env" },
+                "tool": { "kind": "helper" },
+            },
+        }));
+
+        assert_eq!(
+            libcnb_cargo_metadata.buildpack_target.as_deref(),
+            Some("buildpack")
+        );
+        assert_eq!(
+            additional_binary_packaged_paths(
+                &libcnb_cargo_metadata,
+                &[
+                    String::from("buildpack"),
+                    String::from("export_env"),
+                    String::from("tool"),
+                    String::from("other"),
+                ],
+                "buildpack",
+            )
+            .unwrap(),
+            BTreeMap::from([
+                (
+                    String::from("export_env"),
+                    PathBuf::from("exec.d/export-env")
+                ),
+                (String::from("tool"), PathBuf::from("bin/tool")),
+            ])
+        );
+    }
+
+    #[test]
+    fn additional_binary_packaged_paths_errors() {
+        let binary_target_names = [
+            String::from("buildpack"),
+            String::from("one"),
+            String::from("two"),
+        ];
+
+        let error = |value| {
+            additional_binary_packaged_paths(
+                &parse_metadata(serde_json::json!({ "additional-binaries": value })),
+                &binary_target_names,
+                "buildpack",
+            )
+            .unwrap_err()
+            .to_string()
+        };
+
+        assert_eq!(
+            error(serde_json::json!({ "missing": { "kind": "helper" } })),
+            "Additional binary missing is not a binary target of this crate"
+        );
+        assert_eq!(
+            error(serde_json::json!({ "buildpack": { "kind": "exec-d" } })),
+            "Binary target buildpack is the buildpack binary and can't be an additional binary"
+        );
+        assert_eq!(
+            error(serde_json::json!({ "one": { "kind": "helper", "name": "detect" } })),
+            "Additional binary name detect is invalid or reserved"
+        );
+        assert_eq!(
+            error(serde_json::json!({ "one": { "kind": "exec-d", "name": "../one" } })),
+            "Additional binary name ../one is invalid or reserved"
+        );
+        assert_eq!(
+            error(serde_json::json!({
+                "one": { "kind": "exec-d", "name": "tool" },
+                "two": { "kind": "exec-d", "name": "tool" },
+            })),
+            "Additional binary name tool is used more than once"
+        );
+    }
+}
@@ -0,0 +1,979 @@
+use crate::buildpack_dependency_graph::BuildpackDependencyGraphNode;
+use crate::cargo::{
+    additional_binary_packaged_paths, cargo_binary_target_names,
+    determine_buildpack_cargo_target_name, libcnb_cargo_metadata,
+    DetermineBuildpackCargoTargetNameError, InvalidAdditionalBinaryError,
+};
+use crate::dependency_graph::create_dependency_graph;
+use crate::dependency_reference::{DependencyReference, ParseDependencyReferenceError};
+use crate::find_buildpack_dirs;
+use crate::multi_target::{target_platform_from_buildpack_target, unsupported_targets};
+use cargo_metadata::MetadataCommand;
+use libcnb_common::toml_file::{read_toml_file, TomlFileError};
+use libcnb_data::buildpack::{
+    BuildpackDescriptor, BuildpackId, BuildpackTarget, BuildpackVersion, BuildpackVersionReq, Order,
+};
+use libcnb_data::package_descriptor::PackageDescriptor;
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::NodeIndex;
+use petgraph::Graph;
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Validates all buildpacks in a directory (usually a Cargo workspace) without building them.
+///
+/// The checks cover problems that would otherwise only surface while packaging:
+/// - `buildpack.toml` and `package.toml` files that can't be parsed
+/// - buildpack IDs that are used by more than one buildpack
+/// - `package.toml` dependencies that refer to unknown `libcnb:` buildpacks, missing local
+///   directories or use unsupported URI schemes
+/// - cycles between buildpacks via `libcnb:` dependencies
+/// - `[[order.group]]` entries of composite buildpacks whose version doesn't match the version of
+///   the buildpack in the workspace they refer to
+/// - `version` requirements of `libcnb:` dependencies in `package.toml` that the version of the
+///   buildpack in the workspace doesn't satisfy, and `version` requirements of other dependencies
+/// - `[[targets]]` in a composite buildpack's `package.toml` that buildpacks it depends on don't
+///   support
+/// - `[[targets]]` for a specific platform that libcnb.rs can't compile for
+/// - libcnb.rs buildpacks without an unambiguous buildpack binary target, with invalid additional
+///   binaries in `[package.metadata.libcnb]`, or that refer to additional binaries via
+///   `additional_buildpack_binary_path!` that don't exist
+///
+/// All problems are collected and returned at once, each with the location of the file that
+/// causes it. Buildpack images and registry buildpacks that aren't pinned to a specific version
+/// are reported as warnings, since they're valid but make packaging non-reproducible.
+///
+/// # Errors
+///
+/// Will return `Err` if the buildpack directories couldn't be located.
+pub fn check_buildpacks(start_dir: &Path) -> Result<CheckReport, CheckBuildpacksError> {
+    let mut buildpack_dirs =
+        find_buildpack_dirs(start_dir).map_err(CheckBuildpacksError::FindBuildpackDirectories)?;
+    // Sorted so that problems like duplicate buildpack IDs are always reported for the same file.
+    buildpack_dirs.sort();
+
+    let mut problems = Vec::new();
+    let mut warnings = Vec::new();
+
+    let mut buildpacks = Vec::new();
+    for buildpack_dir in &buildpack_dirs {
+        let buildpack_descriptor_path = buildpack_dir.join("buildpack.toml");
+
+        match read_toml_file::<BuildpackDescriptor>(&buildpack_descriptor_path) {
+            Ok(buildpack_descriptor) => buildpacks.push((buildpack_dir, buildpack_descriptor)),
+            Err(error) => problems.push(Problem::new(
+                buildpack_descriptor_path,
+                ProblemKind::InvalidBuildpackDescriptor(error),
+            )),
+        }
+    }
+
+    let mut buildpack_paths: BTreeMap<BuildpackId, &PathBuf> = BTreeMap::new();
+    let mut buildpack_versions: BTreeMap<BuildpackId, &BuildpackVersion> = BTreeMap::new();
+    for (buildpack_dir, buildpack_descriptor) in &buildpacks {
+        let buildpack_id = &buildpack_descriptor.buildpack().id;
+
+        if let Some(other_buildpack_dir) = buildpack_paths.get(buildpack_id) {
+            problems.push(Problem::new(
+                buildpack_dir.join("buildpack.toml"),
+                ProblemKind::DuplicateBuildpackId(
+                    buildpack_id.clone(),
+                    (*other_buildpack_dir).clone(),
+                ),
+            ));
+        } else {
+            buildpack_paths.insert(buildpack_id.clone(), buildpack_dir);
+            buildpack_versions.insert(
+                buildpack_id.clone(),
+                &buildpack_descriptor.buildpack().version,
+            );
+        }
+    }
+
+    let mut graph_nodes = Vec::new();
+    for (buildpack_dir, buildpack_descriptor) in &buildpacks {
+        let dependencies = match buildpack_descriptor {
+            BuildpackDescriptor::Component(component_buildpack_descriptor) => {
+                problems.extend(check_targets(
+                    buildpack_dir,
+                    &component_buildpack_descriptor.targets,
+                ));
+
+                if buildpack_dir.join("Cargo.toml").is_file() {
+                    problems.extend(check_libcnb_buildpack_binaries(buildpack_dir));
+                }
+
+                Vec::new()
+            }
+            BuildpackDescriptor::Composite(composite_buildpack_descriptor) => {
+                problems.extend(check_order_versions(
+                    buildpack_dir,
+                    &composite_buildpack_descriptor.order,
+                    &buildpack_versions,
+                ));
+
+                let (dependencies, package_descriptor_problems, package_descriptor_warnings) =
+                    check_package_descriptor(buildpack_dir, &buildpack_versions);
+                problems.extend(package_descriptor_problems);
+                warnings.extend(package_descriptor_warnings);
+                problems.extend(check_dependency_targets(buildpack_dir, &buildpack_paths));
+                dependencies
+            }
+        };
+
+        if buildpack_paths.get(&buildpack_descriptor.buildpack().id) == Some(buildpack_dir) {
+            graph_nodes.push(BuildpackDependencyGraphNode {
+                buildpack_id: buildpack_descriptor.buildpack().id.clone(),
+                path: (*buildpack_dir).clone(),
+                dependencies,
+            });
+        }
+    }
+
+    problems.extend(check_dependency_cycles(graph_nodes));
+
+    Ok(CheckReport {
+        buildpack_dirs,
+        problems,
+        warnings,
+    })
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum CheckBuildpacksError {
+    #[error("Error while finding buildpack directories: {0}")]
+    FindBuildpackDirectories(ignore::Error),
+}
+
+/// The result of [`check_buildpacks`].
+#[derive(Debug)]
+pub struct CheckReport {
+    /// The directories of all buildpacks that were checked.
+    pub buildpack_dirs: Vec<PathBuf>,
+    /// All problems that were found, an empty list means all buildpacks are valid.
+    pub problems: Vec<Problem>,
+    /// Problems that don't prevent packaging, such as unpinned buildpack images.
+    pub warnings: Vec<Problem>,
+}
+
+/// A problem found by [`check_buildpacks`].
+#[derive(Debug)]
+pub struct Problem {
+    /// The file that causes the problem.
+    pub path: PathBuf,
+    /// The line within the file that causes the problem, if known.
+    pub line: Option<usize>,
+    pub kind: ProblemKind,
+}
+
+impl Problem {
+    fn new(path: PathBuf, kind: ProblemKind) -> Self {
+        Self {
+            path,
+            line: None,
+            kind,
+        }
+    }
+
+    /// Displays the problem like its [`Display`] implementation, but with its path relative to the
+    /// given directory if the path is within it.
+    #[must_use]
+    pub fn display_relative_to<'a>(&'a self, base_dir: &'a Path) -> impl Display + 'a {
+        DisplayProblem {
+            problem: self,
+            path: self.path.strip_prefix(base_dir).unwrap_or(&self.path),
+        }
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        DisplayProblem {
+            problem: self,
+            path: &self.path,
+        }
+        .fmt(formatter)
+    }
+}
+
+struct DisplayProblem<'a> {
+    problem: &'a Problem,
+    path: &'a Path,
+}
+
+impl Display for DisplayProblem<'_> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "{}", self.path.display())?;
+
+        if let Some(line) = self.problem.line {
+            write!(formatter, ":{line}")?;
+        }
+
+        write!(formatter, ": {}", self.problem.kind)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ProblemKind {
+    #[error("Couldn't read buildpack.toml: {0}")]
+    InvalidBuildpackDescriptor(TomlFileError),
+    #[error("Buildpack ID {0} is also used by the buildpack in {}", .1.display())]
+    DuplicateBuildpackId(BuildpackId, PathBuf),
+    #[error("Composite buildpack has no package.toml")]
+    MissingPackageDescriptor,
+    #[error("Couldn't read package.toml: {0}")]
+    InvalidPackageDescriptor(TomlFileError),
+    #[error("{0}")]
+    InvalidDependencyReference(ParseDependencyReferenceError),
+    #[error("Dependency libcnb:{0} doesn't refer to a buildpack in this workspace")]
+    UnknownLibcnbDependency(BuildpackId),
+    #[error("Dependency {} doesn't refer to a buildpack directory", .0.display())]
+    MissingLocalDependency(PathBuf),
+    #[error("Dependency {0} uses an unsupported URI scheme")]
+    UnsupportedDependencyUri(String),
+    #[error("Dependency {0} isn't pinned to a specific version or digest")]
+    UnpinnedDependency(String),
+    #[error("Order group entry {0} requires version {1}, but the buildpack in this workspace has version {2}")]
+    OrderGroupVersionMismatch(BuildpackId, BuildpackVersion, BuildpackVersion),
+    #[error("Dependency libcnb:{0} requires version {1}, but the buildpack in this workspace has version {2}")]
+    DependencyVersionMismatch(BuildpackId, BuildpackVersionReq, BuildpackVersion),
+    #[error("Dependency {0} has a version requirement, which is only supported for libcnb: dependencies")]
+    UnsupportedDependencyVersionReq(String),
+    #[error("Buildpacks depend on each other in a cycle: {}", display_cycle(.0))]
+    DependencyCycle(Vec<BuildpackId>),
+    #[error("Target (os: {}, arch: {}, variant: {}) is not supported by libcnb.rs",
+        .0.os.as_deref().unwrap_or("<unset>"),
+        .0.arch.as_deref().unwrap_or("<unset>"),
+        .0.variant.as_deref().unwrap_or("<unset>"))]
+    UnsupportedTarget(BuildpackTarget),
+    #[error("Dependency {0} doesn't support target {}", display_target(.1))]
+    UnsupportedDependencyTarget(BuildpackId, BuildpackTarget),
+    #[error("Couldn't read Cargo metadata: {0}")]
+    CargoMetadata(cargo_metadata::Error),
+    #[error("Invalid [package.metadata.libcnb] in Cargo.toml: {0}")]
+    InvalidLibcnbCargoMetadata(serde_json::Error),
+    #[error("{0}")]
+    InvalidAdditionalBinary(InvalidAdditionalBinaryError),
+    #[error("Couldn't determine buildpack binary target: {0}")]
+    BuildpackBinaryTarget(DetermineBuildpackCargoTargetNameError),
+    #[error("Additional buildpack binary {0} is not a binary target of this crate")]
+    UnknownAdditionalBinary(String),
+}
+
+fn display_cycle(buildpack_ids: &[BuildpackId]) -> String {
+    buildpack_ids
+        .iter()
+        .chain(buildpack_ids.first())
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+fn display_target(target: &BuildpackTarget) -> String {
+    let platform = [&target.os, &target.arch, &target.variant]
+        .into_iter()
+        .map(|value| value.as_deref().unwrap_or("*"))
+        .collect::<Vec<_>>()
+        .join("/");
+
+    if target.distros.is_empty() {
+        platform
+    } else {
+        let distros = target
+            .distros
+            .iter()
+            .map(|distro| format!("{} {}", distro.name, distro.version))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!("{platform} ({distros})")
+    }
+}
+
+// Targets without an OS or architecture match several platforms and are skipped when packaging, so
+// only targets for a specific platform are reported.
+fn check_targets(buildpack_dir: &Path, targets: &[BuildpackTarget]) -> Vec<Problem> {
+    targets
+        .iter()
+        .filter(|target| {
+            target_platform_from_buildpack_target(target)
+                .is_some_and(|target_platform| target_platform.target_triple().is_none())
+        })
+        .map(|target| {
+            Problem::new(
+                buildpack_dir.join("buildpack.toml"),
+                ProblemKind::UnsupportedTarget(target.clone()),
+            )
+        })
+        .collect()
+}
+
+// Returns the IDs of the `libcnb:` dependencies that exist, so they can be added to the dependency
+// graph, together with all problems found in the package descriptor.
+fn check_package_descriptor(
+    buildpack_dir: &Path,
+    buildpack_versions: &BTreeMap<BuildpackId, &BuildpackVersion>,
+) -> (Vec<BuildpackId>, Vec<Problem>, Vec<Problem>) {
+    let package_descriptor_path = buildpack_dir.join("package.toml");
+
+    if !package_descriptor_path.is_file() {
+        return (
+            Vec::new(),
+            vec![Problem::new(
+                package_descriptor_path,
+                ProblemKind::MissingPackageDescriptor,
+            )],
+            Vec::new(),
+        );
+    }
+
+    let package_descriptor = match read_toml_file::<PackageDescriptor>(&package_descriptor_path) {
+        Ok(package_descriptor) => package_descriptor,
+        Err(error) => {
+            return (
+                Vec::new(),
+                vec![Problem::new(
+                    package_descriptor_path,
+                    ProblemKind::InvalidPackageDescriptor(error),
+                )],
+                Vec::new(),
+            )
+        }
+    };
+
+    let mut dependencies = Vec::new();
+    let mut problems = Vec::new();
+    let mut warnings = Vec::new();
+
+    for dependency in &package_descriptor.dependencies {
+        let reference = DependencyReference::parse(dependency);
+
+        if dependency.version.is_some() && !matches!(reference, Ok(DependencyReference::Libcnb(_)))
+        {
+            problems.push(Problem::new(
+                package_descriptor_path.clone(),
+                ProblemKind::UnsupportedDependencyVersionReq(dependency.uri.to_string()),
+            ));
+        }
+
+        let problem_kind = match reference {
+            Ok(DependencyReference::Libcnb(buildpack_id)) => {
+                if let Some(version) = buildpack_versions.get(&buildpack_id) {
+                    dependencies.push(buildpack_id.clone());
+
+                    dependency
+                        .version
+                        .as_ref()
+                        .filter(|version_req| !version_req.matches(version))
+                        .map(|version_req| {
+                            ProblemKind::DependencyVersionMismatch(
+                                buildpack_id,
+                                version_req.clone(),
+                                (*version).clone(),
+                            )
+                        })
+                } else {
+                    Some(ProblemKind::UnknownLibcnbDependency(buildpack_id))
+                }
+            }
+            Ok(DependencyReference::Path(path)) => {
+                let dependency_path = buildpack_dir.join(path);
+
+                (!dependency_path.join("buildpack.toml").is_file())
+                    .then_some(ProblemKind::MissingLocalDependency(dependency_path))
+            }
+            Ok(
+                reference @ (DependencyReference::DockerImage(_)
+                | DependencyReference::RegistryBuildpack(_)),
+            ) => {
+                if !reference.is_pinned() {
+                    warnings.push(Problem::new(
+                        package_descriptor_path.clone(),
+                        ProblemKind::UnpinnedDependency(dependency.uri.to_string()),
+                    ));
+                }
+
+                None
+            }
+            Ok(DependencyReference::Other(uri)) => dependency
+                .uri
+                .scheme()
+                .map(uriparse::Scheme::as_str)
+                .filter(|scheme| !SUPPORTED_DEPENDENCY_URI_SCHEMES.contains(scheme))
+                .map(|_| ProblemKind::UnsupportedDependencyUri(uri)),
+            Err(error) => Some(ProblemKind::InvalidDependencyReference(error)),
+        };
+
+        if let Some(problem_kind) = problem_kind {
+            problems.push(Problem::new(package_descriptor_path.clone(), problem_kind));
+        }
+    }
+
+    (dependencies, problems, warnings)
+}
+
+// Checks that the buildpacks in the workspace a composite buildpack depends on support all targets
+// in its package.toml. Other problems with the package.toml are reported by
+// `check_package_descriptor`, and dependencies outside the workspace can't be checked.
+fn check_dependency_targets(
+    buildpack_dir: &Path,
+    buildpack_paths: &BTreeMap<BuildpackId, &PathBuf>,
+) -> Vec<Problem> {
+    let package_descriptor_path = buildpack_dir.join("package.toml");
+
+    let Ok(package_descriptor) = read_toml_file::<PackageDescriptor>(&package_descriptor_path)
+    else {
+        return Vec::new();
+    };
+
+    let mut problems = Vec::new();
+
+    for dependency in &package_descriptor.dependencies {
+        let dependency_dir = match DependencyReference::parse(dependency) {
+            Ok(DependencyReference::Libcnb(buildpack_id)) => buildpack_paths
+                .get(&buildpack_id)
+                .map(|path| (*path).clone()),
+            Ok(DependencyReference::Path(path)) => Some(buildpack_dir.join(path)),
+            _ => None,
+        };
+
+        let Some((dependency_buildpack_id, dependency_targets)) =
+            dependency_dir.as_deref().and_then(declared_targets)
+        else {
+            continue;
+        };
+
+        for target in unsupported_targets(&package_descriptor.targets, &dependency_targets) {
+            problems.push(Problem::new(
+                package_descriptor_path.clone(),
+                ProblemKind::UnsupportedDependencyTarget(
+                    dependency_buildpack_id.clone(),
+                    target.clone(),
+                ),
+            ));
+        }
+    }
+
+    problems
+}
+
+// The targets of a component buildpack are declared in its buildpack.toml, the ones of a
+// composite buildpack in its package.toml.
+fn declared_targets(buildpack_dir: &Path) -> Option<(BuildpackId, Vec<BuildpackTarget>)> {
+    match read_toml_file::<BuildpackDescriptor>(buildpack_dir.join("buildpack.toml")).ok()? {
+        BuildpackDescriptor::Component(descriptor) => {
+            Some((descriptor.buildpack.id, descriptor.targets))
+        }
+        BuildpackDescriptor::Composite(descriptor) => Some((
+            descriptor.buildpack.id,
+            read_toml_file::<PackageDescriptor>(buildpack_dir.join("package.toml"))
+                .map(|package_descriptor| package_descriptor.targets)
+                .unwrap_or_default(),
+        )),
+    }
+}
+
+// Order group entries refer to an exact buildpack version, which has to match the version of the
+// workspace buildpack with that ID, since that's the one that is packaged. Entries for buildpacks
+// outside the workspace can't be checked.
+fn check_order_versions(
+    buildpack_dir: &Path,
+    order: &[Order],
+    buildpack_versions: &BTreeMap<BuildpackId, &BuildpackVersion>,
+) -> Vec<Problem> {
+    order
+        .iter()
+        .flat_map(|order| &order.group)
+        .filter_map(|group| {
+            let version = buildpack_versions.get(&group.id)?;
+
+            (group.version != **version).then(|| {
+                Problem::new(
+                    buildpack_dir.join("buildpack.toml"),
+                    ProblemKind::OrderGroupVersionMismatch(
+                        group.id.clone(),
+                        group.version.clone(),
+                        (*version).clone(),
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+fn check_dependency_cycles(graph_nodes: Vec<BuildpackDependencyGraphNode>) -> Vec<Problem> {
+    // All dependencies were checked to exist before, so creating the graph can't fail.
+    let Ok(graph) = create_dependency_graph(graph_nodes) else {
+        return Vec::new();
+    };
+
+    tarjan_scc(&graph)
+        .into_iter()
+        .filter(|component| {
+            component.len() > 1
+                || component
+                    .iter()
+                    .any(|index| graph.contains_edge(*index, *index))
+        })
+        .filter_map(|component| {
+            // Reported starting at the buildpack with the lowest ID for stable output, since the
+            // order of strongly connected components is an implementation detail.
+            let first_index = component
+                .iter()
+                .min_by_key(|index| &graph[**index].buildpack_id)?;
+
+            let cycle = cycle_path(&graph, &component, *first_index)?;
+
+            Some(Problem::new(
+                graph[*first_index].path.join("buildpack.toml"),
+                ProblemKind::DependencyCycle(
+                    cycle
+                        .iter()
+                        .map(|index| graph[*index].buildpack_id.clone())
+                        .collect(),
+                ),
+            ))
+        })
+        .collect()
+}
+
+// Finds the shortest cycle from the given node back to itself via dependency edges between nodes
+// of the given strongly connected component. Returns the nodes of the cycle, starting with the
+// given node.
+fn cycle_path(
+    graph: &Graph<BuildpackDependencyGraphNode, ()>,
+    component: &[NodeIndex],
+    start: NodeIndex,
+) -> Option<Vec<NodeIndex>> {
+    let mut predecessors: BTreeMap<NodeIndex, NodeIndex> = BTreeMap::new();
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(index) = queue.pop_front() {
+        // Dependencies are visited in ID order, so the same cycle is found on every run.
+        let mut dependencies = graph
+            .neighbors(index)
+            .filter(|dependency| component.contains(dependency))
+            .collect::<Vec<_>>();
+        dependencies.sort_by_key(|dependency| &graph[*dependency].buildpack_id);
+
+        for dependency in dependencies {
+            if dependency == start {
+                let mut cycle = vec![index];
+                while let Some(predecessor) = predecessors.get(cycle.last()?) {
+                    cycle.push(*predecessor);
+                }
+                cycle.reverse();
+                return Some(cycle);
+            }
+
+            if let Entry::Vacant(entry) = predecessors.entry(dependency) {
+                entry.insert(index);
+                queue.push_back(dependency);
+            }
+        }
+    }
+
+    None
+}
+
+fn check_libcnb_buildpack_binaries(buildpack_dir: &Path) -> Vec<Problem> {
+    let cargo_toml_path = buildpack_dir.join("Cargo.toml");
+
+    let cargo_metadata = match MetadataCommand::new()
+        .manifest_path(&cargo_toml_path)
+        .exec()
+    {
+        Ok(cargo_metadata) => cargo_metadata,
+        Err(error) => {
+            return vec![Problem::new(
+                cargo_toml_path,
+                ProblemKind::CargoMetadata(error),
+            )]
+        }
+    };
+
+    let libcnb_cargo_metadata = match libcnb_cargo_metadata(&cargo_metadata) {
+        Ok(libcnb_cargo_metadata) => libcnb_cargo_metadata,
+        Err(error) => {
+            return vec![Problem::new(
+                cargo_toml_path,
+                ProblemKind::InvalidLibcnbCargoMetadata(error),
+            )]
+        }
+    };
+
+    let mut problems = Vec::new();
+    let binary_target_names = cargo_binary_target_names(&cargo_metadata);
+
+    match determine_buildpack_cargo_target_name(&cargo_metadata, &libcnb_cargo_metadata) {
+        Ok(buildpack_target_name) => {
+            if let Err(error) = additional_binary_packaged_paths(
+                &libcnb_cargo_metadata,
+                &binary_target_names,
+                &buildpack_target_name,
+            ) {
+                problems.push(Problem::new(
+                    cargo_toml_path.clone(),
+                    ProblemKind::InvalidAdditionalBinary(error),
+                ));
+            }
+        }
+        Err(error) => problems.push(Problem::new(
+            cargo_toml_path.clone(),
+            ProblemKind::BuildpackBinaryTarget(error),
+        )),
+    }
+
+    for source_file_path in rust_source_files(&buildpack_dir.join("src")) {
+        let Ok(source) = fs::read_to_string(&source_file_path) else {
+            continue;
+        };
+
+        for (line, binary_name) in additional_buildpack_binary_references(&source) {
+            if !binary_target_names.contains(&binary_name) {
+                problems.push(Problem {
+                    path: source_file_path.clone(),
+                    line: Some(line),
+                    kind: ProblemKind::UnknownAdditionalBinary(binary_name),
+                });
+            }
+        }
+    }
+
+    problems
+}
+
+fn rust_source_files(dir: &Path) -> Vec<PathBuf> {
+    let mut source_files = Vec::new();
+
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+
+        if path.is_dir() {
+            source_files.extend(rust_source_files(&path));
+        } else if path.extension().is_some_and(|extension| extension == "rs") {
+            source_files.push(path);
+        }
+    }
+
+    source_files.sort();
+    source_files
+}
+
+// Finds the binary names passed to `additional_buildpack_binary_path!` as string literals, together
+// with the (1-based) line they're on. The macro itself verifies this during compilation, but only
+// for code that is actually compiled.
+fn additional_buildpack_binary_references(source: &str) -> Vec<(usize, String)> {
+    const MACRO_NAME: &str = "additional_buildpack_binary_path!";
+
+    source
+        .match_indices(MACRO_NAME)
+        .filter_map(|(index, _)| {
+            let arguments = source[index + MACRO_NAME.len()..]
+                .trim_start()
+                .strip_prefix('(')?
+                .trim_start()
+                .strip_prefix('"')?;
+
+            let binary_name = &arguments[..arguments.find('"')?];
+            let line = source[..index].matches('\n').count() + 1;
+
+            Some((line, String::from(binary_name)))
+        })
+        .collect()
+}
+
+// Buildpack images and registry buildpacks are parsed separately, see `DependencyReference`.
+const SUPPORTED_DEPENDENCY_URI_SCHEMES: [&str; 4] = ["file", "http", "https", "urn"];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_composite_buildpack(dir: &Path, id: &str, dependency_uris: &[&str]) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(
+            dir.join("buildpack.toml"),
+            format!("api = \"0.10\"\n\n[buildpack]\nid = \"{id}\"\nversion = \"0.0.1\"\n\n[[order]]\n[[order.group]]\nid = \"other\"\nversion = \"0.0.1\"\n"),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("package.toml"),
+            dependency_uris.iter().fold(
+                String::from("[buildpack]\nuri = \".\"\n\n"),
+                |package_descriptor, uri| {
+                    format!("{package_descriptor}[[dependencies]]\nuri = \"{uri}\"\n\n")
+                },
+            ),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn check_buildpacks_reports_all_problems() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+
+        write_composite_buildpack(
+            &root.join("a"),
+            "example/a",
+            &[
+                "libcnb:example/b",
+                "libcnb:example/missing",
+                "./missing-dir",
+            ],
+        );
+        write_composite_buildpack(
+            &root.join("b"),
+            "example/b",
+            &[
+                "libcnb:example/a",
+                "docker://docker.io/heroku/example",
+                "ftp://example.com/bp",
+            ],
+        );
+        write_composite_buildpack(&root.join("duplicate"), "example/a", &[]);
+
+        fs::create_dir_all(root.join("component")).unwrap();
+        fs::write(
+            root.join("component").join("buildpack.toml"),
+            "api = \"0.10\"\n\n[buildpack]\nid = \"example/component\"\nversion = \"0.0.1\"\n\n[[targets]]\nos = \"windows\"\narch = \"amd64\"\n",
+        )
+        .unwrap();
+
+        fs::create_dir_all(root.join("invalid")).unwrap();
+        fs::write(root.join("invalid").join("buildpack.toml"), "api = ").unwrap();
+
+        let check_report = check_buildpacks(root).unwrap();
+
+        assert_eq!(
+            check_report
+                .warnings
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [format!(
+                "{}: Dependency docker://docker.io/heroku/example isn't pinned to a specific version or digest",
+                root.join("b").join("package.toml").display()
+            )]
+        );
+
+        let mut problems = check_report
+            .problems
+            .into_iter()
+            .map(|problem| {
+                format!(
+                    "{}: {}",
+                    problem.path.strip_prefix(root).unwrap().to_string_lossy(),
+                    problem.kind
+                )
+            })
+            .collect::<Vec<_>>();
+        problems.sort();
+
+        assert_eq!(problems.len(), 7);
+        assert_eq!(
+            problems[..6],
+            [
+                "a/buildpack.toml: Buildpacks depend on each other in a cycle: example/a -> example/b -> example/a",
+                &format!("a/package.toml: Dependency {} doesn't refer to a buildpack directory", root.join("a").join("./missing-dir").display()),
+                "a/package.toml: Dependency libcnb:example/missing doesn't refer to a buildpack in this workspace",
+                "b/package.toml: Dependency ftp://example.com/bp uses an unsupported URI scheme",
+                "component/buildpack.toml: Target (os: windows, arch: amd64, variant: <unset>) is not supported by libcnb.rs",
+                &format!("duplicate/buildpack.toml: Buildpack ID example/a is also used by the buildpack in {}", root.join("a").display()),
+            ]
+        );
+        assert!(problems[6].starts_with("invalid/buildpack.toml: Couldn't read buildpack.toml: "));
+    }
+
+    #[test]
+    fn check_buildpacks_reports_unsupported_dependency_targets() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+
+        write_composite_buildpack(&root.join("composite"), "example/composite", &[]);
+        fs::write(
+            root.join("composite").join("package.toml"),
+            "[buildpack]\nuri = \".\"\n\n[[dependencies]]\nuri = \"libcnb:example/amd64\"\n\n[[dependencies]]\nuri = \"../any\"\n\n[[targets]]\nos = \"linux\"\narch = \"amd64\"\n\n[[targets]]\nos = \"linux\"\narch = \"arm64\"\n\n[[targets.distros]]\nname = \"ubuntu\"\nversion = \"24.04\"\n",
+        )
+        .unwrap();
+
+        for (dir_name, buildpack_id, targets) in [
+            (
+                "amd64",
+                "example/amd64",
+                "[[targets]]\nos = \"linux\"\narch = \"amd64\"\n",
+            ),
+            ("any", "example/any", ""),
+        ] {
+            fs::create_dir_all(root.join(dir_name)).unwrap();
+            fs::write(
+                root.join(dir_name).join("buildpack.toml"),
+                format!("api = \"0.10\"\n\n[buildpack]\nid = \"{buildpack_id}\"\nversion = \"0.0.1\"\n\n{targets}"),
+            )
+            .unwrap();
+        }
+
+        assert_eq!(
+            check_buildpacks(root)
+                .unwrap()
+                .problems
+                .iter()
+                .map(|problem| problem.kind.to_string())
+                .collect::<Vec<_>>(),
+            ["Dependency example/amd64 doesn't support target linux/arm64/* (ubuntu 24.04)"]
+        );
+    }
+
+    #[test]
+    fn check_buildpacks_reports_dependency_cycles_along_dependencies() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+
+        write_composite_buildpack(&root.join("a"), "example/a", &["libcnb:example/c"]);
+        write_composite_buildpack(&root.join("b"), "example/b", &["libcnb:example/a"]);
+        write_composite_buildpack(&root.join("c"), "example/c", &["libcnb:example/b"]);
+        write_composite_buildpack(&root.join("d"), "example/d", &["libcnb:example/d"]);
+
+        assert_eq!(
+            check_buildpacks(root)
+                .unwrap()
+                .problems
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                format!(
+                    "{}: Buildpacks depend on each other in a cycle: example/a -> example/c -> example/b -> example/a",
+                    root.join("a").join("buildpack.toml").display()
+                ),
+                format!(
+                    "{}: Buildpacks depend on each other in a cycle: example/d -> example/d",
+                    root.join("d").join("buildpack.toml").display()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_buildpacks_reports_order_group_version_mismatches() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+
+        write_composite_buildpack(
+            &root.join("composite"),
+            "example/composite",
+            &["libcnb:example/matching", "libcnb:example/mismatching"],
+        );
+        fs::write(
+            root.join("composite").join("buildpack.toml"),
+            "api = \"0.10\"\n\n[buildpack]\nid = \"example/composite\"\nversion = \"0.0.1\"\n\n[[order]]\n[[order.group]]\nid = \"example/matching\"\nversion = \"1.2.3\"\n\n[[order.group]]\nid = \"example/mismatching\"\nversion = \"1.0.0\"\n\n[[order.group]]\nid = \"example/external\"\nversion = \"9.9.9\"\n",
+        )
+        .unwrap();
+
+        for (dir_name, buildpack_id, version) in [
+            ("matching", "example/matching", "1.2.3"),
+            ("mismatching", "example/mismatching", "1.1.0"),
+        ] {
+            fs::create_dir_all(root.join(dir_name)).unwrap();
+            fs::write(
+                root.join(dir_name).join("buildpack.toml"),
+                format!("api = \"0.10\"\n\n[buildpack]\nid = \"{buildpack_id}\"\nversion = \"{version}\"\n"),
+            )
+            .unwrap();
+        }
+
+        assert_eq!(
+            check_buildpacks(root)
+                .unwrap()
+                .problems
+                .iter()
+                .map(|problem| problem.kind.to_string())
+                .collect::<Vec<_>>(),
+            ["Order group entry example/mismatching requires version 1.0.0, but the buildpack in this workspace has version 1.1.0"]
+        );
+    }
+
+    #[test]
+    fn check_buildpacks_reports_dependency_version_mismatches() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+
+        write_composite_buildpack(&root.join("composite"), "example/composite", &[]);
+        fs::write(
+            root.join("composite").join("package.toml"),
+            "[buildpack]\nuri = \".\"\n\n[[dependencies]]\nuri = \"libcnb:example/matching\"\nversion = \"^1.2\"\n\n[[dependencies]]\nuri = \"libcnb:example/mismatching\"\nversion = \">=1.0, <2\"\n\n[[dependencies]]\nuri = \"docker://docker.io/heroku/example:1.2.3\"\nversion = \"^1.2\"\n",
+        )
+        .unwrap();
+
+        for (dir_name, buildpack_id, version) in [
+            ("matching", "example/matching", "1.4.0"),
+            ("mismatching", "example/mismatching", "2.0.0"),
+        ] {
+            fs::create_dir_all(root.join(dir_name)).unwrap();
+            fs::write(
+                root.join(dir_name).join("buildpack.toml"),
+                format!("api = \"0.10\"\n\n[buildpack]\nid = \"{buildpack_id}\"\nversion = \"{version}\"\n"),
+            )
+            .unwrap();
+        }
+
+        assert_eq!(
+            check_buildpacks(root)
+                .unwrap()
+                .problems
+                .iter()
+                .map(|problem| problem.kind.to_string())
+                .collect::<Vec<_>>(),
+            [
+                "Dependency libcnb:example/mismatching requires version >=1.0, <2, but the buildpack in this workspace has version 2.0.0",
+                "Dependency docker://docker.io/heroku/example:1.2.3 has a version requirement, which is only supported for libcnb: dependencies",
+            ]
+        );
+    }
+
+    #[test]
+    fn display_problems_relative_to_directory() {
+        let problem = Problem {
+            path: PathBuf::from("/workspace/buildpacks/one/buildpack.toml"),
+            line: Some(3),
+            kind: ProblemKind::MissingPackageDescriptor,
+        };
+
+        assert_eq!(
+            problem.to_string(),
+            "/workspace/buildpacks/one/buildpack.toml:3: Composite buildpack has no package.toml"
+        );
+        assert_eq!(
+            problem
+                .display_relative_to(Path::new("/workspace"))
+                .to_string(),
+            "buildpacks/one/buildpack.toml:3: Composite buildpack has no package.toml"
+        );
+        assert_eq!(
+            problem.display_relative_to(Path::new("/other")).to_string(),
+            "/workspace/buildpacks/one/buildpack.toml:3: Composite buildpack has no package.toml"
+        );
+    }
+
+    #[test]
+    fn find_additional_buildpack_binary_references() {
+        let source = r#"
+fn main() {
+    let path = additional_buildpack_binary_path!("dice_roller");
+    let other = additional_buildpack_binary_path!(
+        "other"
+    );
+    let dynamic = additional_buildpack_binary_path!(NAME);
+}
+"#;
+
+        assert_eq!(
+            additional_buildpack_binary_references(source),
+            [(3, String::from("dice_roller")), (4, String::from("other"))]
+        );
+    }
+}
@@ -1,6 +1,10 @@
 use indoc::{formatdoc, indoc};
+use libcnb_data::buildpack::Distro;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::env::consts;
 use std::ffi::OsString;
+use std::fmt::{Display, Formatter};
 use which::which;
 
 /// Provides assistance for cross-compiling from the user's host platform to the desired target platform.
@@ -10,51 +14,17 @@ use which::which;
 /// any other issue has been detected.
 pub fn cross_compile_assistance(target_triple: impl AsRef<str>) -> CrossCompileAssistance {
     let target_triple = target_triple.as_ref();
-    let (gcc_binary_name, help_text) = match (target_triple, consts::OS, consts::ARCH) {
-        (AARCH64_UNKNOWN_LINUX_MUSL, OS_LINUX, ARCH_X86_64) => (
-            "aarch64-linux-gnu-gcc",
-            indoc! {"
-                To install an aarch64 cross-compiler on Ubuntu:
-                sudo apt-get install g++-aarch64-linux-gnu libc6-dev-arm64-cross musl-tools
-            "},
-        ),
-        (AARCH64_UNKNOWN_LINUX_MUSL, OS_MACOS, ARCH_X86_64 | ARCH_AARCH64) => (
-            "aarch64-unknown-linux-musl-gcc",
-            indoc! {"
-                To install an aarch64 cross-compiler on macOS:
-                brew install messense/macos-cross-toolchains/aarch64-unknown-linux-musl
-            "},
-        ),
-        (AARCH64_UNKNOWN_LINUX_MUSL, OS_LINUX, ARCH_AARCH64)
-        | (X86_64_UNKNOWN_LINUX_MUSL, OS_LINUX, ARCH_X86_64) => (
-            "musl-gcc",
-            indoc! {"
-                To install musl-tools on Ubuntu:
-                sudo apt-get install musl-tools
-            "},
-        ),
-        (X86_64_UNKNOWN_LINUX_MUSL, OS_LINUX, ARCH_AARCH64) => (
-            "x86_64-linux-gnu-gcc",
-            indoc! {"
-                To install an x86_64 cross-compiler on Ubuntu:
-                sudo apt-get install g++-x86-64-linux-gnu libc6-dev-amd64-cross musl-tools
-            "},
-        ),
-        (X86_64_UNKNOWN_LINUX_MUSL, OS_MACOS, ARCH_X86_64 | ARCH_AARCH64) => (
-            "x86_64-unknown-linux-musl-gcc",
-            indoc! {"
-                To install an x86_64 cross-compiler on macOS:
-                brew install messense/macos-cross-toolchains/x86_64-unknown-linux-musl
-            "},
-        ),
-        _ => return CrossCompileAssistance::NoAssistance,
+    let Some((gcc_binary_name, help_text)) =
+        gcc_binary_name_and_help_text(target_triple, consts::OS, consts::ARCH)
+    else {
+        return CrossCompileAssistance::NoAssistance;
     };
 
     match which(gcc_binary_name) {
         Ok(_) => {
             // When the gcc binary name is `musl-gcc`, Cargo will automatically select the appropriate default linker,
-            // and set the required environment variables.
-            if gcc_binary_name == "musl-gcc" {
+            // and set the required environment variables. The same applies to native glibc builds using `cc`.
+            if gcc_binary_name == "musl-gcc" || gcc_binary_name == "cc" {
                 CrossCompileAssistance::Configuration {
                     cargo_env: Vec::new(),
                 }
@@ -95,6 +65,259 @@ pub fn cross_compile_assistance(target_triple: impl AsRef<str>) -> CrossCompileA
     }
 }
 
+// Returns the name of the gcc binary for the given target on a host with the given OS and
+// architecture (see `std::env::consts`), together with instructions on how to install it.
+#[allow(clippy::too_many_lines)]
+fn gcc_binary_name_and_help_text(
+    target_triple: &str,
+    host_os: &str,
+    host_arch: &str,
+) -> Option<(&'static str, &'static str)> {
+    match (target_triple, host_os, host_arch) {
+        (AARCH64_UNKNOWN_LINUX_MUSL, OS_LINUX, ARCH_X86_64) => Some((
+            "aarch64-linux-gnu-gcc",
+            indoc! {"
+                To install an aarch64 cross-compiler on Ubuntu:
+                sudo apt-get install g++-aarch64-linux-gnu libc6-dev-arm64-cross musl-tools
+            "},
+        )),
+        (AARCH64_UNKNOWN_LINUX_MUSL, OS_MACOS, ARCH_X86_64 | ARCH_AARCH64) => Some((
+            "aarch64-unknown-linux-musl-gcc",
+            indoc! {"
+                To install an aarch64 cross-compiler on macOS:
+                brew install messense/macos-cross-toolchains/aarch64-unknown-linux-musl
+            "},
+        )),
+        (AARCH64_UNKNOWN_LINUX_MUSL, OS_LINUX, ARCH_AARCH64)
+        | (X86_64_UNKNOWN_LINUX_MUSL, OS_LINUX, ARCH_X86_64) => Some((
+            "musl-gcc",
+            indoc! {"
+                To install musl-tools on Ubuntu:
+                sudo apt-get install musl-tools
+            "},
+        )),
+        (X86_64_UNKNOWN_LINUX_MUSL, OS_LINUX, ARCH_AARCH64) => Some((
+            "x86_64-linux-gnu-gcc",
+            indoc! {"
+                To install an x86_64 cross-compiler on Ubuntu:
+                sudo apt-get install g++-x86-64-linux-gnu libc6-dev-amd64-cross musl-tools
+            "},
+        )),
+        (X86_64_UNKNOWN_LINUX_MUSL, OS_MACOS, ARCH_X86_64 | ARCH_AARCH64) => Some((
+            "x86_64-unknown-linux-musl-gcc",
+            indoc! {"
+                To install an x86_64 cross-compiler on macOS:
+                brew install messense/macos-cross-toolchains/x86_64-unknown-linux-musl
+            "},
+        )),
+        (AARCH64_UNKNOWN_LINUX_GNU, OS_LINUX, ARCH_AARCH64)
+        | (X86_64_UNKNOWN_LINUX_GNU, OS_LINUX, ARCH_X86_64) => Some((
+            "cc",
+            indoc! {"
+                To install a C compiler on Ubuntu:
+                sudo apt-get install build-essential
+            "},
+        )),
+        (AARCH64_UNKNOWN_LINUX_GNU, OS_LINUX, ARCH_X86_64) => Some((
+            "aarch64-linux-gnu-gcc",
+            indoc! {"
+                To install an aarch64 cross-compiler on Ubuntu:
+                sudo apt-get install g++-aarch64-linux-gnu libc6-dev-arm64-cross
+            "},
+        )),
+        (AARCH64_UNKNOWN_LINUX_GNU, OS_MACOS, ARCH_X86_64 | ARCH_AARCH64) => Some((
+            "aarch64-unknown-linux-gnu-gcc",
+            indoc! {"
+                To install an aarch64 cross-compiler on macOS:
+                brew install messense/macos-cross-toolchains/aarch64-unknown-linux-gnu
+            "},
+        )),
+        (X86_64_UNKNOWN_LINUX_GNU, OS_LINUX, ARCH_AARCH64) => Some((
+            "x86_64-linux-gnu-gcc",
+            indoc! {"
+                To install an x86_64 cross-compiler on Ubuntu:
+                sudo apt-get install g++-x86-64-linux-gnu libc6-dev-amd64-cross
+            "},
+        )),
+        (X86_64_UNKNOWN_LINUX_GNU, OS_MACOS, ARCH_X86_64 | ARCH_AARCH64) => Some((
+            "x86_64-unknown-linux-gnu-gcc",
+            indoc! {"
+                To install an x86_64 cross-compiler on macOS:
+                brew install messense/macos-cross-toolchains/x86_64-unknown-linux-gnu
+            "},
+        )),
+        (ARMV7_UNKNOWN_LINUX_GNUEABIHF, OS_LINUX, ARCH_X86_64 | ARCH_AARCH64) => Some((
+            "arm-linux-gnueabihf-gcc",
+            indoc! {"
+                To install an armv7 cross-compiler on Ubuntu:
+                sudo apt-get install g++-arm-linux-gnueabihf libc6-dev-armhf-cross
+            "},
+        )),
+        (ARMV7_UNKNOWN_LINUX_MUSLEABIHF, OS_LINUX, ARCH_X86_64 | ARCH_AARCH64) => Some((
+            "arm-linux-gnueabihf-gcc",
+            indoc! {"
+                To install an armv7 cross-compiler on Ubuntu:
+                sudo apt-get install g++-arm-linux-gnueabihf libc6-dev-armhf-cross musl-tools
+            "},
+        )),
+        (ARMV7_UNKNOWN_LINUX_GNUEABIHF, OS_MACOS, ARCH_X86_64 | ARCH_AARCH64) => Some((
+            "armv7-unknown-linux-gnueabihf-gcc",
+            indoc! {"
+                To install an armv7 cross-compiler on macOS:
+                brew install messense/macos-cross-toolchains/armv7-unknown-linux-gnueabihf
+            "},
+        )),
+        (ARMV7_UNKNOWN_LINUX_MUSLEABIHF, OS_MACOS, ARCH_X86_64 | ARCH_AARCH64) => Some((
+            "armv7-unknown-linux-musleabihf-gcc",
+            indoc! {"
+                To install an armv7 cross-compiler on macOS:
+                brew install messense/macos-cross-toolchains/armv7-unknown-linux-musleabihf
+            "},
+        )),
+        (RISCV64GC_UNKNOWN_LINUX_GNU, OS_LINUX, ARCH_X86_64 | ARCH_AARCH64) => Some((
+            "riscv64-linux-gnu-gcc",
+            indoc! {"
+                To install a riscv64 cross-compiler on Ubuntu:
+                sudo apt-get install g++-riscv64-linux-gnu libc6-dev-riscv64-cross
+            "},
+        )),
+        (RISCV64GC_UNKNOWN_LINUX_MUSL, OS_LINUX, ARCH_X86_64 | ARCH_AARCH64) => Some((
+            "riscv64-linux-gnu-gcc",
+            indoc! {"
+                To install a riscv64 cross-compiler on Ubuntu:
+                sudo apt-get install g++-riscv64-linux-gnu libc6-dev-riscv64-cross musl-tools
+            "},
+        )),
+        (RISCV64GC_UNKNOWN_LINUX_GNU, OS_MACOS, ARCH_X86_64 | ARCH_AARCH64) => Some((
+            "riscv64gc-unknown-linux-gnu-gcc",
+            indoc! {"
+                To install a riscv64 cross-compiler on macOS:
+                brew install messense/macos-cross-toolchains/riscv64gc-unknown-linux-gnu
+            "},
+        )),
+        (RISCV64GC_UNKNOWN_LINUX_MUSL, OS_MACOS, ARCH_X86_64 | ARCH_AARCH64) => Some((
+            "riscv64gc-unknown-linux-musl-gcc",
+            indoc! {"
+                To install a riscv64 cross-compiler on macOS:
+                brew install messense/macos-cross-toolchains/riscv64gc-unknown-linux-musl
+            "},
+        )),
+        _ => None,
+    }
+}
+
+/// Provides assistance for cross-compiling with the given backend.
+///
+/// For [`CrossCompileBackend::Cargo`], this is the same as [`cross_compile_assistance`]. The other
+/// backends bring their own C compilers and linkers, so only the availability of their tools is
+/// checked and a human-readable help text is returned if any of them can't be found.
+pub fn cross_compile_backend_assistance(
+    backend: CrossCompileBackend,
+    target_triple: impl AsRef<str>,
+) -> CrossCompileAssistance {
+    backend_assistance(backend, target_triple.as_ref(), |tool| which(tool).is_ok())
+}
+
+fn backend_assistance(
+    backend: CrossCompileBackend,
+    target_triple: &str,
+    is_installed: impl Fn(&str) -> bool,
+) -> CrossCompileAssistance {
+    let help_text = match backend {
+        CrossCompileBackend::Cargo => return cross_compile_assistance(target_triple),
+        CrossCompileBackend::Zigbuild => formatdoc! {"
+            To install Zig and cargo-zigbuild:
+            https://ziglang.org/learn/getting-started/#installing-zig
+            cargo install --locked cargo-zigbuild
+
+            You will also need to install the Rust target:
+            rustup target add {target_triple}
+        "},
+        CrossCompileBackend::Cross => indoc! {"
+            To install cross:
+            cargo install --locked cross
+
+            cross builds in containers, so Docker or Podman must be installed and running as well.
+        "}
+        .to_string(),
+    };
+
+    let missing_tools = backend.missing_tools(is_installed);
+    if missing_tools.is_empty() {
+        return CrossCompileAssistance::Configuration {
+            cargo_env: Vec::new(),
+        };
+    }
+
+    CrossCompileAssistance::HelpText(formatdoc! {"
+        For cross-compilation to {target_triple} with {backend}, the following tools
+        must be installed: {missing_tools}
+
+        {help_text}",
+        missing_tools = missing_tools.join(", ")
+    })
+}
+
+/// The tool used to build buildpack binaries.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum CrossCompileBackend {
+    /// `cargo build`, with a C compiler and linker for the target platform installed on the host.
+    #[default]
+    Cargo,
+    /// `cargo zigbuild` from [cargo-zigbuild](https://github.com/rust-cross/cargo-zigbuild), using
+    /// Zig as the C compiler and linker for all target platforms.
+    Zigbuild,
+    /// `cross build` from [cross](https://github.com/cross-rs/cross), building in a container with
+    /// the toolchain for the target platform.
+    Cross,
+}
+
+impl CrossCompileBackend {
+    /// The program and arguments to run instead of `cargo build` when building with this backend.
+    #[must_use]
+    pub fn build_command(self) -> (&'static str, &'static [&'static str]) {
+        match self {
+            Self::Cargo => ("cargo", &["build"]),
+            Self::Zigbuild => ("cargo", &["zigbuild"]),
+            Self::Cross => ("cross", &["build"]),
+        }
+    }
+
+    /// Whether all tools required by this backend can be found. For [`CrossCompileBackend::Cargo`],
+    /// this doesn't include C compilers and linkers for the target platform, see
+    /// [`cross_compile_assistance`] for those.
+    #[must_use]
+    pub fn is_available(self) -> bool {
+        self.missing_tools(|tool| which(tool).is_ok()).is_empty()
+    }
+
+    // Returns the tools that aren't installed, according to `is_installed`. Tools that can be
+    // replaced by each other are listed together, for example `docker or podman`.
+    fn missing_tools(self, is_installed: impl Fn(&str) -> bool) -> Vec<String> {
+        let required_tools: &[&[&str]] = match self {
+            Self::Cargo => &[],
+            Self::Zigbuild => &[&["cargo-zigbuild"], &["zig"]],
+            Self::Cross => &[&["cross"], &["docker", "podman"]],
+        };
+
+        required_tools
+            .iter()
+            .filter(|alternatives| !alternatives.iter().any(|tool| is_installed(tool)))
+            .map(|alternatives| alternatives.join(" or "))
+            .collect()
+    }
+}
+
+impl Display for CrossCompileBackend {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str(match self {
+            Self::Cargo => "cargo",
+            Self::Zigbuild => "cargo-zigbuild",
+            Self::Cross => "cross",
+        })
+    }
+}
+
 pub enum CrossCompileAssistance {
     /// No specific assistance available for the current host and target platform combination.
     NoAssistance,
@@ -107,12 +330,348 @@ pub enum CrossCompileAssistance {
     },
 }
 
+/// Determines the distributions on which binaries that need the given glibc version won't run,
+/// because the distribution provides an older glibc.
+///
+/// The glibc version a binary needs is the highest `GLIBC_x.y` symbol version it references, see
+/// [`crate::binary_audit::BinaryAudit::required_glibc_version`]. Distributions with an unknown
+/// glibc version are skipped.
+#[must_use]
+pub fn glibc_incompatibilities(
+    required_glibc_version: GlibcVersion,
+    distros: &[Distro],
+) -> Vec<GlibcIncompatibility> {
+    distros
+        .iter()
+        .filter_map(|distro| {
+            distro_glibc_version(distro)
+                .filter(|distro_glibc_version| *distro_glibc_version < required_glibc_version)
+                .map(|distro_glibc_version| GlibcIncompatibility {
+                    distro: distro.clone(),
+                    distro_glibc_version,
+                    required_glibc_version,
+                })
+        })
+        .collect()
+}
+
+/// A distribution that provides an older glibc than binaries require.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GlibcIncompatibility {
+    pub distro: Distro,
+    pub distro_glibc_version: GlibcVersion,
+    /// The glibc version the binaries need.
+    pub required_glibc_version: GlibcVersion,
+}
+
+impl Display for GlibcIncompatibility {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            formatter,
+            "Binaries that need glibc {} won't run on {} {}, which provides glibc {}",
+            self.required_glibc_version,
+            self.distro.name,
+            self.distro.version,
+            self.distro_glibc_version
+        )
+    }
+}
+
+/// A glibc version such as `2.35`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct GlibcVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl GlibcVersion {
+    // Accepts versions with a patch part, such as `2.2.5`, which is ignored.
+    pub(crate) fn parse(version: &str) -> Option<Self> {
+        let mut parts = version.trim().split('.');
+
+        Some(Self {
+            major: parts.next()?.parse().ok()?,
+            minor: parts.next()?.parse().ok()?,
+        })
+    }
+}
+
+impl Display for GlibcVersion {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "{}.{}", self.major, self.minor)
+    }
+}
+
+// Serialized as a string such as `2.35`, the same format that is accepted when deserializing.
+impl Serialize for GlibcVersion {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for GlibcVersion {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let version = String::deserialize(deserializer)?;
+        Self::parse(&version)
+            .ok_or_else(|| D::Error::custom(format!("Invalid glibc version: `{version}`")))
+    }
+}
+
+/// Returns the glibc version shipped by the given distribution release, if known.
+#[must_use]
+pub fn distro_glibc_version(distro: &Distro) -> Option<GlibcVersion> {
+    let version = match (distro.name.as_str(), distro.version.as_str()) {
+        ("ubuntu", "18.04") => "2.27",
+        ("ubuntu", "20.04") | ("debian", "11") => "2.31",
+        ("ubuntu", "22.04") => "2.35",
+        ("ubuntu", "24.04") => "2.39",
+        ("ubuntu", "24.10") => "2.40",
+        ("ubuntu", "25.04") | ("debian", "13") => "2.41",
+        ("debian", "10") => "2.28",
+        ("debian", "12") => "2.36",
+        _ => return None,
+    };
+
+    GlibcVersion::parse(version)
+}
+
 // Constants for supported target triples
 const AARCH64_UNKNOWN_LINUX_MUSL: &str = "aarch64-unknown-linux-musl";
 const X86_64_UNKNOWN_LINUX_MUSL: &str = "x86_64-unknown-linux-musl";
+const AARCH64_UNKNOWN_LINUX_GNU: &str = "aarch64-unknown-linux-gnu";
+const X86_64_UNKNOWN_LINUX_GNU: &str = "x86_64-unknown-linux-gnu";
+const ARMV7_UNKNOWN_LINUX_GNUEABIHF: &str = "armv7-unknown-linux-gnueabihf";
+const ARMV7_UNKNOWN_LINUX_MUSLEABIHF: &str = "armv7-unknown-linux-musleabihf";
+const RISCV64GC_UNKNOWN_LINUX_GNU: &str = "riscv64gc-unknown-linux-gnu";
+const RISCV64GC_UNKNOWN_LINUX_MUSL: &str = "riscv64gc-unknown-linux-musl";
 
 // Constants for `std::env::consts::OS` and `std::env::consts::ARCH`
 const OS_LINUX: &str = "linux";
 const OS_MACOS: &str = "macos";
 const ARCH_X86_64: &str = "x86_64";
 const ARCH_AARCH64: &str = "aarch64";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glibc_incompatibilities_of_distros() {
+        let distro = |name: &str, version: &str| Distro {
+            name: String::from(name),
+            version: String::from(version),
+        };
+
+        let glibc_version = GlibcVersion {
+            major: 2,
+            minor: 36,
+        };
+
+        assert_eq!(
+            glibc_incompatibilities(
+                glibc_version,
+                &[
+                    distro("ubuntu", "22.04"),
+                    distro("ubuntu", "24.04"),
+                    distro("debian", "12"),
+                    distro("alpine", "3.20"),
+                ]
+            ),
+            vec![GlibcIncompatibility {
+                distro: distro("ubuntu", "22.04"),
+                distro_glibc_version: GlibcVersion {
+                    major: 2,
+                    minor: 35
+                },
+                required_glibc_version: glibc_version,
+            }]
+        );
+
+        assert_eq!(
+            glibc_incompatibilities(glibc_version, &[distro("ubuntu", "22.04")])[0].to_string(),
+            "Binaries that need glibc 2.36 won't run on ubuntu 22.04, which provides glibc 2.35"
+        );
+    }
+
+    #[test]
+    fn glibc_version_serialization() {
+        let glibc_version = GlibcVersion {
+            major: 2,
+            minor: 17,
+        };
+
+        assert_eq!(serde_json::to_string(&glibc_version).unwrap(), r#""2.17""#);
+        assert_eq!(
+            serde_json::from_str::<GlibcVersion>(r#""2.17""#).unwrap(),
+            glibc_version
+        );
+        assert!(serde_json::from_str::<GlibcVersion>(r#""2""#).is_err());
+    }
+
+    #[test]
+    fn gcc_binary_names_for_glibc_armv7_and_riscv64_targets() {
+        let gcc_binary_name = |target_triple, host_os, host_arch| {
+            gcc_binary_name_and_help_text(target_triple, host_os, host_arch)
+                .map(|(gcc_binary_name, _)| gcc_binary_name)
+        };
+
+        assert_eq!(
+            gcc_binary_name(X86_64_UNKNOWN_LINUX_GNU, OS_LINUX, ARCH_X86_64),
+            Some("cc")
+        );
+        assert_eq!(
+            gcc_binary_name(X86_64_UNKNOWN_LINUX_GNU, OS_LINUX, ARCH_AARCH64),
+            Some("x86_64-linux-gnu-gcc")
+        );
+        assert_eq!(
+            gcc_binary_name(X86_64_UNKNOWN_LINUX_GNU, OS_MACOS, ARCH_AARCH64),
+            Some("x86_64-unknown-linux-gnu-gcc")
+        );
+        assert_eq!(
+            gcc_binary_name(AARCH64_UNKNOWN_LINUX_GNU, OS_LINUX, ARCH_AARCH64),
+            Some("cc")
+        );
+        assert_eq!(
+            gcc_binary_name(AARCH64_UNKNOWN_LINUX_GNU, OS_LINUX, ARCH_X86_64),
+            Some("aarch64-linux-gnu-gcc")
+        );
+        assert_eq!(
+            gcc_binary_name(AARCH64_UNKNOWN_LINUX_GNU, OS_MACOS, ARCH_X86_64),
+            Some("aarch64-unknown-linux-gnu-gcc")
+        );
+        assert_eq!(
+            gcc_binary_name(ARMV7_UNKNOWN_LINUX_GNUEABIHF, OS_LINUX, ARCH_X86_64),
+            Some("arm-linux-gnueabihf-gcc")
+        );
+        assert_eq!(
+            gcc_binary_name(ARMV7_UNKNOWN_LINUX_GNUEABIHF, OS_MACOS, ARCH_AARCH64),
+            Some("armv7-unknown-linux-gnueabihf-gcc")
+        );
+        assert_eq!(
+            gcc_binary_name(ARMV7_UNKNOWN_LINUX_MUSLEABIHF, OS_LINUX, ARCH_AARCH64),
+            Some("arm-linux-gnueabihf-gcc")
+        );
+        assert_eq!(
+            gcc_binary_name(ARMV7_UNKNOWN_LINUX_MUSLEABIHF, OS_MACOS, ARCH_X86_64),
+            Some("armv7-unknown-linux-musleabihf-gcc")
+        );
+        assert_eq!(
+            gcc_binary_name(RISCV64GC_UNKNOWN_LINUX_GNU, OS_LINUX, ARCH_X86_64),
+            Some("riscv64-linux-gnu-gcc")
+        );
+        assert_eq!(
+            gcc_binary_name(RISCV64GC_UNKNOWN_LINUX_GNU, OS_MACOS, ARCH_AARCH64),
+            Some("riscv64gc-unknown-linux-gnu-gcc")
+        );
+        assert_eq!(
+            gcc_binary_name(RISCV64GC_UNKNOWN_LINUX_MUSL, OS_LINUX, ARCH_AARCH64),
+            Some("riscv64-linux-gnu-gcc")
+        );
+        assert_eq!(
+            gcc_binary_name(RISCV64GC_UNKNOWN_LINUX_MUSL, OS_MACOS, ARCH_X86_64),
+            Some("riscv64gc-unknown-linux-musl-gcc")
+        );
+        assert_eq!(
+            gcc_binary_name(RISCV64GC_UNKNOWN_LINUX_GNU, "windows", ARCH_X86_64),
+            None
+        );
+    }
+
+    #[test]
+    fn gcc_help_texts_mention_musl_tools_only_for_musl_targets() {
+        for (target_triple, is_musl) in [
+            (X86_64_UNKNOWN_LINUX_GNU, false),
+            (AARCH64_UNKNOWN_LINUX_GNU, false),
+            (ARMV7_UNKNOWN_LINUX_GNUEABIHF, false),
+            (ARMV7_UNKNOWN_LINUX_MUSLEABIHF, true),
+            (RISCV64GC_UNKNOWN_LINUX_GNU, false),
+            (RISCV64GC_UNKNOWN_LINUX_MUSL, true),
+        ] {
+            let (_, help_text) =
+                gcc_binary_name_and_help_text(target_triple, OS_LINUX, ARCH_AARCH64).unwrap();
+
+            assert_eq!(help_text.contains("musl-tools"), is_musl, "{target_triple}");
+        }
+    }
+
+    #[test]
+    fn backend_build_commands() {
+        assert_eq!(
+            CrossCompileBackend::Cargo.build_command(),
+            ("cargo", &["build"][..])
+        );
+        assert_eq!(
+            CrossCompileBackend::Zigbuild.build_command(),
+            ("cargo", &["zigbuild"][..])
+        );
+        assert_eq!(
+            CrossCompileBackend::Cross.build_command(),
+            ("cross", &["build"][..])
+        );
+    }
+
+    #[test]
+    fn backend_assistance_with_all_tools_installed() {
+        for backend in [CrossCompileBackend::Zigbuild, CrossCompileBackend::Cross] {
+            assert!(matches!(
+                backend_assistance(backend, "aarch64-unknown-linux-musl", |_| true),
+                CrossCompileAssistance::Configuration { cargo_env } if cargo_env.is_empty()
+            ));
+        }
+    }
+
+    #[test]
+    fn backend_assistance_with_missing_tools() {
+        let CrossCompileAssistance::HelpText(help_text) = backend_assistance(
+            CrossCompileBackend::Zigbuild,
+            "aarch64-unknown-linux-musl",
+            |_| false,
+        ) else {
+            panic!("Expected a help text");
+        };
+
+        assert_eq!(
+            help_text,
+            indoc! {"
+                For cross-compilation to aarch64-unknown-linux-musl with cargo-zigbuild, the following tools
+                must be installed: cargo-zigbuild, zig
+
+                To install Zig and cargo-zigbuild:
+                https://ziglang.org/learn/getting-started/#installing-zig
+                cargo install --locked cargo-zigbuild
+
+                You will also need to install the Rust target:
+                rustup target add aarch64-unknown-linux-musl
+            "}
+        );
+
+        // Either Docker or Podman is enough for cross.
+        let CrossCompileAssistance::HelpText(help_text) = backend_assistance(
+            CrossCompileBackend::Cross,
+            "aarch64-unknown-linux-musl",
+            |tool| tool == "podman",
+        ) else {
+            panic!("Expected a help text");
+        };
+
+        assert!(help_text.starts_with(
+            "For cross-compilation to aarch64-unknown-linux-musl with cross, the following tools\nmust be installed: cross\n"
+        ));
+
+        let CrossCompileAssistance::HelpText(help_text) = backend_assistance(
+            CrossCompileBackend::Cross,
+            "aarch64-unknown-linux-musl",
+            |tool| tool == "cross",
+        ) else {
+            panic!("Expected a help text");
+        };
+
+        assert!(help_text.contains("must be installed: docker or podman\n"));
+    }
+}
@@ -0,0 +1,299 @@
+use libcnb_data::buildpack::{
+    BuildpackId, BuildpackIdError, BuildpackVersion, BuildpackVersionError,
+};
+use libcnb_data::package_descriptor::PackageDescriptorDependency;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+/// A typed view of the URI of a `package.toml` dependency.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DependencyReference {
+    /// A libcnb.rs buildpack in the same workspace, referenced via `libcnb:<buildpack id>`.
+    Libcnb(BuildpackId),
+    /// A buildpack directory, relative to the `package.toml` file if the path isn't absolute.
+    Path(PathBuf),
+    /// A buildpack image, referenced via `docker://<image>`.
+    DockerImage(DockerImageReference),
+    /// A buildpack from the buildpack registry, referenced via `urn:cnb:registry:<id>[@<version>]`.
+    RegistryBuildpack(RegistryBuildpackReference),
+    /// Any other URI, such as `file:` or `https:` URIs of buildpack archives.
+    Other(String),
+}
+
+impl DependencyReference {
+    /// Parses the URI of a `package.toml` dependency.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the dependency is a `libcnb:`, `docker:` or registry reference that
+    /// can't be parsed.
+    pub fn parse(
+        dependency: &PackageDescriptorDependency,
+    ) -> Result<Self, ParseDependencyReferenceError> {
+        let uri = dependency.uri.to_string();
+
+        match dependency.uri.scheme().map(uriparse::Scheme::as_str) {
+            None => Ok(Self::Path(PathBuf::from(dependency.uri.path().to_string()))),
+            Some("libcnb") => dependency
+                .uri
+                .path()
+                .to_string()
+                .parse()
+                .map(Self::Libcnb)
+                .map_err(ParseDependencyReferenceError::InvalidLibcnbBuildpackId),
+            Some("docker") => DockerImageReference::parse(
+                uri.trim_start_matches("docker:").trim_start_matches('/'),
+            )
+            .map(Self::DockerImage)
+            .ok_or(ParseDependencyReferenceError::InvalidDockerImageReference(
+                uri.clone(),
+            )),
+            Some("urn") => match uri.strip_prefix(REGISTRY_URN_PREFIX) {
+                Some(reference) => {
+                    RegistryBuildpackReference::parse(reference).map(Self::RegistryBuildpack)
+                }
+                None => Ok(Self::Other(uri)),
+            },
+            Some(_) => Ok(Self::Other(uri)),
+        }
+    }
+
+    /// Whether the dependency always resolves to the same buildpack.
+    ///
+    /// Only images without a tag or digest, images tagged `latest` and registry buildpacks without
+    /// a version are considered unpinned.
+    #[must_use]
+    pub fn is_pinned(&self) -> bool {
+        match self {
+            Self::DockerImage(reference) => reference.is_pinned(),
+            Self::RegistryBuildpack(reference) => reference.is_pinned(),
+            Self::Libcnb(_) | Self::Path(_) | Self::Other(_) => true,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ParseDependencyReferenceError {
+    #[error("Dependency uses an invalid buildpack ID: {0}")]
+    InvalidLibcnbBuildpackId(BuildpackIdError),
+    #[error("Dependency {0} isn't a valid image reference")]
+    InvalidDockerImageReference(String),
+    #[error("Registry dependency uses an invalid buildpack ID: {0}")]
+    InvalidRegistryBuildpackId(BuildpackIdError),
+    #[error("Registry dependency uses an invalid version: {0}")]
+    InvalidRegistryBuildpackVersion(BuildpackVersionError),
+}
+
+/// A buildpack image such as `docker.io/heroku/procfile-cnb:3.1.2`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DockerImageReference {
+    /// The image name including the registry, without tag or digest.
+    pub name: String,
+    pub tag: Option<String>,
+    /// The digest, including the algorithm, for example `sha256:...`.
+    pub digest: Option<String>,
+}
+
+impl DockerImageReference {
+    fn parse(reference: &str) -> Option<Self> {
+        let (name_and_tag, digest) = match reference.split_once('@') {
+            Some((name_and_tag, digest)) => (name_and_tag, Some(digest)),
+            None => (reference, None),
+        };
+
+        // A colon followed by a path is a registry port (`localhost:5000/image`), not a tag.
+        let (name, tag) = match name_and_tag.rsplit_once(':') {
+            Some((name, tag)) if !tag.contains('/') => (name, Some(tag)),
+            _ => (name_and_tag, None),
+        };
+
+        let is_valid = !name.is_empty()
+            && !tag.is_some_and(str::is_empty)
+            && !digest.is_some_and(|digest| {
+                digest.split_once(':').map_or(true, |(algorithm, hex)| {
+                    algorithm.is_empty() || hex.is_empty()
+                })
+            });
+
+        is_valid.then(|| Self {
+            name: String::from(name),
+            tag: tag.map(String::from),
+            digest: digest.map(String::from),
+        })
+    }
+
+    /// Whether the image has a digest or a tag other than `latest`.
+    #[must_use]
+    pub fn is_pinned(&self) -> bool {
+        self.digest.is_some() || self.tag.as_ref().is_some_and(|tag| tag != "latest")
+    }
+}
+
+impl Display for DockerImageReference {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "docker://{}", self.name)?;
+
+        if let Some(tag) = &self.tag {
+            write!(formatter, ":{tag}")?;
+        }
+
+        if let Some(digest) = &self.digest {
+            write!(formatter, "@{digest}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A buildpack from the buildpack registry such as `heroku/procfile@3.1.2`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RegistryBuildpackReference {
+    pub buildpack_id: BuildpackId,
+    pub version: Option<BuildpackVersion>,
+}
+
+impl RegistryBuildpackReference {
+    fn parse(reference: &str) -> Result<Self, ParseDependencyReferenceError> {
+        let (buildpack_id, version) = match reference.split_once('@') {
+            Some((buildpack_id, version)) => (buildpack_id, Some(version)),
+            None => (reference, None),
+        };
+
+        Ok(Self {
+            buildpack_id: buildpack_id
+                .parse()
+                .map_err(ParseDependencyReferenceError::InvalidRegistryBuildpackId)?,
+            version: version
+                .map(|version| BuildpackVersion::try_from(String::from(version)))
+                .transpose()
+                .map_err(ParseDependencyReferenceError::InvalidRegistryBuildpackVersion)?,
+        })
+    }
+
+    /// Whether a specific version of the buildpack is referenced.
+    #[must_use]
+    pub fn is_pinned(&self) -> bool {
+        self.version.is_some()
+    }
+}
+
+impl Display for RegistryBuildpackReference {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "{REGISTRY_URN_PREFIX}{}", self.buildpack_id)?;
+
+        if let Some(version) = &self.version {
+            write!(formatter, "@{version}")?;
+        }
+
+        Ok(())
+    }
+}
+
+const REGISTRY_URN_PREFIX: &str = "urn:cnb:registry:";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libcnb_data::buildpack_id;
+
+    fn parse(uri: &str) -> Result<DependencyReference, ParseDependencyReferenceError> {
+        DependencyReference::parse(&PackageDescriptorDependency::try_from(uri).unwrap())
+    }
+
+    #[test]
+    fn parse_dependency_references() {
+        assert_eq!(
+            parse("libcnb:heroku/example").unwrap(),
+            DependencyReference::Libcnb(buildpack_id!("heroku/example"))
+        );
+        assert_eq!(
+            parse("../example").unwrap(),
+            DependencyReference::Path(PathBuf::from("../example"))
+        );
+        assert_eq!(
+            parse("https://example.com/buildpack.cnb").unwrap(),
+            DependencyReference::Other(String::from("https://example.com/buildpack.cnb"))
+        );
+        assert_eq!(
+            parse("urn:cnb:builder:heroku/example").unwrap(),
+            DependencyReference::Other(String::from("urn:cnb:builder:heroku/example"))
+        );
+
+        for (uri, name, tag, digest) in [
+            (
+                "docker://docker.io/heroku/example",
+                "docker.io/heroku/example",
+                None,
+                None,
+            ),
+            (
+                "docker://heroku/example:1.2.3",
+                "heroku/example",
+                Some("1.2.3"),
+                None,
+            ),
+            (
+                "docker://localhost:5000/example@sha256:abc",
+                "localhost:5000/example",
+                None,
+                Some("sha256:abc"),
+            ),
+            (
+                "docker://heroku/example:latest@sha256:abc",
+                "heroku/example",
+                Some("latest"),
+                Some("sha256:abc"),
+            ),
+        ] {
+            let expected = DockerImageReference {
+                name: String::from(name),
+                tag: tag.map(String::from),
+                digest: digest.map(String::from),
+            };
+            assert_eq!(expected.to_string(), uri);
+            assert_eq!(
+                parse(uri).unwrap(),
+                DependencyReference::DockerImage(expected)
+            );
+        }
+
+        let registry_reference = RegistryBuildpackReference {
+            buildpack_id: buildpack_id!("heroku/example"),
+            version: Some(BuildpackVersion::new(1, 2, 3)),
+        };
+        assert_eq!(
+            registry_reference.to_string(),
+            "urn:cnb:registry:heroku/example@1.2.3"
+        );
+        assert_eq!(
+            parse("urn:cnb:registry:heroku/example@1.2.3").unwrap(),
+            DependencyReference::RegistryBuildpack(registry_reference)
+        );
+
+        assert!(matches!(
+            parse("docker://heroku/example@sha256"),
+            Err(ParseDependencyReferenceError::InvalidDockerImageReference(
+                _
+            ))
+        ));
+        assert!(matches!(
+            parse("urn:cnb:registry:heroku/example@latest"),
+            Err(ParseDependencyReferenceError::InvalidRegistryBuildpackVersion(_))
+        ));
+    }
+
+    #[test]
+    fn dependency_references_are_pinned() {
+        for (uri, is_pinned) in [
+            ("libcnb:heroku/example", true),
+            ("docker://heroku/example", false),
+            ("docker://heroku/example:latest", false),
+            ("docker://heroku/example:1.2.3", true),
+            ("docker://heroku/example:latest@sha256:abc", true),
+            ("urn:cnb:registry:heroku/example", false),
+            ("urn:cnb:registry:heroku/example@1.2.3", true),
+        ] {
+            assert_eq!(parse(uri).unwrap().is_pinned(), is_pinned, "{uri}");
+        }
+    }
+}
@@ -1,15 +1,26 @@
 #![doc = include_str!("../README.md")]
 
+pub mod assets;
+pub mod binary_audit;
 pub mod build;
+pub mod build_plan_resolution;
 pub mod buildpack_dependency_graph;
 pub mod buildpack_kind;
+pub mod buildpackage;
 pub mod cargo;
+pub mod check;
 pub mod cross_compile;
 pub mod dependency_graph;
+pub mod dependency_reference;
+pub mod multi_target;
+pub mod order_resolution;
 pub mod output;
+pub mod pack;
 pub mod package;
 pub mod package_descriptor;
+pub mod reproducible;
 pub mod util;
+pub mod workspace_graph;
 
 use crate::build::BuildpackBinaries;
 use std::fs;
@@ -71,10 +82,32 @@ fn assemble_buildpack_directory(
 
         for (binary_target_name, binary_path) in &buildpack_binaries.additional_target_binary_paths
         {
-            fs::copy(
-                binary_path,
-                additional_binaries_dir.join(binary_target_name),
-            )?;
+            match buildpack_binaries
+                .additional_binary_packaged_paths
+                .get(binary_target_name)
+            {
+                // Binaries placed elsewhere are linked, so `additional_buildpack_binary_path!`
+                // keeps working for them.
+                Some(packaged_path) => {
+                    let packaged_binary_path = destination_path.as_ref().join(packaged_path);
+
+                    if let Some(parent) = packaged_binary_path.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+
+                    fs::copy(binary_path, packaged_binary_path)?;
+                    create_file_symlink(
+                        Path::new("../..").join(packaged_path),
+                        additional_binaries_dir.join(binary_target_name),
+                    )?;
+                }
+                None => {
+                    fs::copy(
+                        binary_path,
+                        additional_binaries_dir.join(binary_target_name),
+                    )?;
+                }
+            }
         }
     }
 
@@ -0,0 +1,539 @@
+use crate::create_file_symlink;
+use crate::reproducible::{normalize_buildpack_directory, write_buildpack_manifest};
+use libcnb_common::toml_file::{read_toml_file, write_toml_file, TomlFileError};
+use libcnb_data::buildpack::{BuildpackDescriptor, BuildpackTarget, Distro};
+use libcnb_data::package_descriptor::{PackageDescriptor, PackageDescriptorPlatform};
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uriparse::URIReference;
+
+/// A platform in the format used by Cloud Native Buildpacks, i.e. OS and architecture names as used
+/// by Go and OCI images.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct TargetPlatform {
+    pub os: String,
+    pub arch: String,
+    pub variant: Option<String>,
+}
+
+impl TargetPlatform {
+    /// Determines the platform of the given Rust target triple.
+    ///
+    /// Returns `None` for target triples that are not supported by libcnb.rs.
+    #[must_use]
+    pub fn from_target_triple(target_triple: impl AsRef<str>) -> Option<Self> {
+        match target_triple.as_ref() {
+            X86_64_UNKNOWN_LINUX_MUSL | X86_64_UNKNOWN_LINUX_GNU => {
+                Some(Self::new(OS_LINUX, ARCH_AMD64, None))
+            }
+            AARCH64_UNKNOWN_LINUX_MUSL | AARCH64_UNKNOWN_LINUX_GNU => {
+                Some(Self::new(OS_LINUX, ARCH_ARM64, None))
+            }
+            ARMV7_UNKNOWN_LINUX_MUSLEABIHF | ARMV7_UNKNOWN_LINUX_GNUEABIHF => {
+                Some(Self::new(OS_LINUX, ARCH_ARM, Some("v7")))
+            }
+            RISCV64GC_UNKNOWN_LINUX_MUSL | RISCV64GC_UNKNOWN_LINUX_GNU => {
+                Some(Self::new(OS_LINUX, ARCH_RISCV64, None))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the Rust target triple libcnb.rs compiles to for this platform. This is always a
+    /// statically linked musl target, glibc targets have to be selected explicitly.
+    ///
+    /// Returns `None` for platforms that are not supported by libcnb.rs.
+    #[must_use]
+    pub fn target_triple(&self) -> Option<&'static str> {
+        match (
+            self.os.as_str(),
+            self.arch.as_str(),
+            self.variant.as_deref(),
+        ) {
+            (OS_LINUX, ARCH_AMD64, None) => Some(X86_64_UNKNOWN_LINUX_MUSL),
+            (OS_LINUX, ARCH_ARM64, None | Some("v8")) => Some(AARCH64_UNKNOWN_LINUX_MUSL),
+            (OS_LINUX, ARCH_ARM, Some("v7")) => Some(ARMV7_UNKNOWN_LINUX_MUSLEABIHF),
+            (OS_LINUX, ARCH_RISCV64, None) => Some(RISCV64GC_UNKNOWN_LINUX_MUSL),
+            _ => None,
+        }
+    }
+
+    /// The relative path of this platform's directory within a multi-target buildpack directory,
+    /// for example `linux/amd64`.
+    #[must_use]
+    pub fn directory_path(&self) -> PathBuf {
+        [Some(&self.os), Some(&self.arch), self.variant.as_ref()]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    fn new(os: &str, arch: &str, variant: Option<&str>) -> Self {
+        Self {
+            os: String::from(os),
+            arch: String::from(arch),
+            variant: variant.map(String::from),
+        }
+    }
+}
+
+impl Display for TargetPlatform {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "{}/{}", self.os, self.arch)?;
+
+        if let Some(variant) = &self.variant {
+            write!(formatter, "/{variant}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the `[[targets]]` of the buildpack in the given directory and returns the Rust target
+/// triples they correspond to, in declaration order and without duplicates.
+///
+/// Targets that libcnb.rs can't compile for, such as targets without an architecture or for other
+/// operating systems than Linux, are skipped. Returns an empty list for composite buildpacks and
+/// buildpacks that don't declare any supported targets.
+///
+/// # Errors
+///
+/// Will return `Err` if the buildpack descriptor couldn't be read.
+pub fn buildpack_target_triples(
+    buildpack_dir: &Path,
+) -> Result<Vec<String>, BuildpackTargetTriplesError> {
+    let buildpack_descriptor =
+        read_toml_file::<BuildpackDescriptor>(buildpack_dir.join("buildpack.toml"))
+            .map_err(BuildpackTargetTriplesError::CannotReadBuildpackDescriptor)?;
+
+    let BuildpackDescriptor::Component(component_buildpack_descriptor) = buildpack_descriptor
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut target_triples = Vec::new();
+
+    for target in &component_buildpack_descriptor.targets {
+        let Some(target_triple) = target_platform_from_buildpack_target(target)
+            .and_then(|target_platform| target_platform.target_triple())
+        else {
+            continue;
+        };
+
+        if !target_triples
+            .iter()
+            .any(|existing| existing == target_triple)
+        {
+            target_triples.push(String::from(target_triple));
+        }
+    }
+
+    Ok(target_triples)
+}
+
+/// Reads the `[[targets]]` of the buildpack in the given directory and returns the distributions
+/// declared for the platform of the given Rust target triple.
+///
+/// Returns an empty list for composite buildpacks, unsupported target triples and targets that
+/// don't declare any distributions.
+///
+/// # Errors
+///
+/// Will return `Err` if the buildpack descriptor couldn't be read.
+pub fn buildpack_target_distros(
+    buildpack_dir: &Path,
+    target_triple: impl AsRef<str>,
+) -> Result<Vec<Distro>, BuildpackTargetTriplesError> {
+    let buildpack_descriptor =
+        read_toml_file::<BuildpackDescriptor>(buildpack_dir.join("buildpack.toml"))
+            .map_err(BuildpackTargetTriplesError::CannotReadBuildpackDescriptor)?;
+
+    let (BuildpackDescriptor::Component(component_buildpack_descriptor), Some(target_platform)) = (
+        buildpack_descriptor,
+        TargetPlatform::from_target_triple(target_triple),
+    ) else {
+        return Ok(Vec::new());
+    };
+
+    let mut distros = Vec::new();
+
+    for target in &component_buildpack_descriptor.targets {
+        let matches_target_platform =
+            target_platform_from_buildpack_target(target).is_some_and(|platform| {
+                platform.os == target_platform.os
+                    && platform.arch == target_platform.arch
+                    && (platform.variant.is_none()
+                        || target_platform.variant.is_none()
+                        || platform.variant == target_platform.variant)
+            });
+
+        if matches_target_platform {
+            for distro in &target.distros {
+                if !distros.contains(distro) {
+                    distros.push(distro.clone());
+                }
+            }
+        }
+    }
+
+    Ok(distros)
+}
+
+pub(crate) fn target_platform_from_buildpack_target(
+    target: &BuildpackTarget,
+) -> Option<TargetPlatform> {
+    Some(TargetPlatform {
+        os: target.os.clone()?,
+        arch: target.arch.clone()?,
+        variant: target.variant.clone(),
+    })
+}
+
+/// Returns the given targets that aren't supported by any of the targets of a dependency.
+///
+/// A dependency target supports a target if its OS, architecture and variant are either unset or
+/// equal to the ones of the target. If the dependency target lists distributions, the target must
+/// list distributions as well and all of them must be supported. Dependencies without any targets
+/// are considered to support all targets, the same way the lifecycle treats them.
+#[must_use]
+pub fn unsupported_targets<'a>(
+    targets: &'a [BuildpackTarget],
+    dependency_targets: &[BuildpackTarget],
+) -> Vec<&'a BuildpackTarget> {
+    if dependency_targets.is_empty() {
+        return Vec::new();
+    }
+
+    targets
+        .iter()
+        .filter(|target| {
+            !dependency_targets
+                .iter()
+                .any(|dependency_target| target_supports(dependency_target, target))
+        })
+        .collect()
+}
+
+fn target_supports(dependency_target: &BuildpackTarget, target: &BuildpackTarget) -> bool {
+    let field_supports = |dependency_value: &Option<String>, value: &Option<String>| {
+        dependency_value.is_none() || dependency_value == value
+    };
+
+    field_supports(&dependency_target.os, &target.os)
+        && field_supports(&dependency_target.arch, &target.arch)
+        && field_supports(&dependency_target.variant, &target.variant)
+        && (dependency_target.distros.is_empty()
+            || (!target.distros.is_empty()
+                && target
+                    .distros
+                    .iter()
+                    .all(|distro| dependency_target.distros.contains(distro))))
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum BuildpackTargetTriplesError {
+    #[error("Couldn't read buildpack.toml: {0}")]
+    CannotReadBuildpackDescriptor(TomlFileError),
+}
+
+/// Assembles a multi-target buildpack directory from buildpack directories packaged for single
+/// targets.
+///
+/// Each packaged buildpack directory is copied to a per-platform directory (see
+/// [`TargetPlatform::directory_path`]). The `buildpack.toml` of the first packaged buildpack
+/// directory is copied to the root, next to a `package.toml` that lists all platforms in
+/// `[[targets]]` and their directories in `[[platforms]]`. Like single-target packaged buildpack
+/// directories, the directory is normalized and has a manifest of all files (see
+/// [`crate::reproducible`]).
+///
+/// # Errors
+///
+/// Will return `Err` if any I/O errors happen while assembling the directory.
+pub fn assemble_multi_target_buildpack_directory(
+    destination: &Path,
+    packaged_buildpack_dirs: &[(TargetPlatform, PathBuf)],
+) -> Result<(), AssembleMultiTargetBuildpackDirectoryError> {
+    let _ = fs::remove_dir_all(destination);
+    fs::create_dir_all(destination)
+        .map_err(AssembleMultiTargetBuildpackDirectoryError::CannotCreateDirectory)?;
+
+    let mut platforms = Vec::new();
+    let mut targets = Vec::new();
+
+    for (target_platform, packaged_buildpack_dir) in packaged_buildpack_dirs {
+        let platform_dir_path = target_platform.directory_path();
+
+        copy_dir_recursively(
+            packaged_buildpack_dir,
+            &destination.join(&platform_dir_path),
+        )
+        .map_err(AssembleMultiTargetBuildpackDirectoryError::CannotCopyBuildpackDirectory)?;
+
+        platforms.push(PackageDescriptorPlatform {
+            os: target_platform.os.clone(),
+            arch: target_platform.arch.clone(),
+            variant: target_platform.variant.clone(),
+            uri: URIReference::try_from(platform_dir_path.to_string_lossy().as_ref())
+                .map(URIReference::into_owned)
+                .map_err(|_| {
+                    AssembleMultiTargetBuildpackDirectoryError::InvalidPlatformUri(
+                        platform_dir_path.clone(),
+                    )
+                })?,
+        });
+
+        targets.push(BuildpackTarget {
+            os: Some(target_platform.os.clone()),
+            arch: Some(target_platform.arch.clone()),
+            variant: target_platform.variant.clone(),
+            distros: Vec::new(),
+        });
+    }
+
+    if let Some((_, packaged_buildpack_dir)) = packaged_buildpack_dirs.first() {
+        fs::copy(
+            packaged_buildpack_dir.join("buildpack.toml"),
+            destination.join("buildpack.toml"),
+        )
+        .map_err(AssembleMultiTargetBuildpackDirectoryError::CannotCopyBuildpackDescriptor)?;
+    }
+
+    write_toml_file(
+        &PackageDescriptor {
+            targets,
+            platforms,
+            ..PackageDescriptor::default()
+        },
+        destination.join("package.toml"),
+    )
+    .map_err(AssembleMultiTargetBuildpackDirectoryError::CannotWritePackageDescriptor)?;
+
+    write_buildpack_manifest(destination)
+        .and_then(|()| normalize_buildpack_directory(destination))
+        .map_err(AssembleMultiTargetBuildpackDirectoryError::CannotNormalizeDirectory)
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum AssembleMultiTargetBuildpackDirectoryError {
+    #[error("Couldn't create multi-target buildpack directory: {0}")]
+    CannotCreateDirectory(std::io::Error),
+    #[error("Couldn't copy packaged buildpack directory: {0}")]
+    CannotCopyBuildpackDirectory(std::io::Error),
+    #[error("Couldn't copy buildpack.toml: {0}")]
+    CannotCopyBuildpackDescriptor(std::io::Error),
+    #[error("Platform directory path {} is not a valid URI", .0.display())]
+    InvalidPlatformUri(PathBuf),
+    #[error("Couldn't write package.toml: {0}")]
+    CannotWritePackageDescriptor(TomlFileError),
+    #[error("Couldn't normalize multi-target buildpack directory: {0}")]
+    CannotNormalizeDirectory(std::io::Error),
+}
+
+// Symlinks are recreated rather than followed, since packaged buildpacks use them for `bin/detect`.
+fn copy_dir_recursively(source: &Path, destination: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(destination)?;
+
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let destination_path = destination.join(entry.file_name());
+
+        if file_type.is_dir() {
+            copy_dir_recursively(&entry.path(), &destination_path)?;
+        } else if file_type.is_symlink() {
+            create_file_symlink(fs::read_link(entry.path())?, &destination_path)?;
+        } else {
+            fs::copy(entry.path(), &destination_path)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Constants for supported target triples
+const AARCH64_UNKNOWN_LINUX_MUSL: &str = "aarch64-unknown-linux-musl";
+const X86_64_UNKNOWN_LINUX_MUSL: &str = "x86_64-unknown-linux-musl";
+const AARCH64_UNKNOWN_LINUX_GNU: &str = "aarch64-unknown-linux-gnu";
+const X86_64_UNKNOWN_LINUX_GNU: &str = "x86_64-unknown-linux-gnu";
+const ARMV7_UNKNOWN_LINUX_GNUEABIHF: &str = "armv7-unknown-linux-gnueabihf";
+const ARMV7_UNKNOWN_LINUX_MUSLEABIHF: &str = "armv7-unknown-linux-musleabihf";
+const RISCV64GC_UNKNOWN_LINUX_GNU: &str = "riscv64gc-unknown-linux-gnu";
+const RISCV64GC_UNKNOWN_LINUX_MUSL: &str = "riscv64gc-unknown-linux-musl";
+
+// Constants for OS and architecture names as used by Cloud Native Buildpacks
+const OS_LINUX: &str = "linux";
+const ARCH_AMD64: &str = "amd64";
+const ARCH_ARM64: &str = "arm64";
+const ARCH_ARM: &str = "arm";
+const ARCH_RISCV64: &str = "riscv64";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn target_platform_from_target_triple() {
+        assert_eq!(
+            TargetPlatform::from_target_triple("x86_64-unknown-linux-musl"),
+            Some(TargetPlatform::new("linux", "amd64", None))
+        );
+        assert_eq!(
+            TargetPlatform::from_target_triple("aarch64-unknown-linux-musl"),
+            Some(TargetPlatform::new("linux", "arm64", None))
+        );
+        assert_eq!(
+            TargetPlatform::from_target_triple("x86_64-unknown-linux-gnu"),
+            Some(TargetPlatform::new("linux", "amd64", None))
+        );
+        assert_eq!(
+            TargetPlatform::from_target_triple("armv7-unknown-linux-gnueabihf"),
+            Some(TargetPlatform::new("linux", "arm", Some("v7")))
+        );
+        assert_eq!(
+            TargetPlatform::from_target_triple("riscv64gc-unknown-linux-musl"),
+            Some(TargetPlatform::new("linux", "riscv64", None))
+        );
+        assert_eq!(
+            TargetPlatform::from_target_triple("x86_64-pc-windows-msvc"),
+            None
+        );
+    }
+
+    #[test]
+    fn target_platform_target_triple() {
+        assert_eq!(
+            TargetPlatform::new("linux", "amd64", None).target_triple(),
+            Some("x86_64-unknown-linux-musl")
+        );
+        assert_eq!(
+            TargetPlatform::new("linux", "arm64", Some("v8")).target_triple(),
+            Some("aarch64-unknown-linux-musl")
+        );
+        assert_eq!(
+            TargetPlatform::new("linux", "arm", Some("v7")).target_triple(),
+            Some("armv7-unknown-linux-musleabihf")
+        );
+        assert_eq!(
+            TargetPlatform::new("linux", "riscv64", None).target_triple(),
+            Some("riscv64gc-unknown-linux-musl")
+        );
+        assert_eq!(
+            TargetPlatform::new("windows", "amd64", None).target_triple(),
+            None
+        );
+    }
+
+    #[test]
+    fn target_platform_directory_path() {
+        assert_eq!(
+            TargetPlatform::new("linux", "amd64", None).directory_path(),
+            PathBuf::from("linux/amd64")
+        );
+        assert_eq!(
+            TargetPlatform::new("linux", "arm64", Some("v8")).directory_path(),
+            PathBuf::from("linux/arm64/v8")
+        );
+        assert_eq!(
+            TargetPlatform::new("linux", "arm64", Some("v8")).to_string(),
+            "linux/arm64/v8"
+        );
+    }
+
+    #[test]
+    fn buildpack_target_triples_skip_unsupported_targets() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("buildpack.toml"),
+            r#"
+api = "0.10"
+
+[buildpack]
+id = "foo"
+version = "0.0.0"
+
+[[targets]]
+os = "linux"
+
+[[targets]]
+os = "windows"
+arch = "amd64"
+
+[[targets]]
+os = "linux"
+arch = "arm64"
+
+[[targets]]
+os = "linux"
+arch = "amd64"
+
+[[targets]]
+os = "linux"
+arch = "arm64"
+variant = "v8"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            buildpack_target_triples(temp_dir.path()).unwrap(),
+            ["aarch64-unknown-linux-musl", "x86_64-unknown-linux-musl"]
+        );
+    }
+
+    #[test]
+    fn unsupported_dependency_targets() {
+        let target = |os: &str, arch: &str, variant: Option<&str>, distros: &[(&str, &str)]| {
+            BuildpackTarget {
+                os: Some(String::from(os)),
+                arch: Some(String::from(arch)),
+                variant: variant.map(String::from),
+                distros: distros
+                    .iter()
+                    .map(|(name, version)| libcnb_data::buildpack::Distro {
+                        name: String::from(*name),
+                        version: String::from(*version),
+                    })
+                    .collect(),
+            }
+        };
+
+        let targets = [
+            target("linux", "amd64", None, &[]),
+            target("linux", "arm64", Some("v8"), &[("ubuntu", "24.04")]),
+            target("linux", "arm64", None, &[]),
+        ];
+
+        assert!(unsupported_targets(&targets, &[]).is_empty());
+        assert!(unsupported_targets(
+            &targets,
+            &[BuildpackTarget {
+                os: Some(String::from("linux")),
+                arch: None,
+                variant: None,
+                distros: Vec::new(),
+            }]
+        )
+        .is_empty());
+        assert_eq!(
+            unsupported_targets(&targets, &[target("linux", "amd64", None, &[])]),
+            [&targets[1], &targets[2]]
+        );
+        assert_eq!(
+            unsupported_targets(
+                &targets,
+                &[
+                    target("linux", "amd64", None, &[]),
+                    target(
+                        "linux",
+                        "arm64",
+                        Some("v8"),
+                        &[("ubuntu", "22.04"), ("ubuntu", "24.04")]
+                    ),
+                ]
+            ),
+            [&targets[2]]
+        );
+    }
+}
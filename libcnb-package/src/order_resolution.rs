@@ -0,0 +1,429 @@
+use crate::build_plan_resolution::{resolve_build_plans, BuildPlanResolutionError};
+use libcnb_data::build_plan::BuildPlan;
+use libcnb_data::buildpack::{BuildpackId, Group, Order};
+use libcnb_data::buildpack_plan::BuildpackPlan;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+/// The outcome of running detect for a single component buildpack.
+#[derive(Debug)]
+pub enum DetectOutcome {
+    /// Detection passed with the given build plan.
+    Pass(BuildPlan),
+    /// Detection failed.
+    Fail,
+}
+
+/// Simulates how the lifecycle resolves the given order during the detect phase.
+///
+/// Groups are tried in order until one of them passes, mirroring the lifecycle:
+///
+/// - Buildpacks that fail detection are removed from the group if they are optional, otherwise
+///   the whole group fails.
+/// - The build plans of the remaining buildpacks are resolved with [`resolve_build_plans`].
+///
+/// Each group entry must refer to a buildpack with an entry in `detect_outcomes`, nested composite
+/// buildpacks are not expanded.
+///
+/// # Example
+/// ```
+/// use libcnb_data::build_plan::BuildPlanBuilder;
+/// use libcnb_data::buildpack::{BuildpackVersion, Group, Order};
+/// use libcnb_data::buildpack_id;
+/// use libcnb_package::order_resolution::{resolve_order, DetectOutcome};
+/// use std::collections::HashMap;
+///
+/// let group = |id| Group {
+///     id,
+///     version: BuildpackVersion::new(1, 0, 0),
+///     optional: false,
+/// };
+///
+/// let order = vec![
+///     Order {
+///         group: vec![group(buildpack_id!("example/ruby"))],
+///     },
+///     Order {
+///         group: vec![
+///             group(buildpack_id!("example/nodejs-engine")),
+///             group(buildpack_id!("example/npm")),
+///         ],
+///     },
+/// ];
+///
+/// let detect_outcomes = HashMap::from([
+///     (buildpack_id!("example/ruby"), DetectOutcome::Fail),
+///     (
+///         buildpack_id!("example/nodejs-engine"),
+///         DetectOutcome::Pass(BuildPlanBuilder::new().provides("node").build()),
+///     ),
+///     (
+///         buildpack_id!("example/npm"),
+///         DetectOutcome::Pass(BuildPlanBuilder::new().requires("node").build()),
+///     ),
+/// ]);
+///
+/// let resolution = resolve_order(&order, &detect_outcomes);
+/// let (index, resolved_group) = resolution.passing_group().unwrap();
+///
+/// assert_eq!(index, 1);
+/// assert_eq!(resolved_group.buildpacks.len(), 2);
+/// ```
+#[must_use]
+pub fn resolve_order<S: BuildHasher>(
+    order: &[Order],
+    detect_outcomes: &HashMap<BuildpackId, DetectOutcome, S>,
+) -> OrderResolution {
+    let mut groups = Vec::new();
+
+    for order_entry in order {
+        let group_resolution = resolve_group(&order_entry.group, detect_outcomes);
+        let passed = matches!(group_resolution, GroupResolution::Pass(_));
+        groups.push(group_resolution);
+
+        if passed {
+            break;
+        }
+    }
+
+    OrderResolution { groups }
+}
+
+/// The result of [`resolve_order`].
+#[derive(Debug)]
+pub struct OrderResolution {
+    /// The resolution of each group that was tried, in order.
+    ///
+    /// Groups after the first passing group are not tried and therefore not included.
+    pub groups: Vec<GroupResolution>,
+}
+
+impl OrderResolution {
+    /// Returns the index and details of the passing group, if any.
+    #[must_use]
+    pub fn passing_group(&self) -> Option<(usize, &ResolvedGroup)> {
+        self.groups.iter().enumerate().find_map(
+            |(index, group_resolution)| match group_resolution {
+                GroupResolution::Pass(resolved_group) => Some((index, resolved_group)),
+                GroupResolution::Fail(_) => None,
+            },
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum GroupResolution {
+    /// The group passed detection.
+    Pass(ResolvedGroup),
+    /// The group failed detection for the given reasons.
+    ///
+    /// When several combinations of build plan alternatives were tried, the reasons are those of
+    /// the last combination.
+    Fail(Vec<GroupFailureReason>),
+}
+
+/// A group that passed detection.
+#[derive(Debug)]
+pub struct ResolvedGroup {
+    /// The buildpacks that take part in the build, in order.
+    pub buildpacks: Vec<Group>,
+    /// The optional buildpacks that were removed from the group.
+    pub skipped_buildpacks: Vec<BuildpackId>,
+    /// The buildpack plan each buildpack in [`buildpacks`](Self::buildpacks) receives.
+    pub buildpack_plans: HashMap<BuildpackId, BuildpackPlan>,
+}
+
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
+pub enum GroupFailureReason {
+    #[error("No detect outcome was given for buildpack {0}")]
+    MissingDetectOutcome(BuildpackId),
+    #[error("Required buildpack {0} failed detection")]
+    DetectFailed(BuildpackId),
+    #[error(transparent)]
+    BuildPlan(BuildPlanResolutionError),
+}
+
+fn resolve_group<S: BuildHasher>(
+    group: &[Group],
+    detect_outcomes: &HashMap<BuildpackId, DetectOutcome, S>,
+) -> GroupResolution {
+    let mut failure_reasons = Vec::new();
+    let mut skipped_buildpacks = Vec::new();
+    let mut passed = Vec::new();
+
+    for group_entry in group {
+        match detect_outcomes.get(&group_entry.id) {
+            Some(DetectOutcome::Pass(build_plan)) => passed.push((group_entry, build_plan)),
+            Some(DetectOutcome::Fail) if group_entry.optional => {
+                skipped_buildpacks.push(group_entry.id.clone());
+            }
+            Some(DetectOutcome::Fail) => {
+                failure_reasons.push(GroupFailureReason::DetectFailed(group_entry.id.clone()));
+            }
+            None => failure_reasons.push(GroupFailureReason::MissingDetectOutcome(
+                group_entry.id.clone(),
+            )),
+        }
+    }
+
+    if !failure_reasons.is_empty() {
+        return GroupResolution::Fail(failure_reasons);
+    }
+
+    let build_plans = passed
+        .iter()
+        .map(|(group_entry, build_plan)| (*group_entry, *build_plan))
+        .collect::<Vec<_>>();
+
+    match resolve_build_plans(&build_plans) {
+        Ok(resolved_build_plans) => {
+            skipped_buildpacks.extend(resolved_build_plans.skipped_buildpacks);
+
+            GroupResolution::Pass(ResolvedGroup {
+                buildpacks: resolved_build_plans.buildpacks,
+                skipped_buildpacks,
+                buildpack_plans: resolved_build_plans.buildpack_plans,
+            })
+        }
+        Err(errors) => GroupResolution::Fail(
+            errors
+                .into_iter()
+                .map(GroupFailureReason::BuildPlan)
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libcnb_data::build_plan::BuildPlanBuilder;
+    use libcnb_data::buildpack::BuildpackVersion;
+    use libcnb_data::buildpack_id;
+
+    fn group(id: BuildpackId, optional: bool) -> Group {
+        Group {
+            id,
+            version: BuildpackVersion::new(1, 0, 0),
+            optional,
+        }
+    }
+
+    fn resolved_ids(resolution: &OrderResolution) -> Option<(usize, Vec<String>)> {
+        resolution.passing_group().map(|(index, resolved_group)| {
+            (
+                index,
+                resolved_group
+                    .buildpacks
+                    .iter()
+                    .map(|group_entry| group_entry.id.to_string())
+                    .collect(),
+            )
+        })
+    }
+
+    #[test]
+    fn first_passing_group_wins() {
+        let order = vec![
+            Order {
+                group: vec![group(buildpack_id!("a"), false)],
+            },
+            Order {
+                group: vec![group(buildpack_id!("b"), false)],
+            },
+            Order {
+                group: vec![group(buildpack_id!("c"), false)],
+            },
+        ];
+
+        let detect_outcomes = HashMap::from([
+            (buildpack_id!("a"), DetectOutcome::Fail),
+            (buildpack_id!("b"), DetectOutcome::Pass(BuildPlan::new())),
+            (buildpack_id!("c"), DetectOutcome::Pass(BuildPlan::new())),
+        ]);
+
+        let resolution = resolve_order(&order, &detect_outcomes);
+
+        assert_eq!(
+            resolved_ids(&resolution),
+            Some((1, vec![String::from("b")]))
+        );
+        assert_eq!(resolution.groups.len(), 2);
+        assert!(matches!(
+            &resolution.groups[0],
+            GroupResolution::Fail(reasons) if reasons == &[GroupFailureReason::DetectFailed(buildpack_id!("a"))]
+        ));
+    }
+
+    #[test]
+    fn optional_buildpacks_are_skipped() {
+        let order = vec![Order {
+            group: vec![
+                group(buildpack_id!("a"), true),
+                group(buildpack_id!("b"), false),
+                group(buildpack_id!("c"), true),
+            ],
+        }];
+
+        let detect_outcomes = HashMap::from([
+            (buildpack_id!("a"), DetectOutcome::Fail),
+            (buildpack_id!("b"), DetectOutcome::Pass(BuildPlan::new())),
+            // Has an unmet require, but is optional.
+            (
+                buildpack_id!("c"),
+                DetectOutcome::Pass(BuildPlanBuilder::new().requires("missing").build()),
+            ),
+        ]);
+
+        let resolution = resolve_order(&order, &detect_outcomes);
+        let (_, resolved_group) = resolution.passing_group().unwrap();
+
+        assert_eq!(
+            resolved_ids(&resolution),
+            Some((0, vec![String::from("b")]))
+        );
+        assert_eq!(
+            resolved_group.skipped_buildpacks,
+            vec![buildpack_id!("a"), buildpack_id!("c")]
+        );
+    }
+
+    #[test]
+    fn requires_must_be_provided_by_same_or_earlier_buildpack() {
+        let order = vec![Order {
+            group: vec![
+                group(buildpack_id!("a"), false),
+                group(buildpack_id!("b"), false),
+            ],
+        }];
+
+        let detect_outcomes = HashMap::from([
+            (
+                buildpack_id!("a"),
+                DetectOutcome::Pass(BuildPlanBuilder::new().requires("node").build()),
+            ),
+            (
+                buildpack_id!("b"),
+                DetectOutcome::Pass(BuildPlanBuilder::new().provides("node").build()),
+            ),
+        ]);
+
+        let resolution = resolve_order(&order, &detect_outcomes);
+
+        assert_eq!(resolved_ids(&resolution), None);
+        assert!(matches!(
+            &resolution.groups[0],
+            GroupResolution::Fail(reasons) if reasons == &[
+                GroupFailureReason::BuildPlan(BuildPlanResolutionError::UnmetRequire {
+                    buildpack_id: buildpack_id!("a"),
+                    name: String::from("node"),
+                }),
+                GroupFailureReason::BuildPlan(BuildPlanResolutionError::UnmetProvide {
+                    buildpack_id: buildpack_id!("b"),
+                    name: String::from("node"),
+                }),
+            ]
+        ));
+    }
+
+    #[test]
+    fn or_alternatives_are_tried_in_order() {
+        let order = vec![Order {
+            group: vec![
+                group(buildpack_id!("a"), false),
+                group(buildpack_id!("b"), false),
+            ],
+        }];
+
+        let detect_outcomes = HashMap::from([
+            (
+                buildpack_id!("a"),
+                DetectOutcome::Pass(
+                    BuildPlanBuilder::new()
+                        .provides("node")
+                        .or()
+                        .provides("node")
+                        .requires("node")
+                        .build(),
+                ),
+            ),
+            (buildpack_id!("b"), DetectOutcome::Pass(BuildPlan::new())),
+        ]);
+
+        let resolution = resolve_order(&order, &detect_outcomes);
+
+        assert_eq!(
+            resolved_ids(&resolution),
+            Some((0, vec![String::from("a"), String::from("b")]))
+        );
+    }
+
+    #[test]
+    fn removing_optional_buildpack_can_fail_required_buildpack() {
+        let order = vec![Order {
+            group: vec![
+                group(buildpack_id!("a"), false),
+                group(buildpack_id!("b"), true),
+            ],
+        }];
+
+        let detect_outcomes = HashMap::from([
+            (
+                buildpack_id!("a"),
+                DetectOutcome::Pass(BuildPlanBuilder::new().provides("node").build()),
+            ),
+            (
+                buildpack_id!("b"),
+                DetectOutcome::Pass(
+                    BuildPlanBuilder::new()
+                        .requires("node")
+                        .requires("missing")
+                        .build(),
+                ),
+            ),
+        ]);
+
+        let resolution = resolve_order(&order, &detect_outcomes);
+
+        assert!(matches!(
+            &resolution.groups[0],
+            GroupResolution::Fail(reasons) if reasons == &[
+                GroupFailureReason::BuildPlan(BuildPlanResolutionError::UnmetProvide {
+                    buildpack_id: buildpack_id!("a"),
+                    name: String::from("node"),
+                }),
+            ]
+        ));
+    }
+
+    #[test]
+    fn group_without_viable_buildpacks_fails() {
+        let order = vec![Order {
+            group: vec![
+                group(buildpack_id!("a"), true),
+                group(buildpack_id!("b"), true),
+            ],
+        }];
+
+        let detect_outcomes = HashMap::from([(buildpack_id!("a"), DetectOutcome::Fail)]);
+
+        let resolution = resolve_order(&order, &detect_outcomes);
+
+        assert!(matches!(
+            &resolution.groups[0],
+            GroupResolution::Fail(reasons) if reasons == &[GroupFailureReason::MissingDetectOutcome(buildpack_id!("b"))]
+        ));
+
+        let detect_outcomes = HashMap::from([
+            (buildpack_id!("a"), DetectOutcome::Fail),
+            (buildpack_id!("b"), DetectOutcome::Fail),
+        ]);
+
+        let resolution = resolve_order(&order, &detect_outcomes);
+
+        assert!(matches!(
+            &resolution.groups[0],
+            GroupResolution::Fail(reasons) if reasons == &[GroupFailureReason::BuildPlan(BuildPlanResolutionError::NoViableBuildpacks)]
+        ));
+    }
+}
@@ -22,6 +22,54 @@ pub fn create_packaged_buildpack_dir_resolver(
     }
 }
 
+/// Create a function that can construct the output location for a buildpack packaged for
+/// multiple targets.
+///
+/// See [`crate::multi_target::assemble_multi_target_buildpack_directory`] for the layout of these
+/// directories.
+pub fn create_multi_target_packaged_buildpack_dir_resolver(
+    package_dir: &Path,
+    cargo_profile: CargoProfile,
+) -> impl Fn(&BuildpackId) -> PathBuf {
+    create_packaged_buildpack_dir_resolver(package_dir, cargo_profile, MULTI_TARGET_DIR_NAME)
+}
+
+const MULTI_TARGET_DIR_NAME: &str = "multi-target";
+
+/// Determines the package directory of a packaged buildpack directory constructed by the resolvers
+/// in this module, such as [`create_packaged_buildpack_dir_resolver`].
+pub(crate) fn package_dir_of(packaged_buildpack_dir: &Path) -> Option<&Path> {
+    // Packaged buildpack directories are at `<package dir>/<target triple>/<profile>/<buildpack>`.
+    packaged_buildpack_dir.ancestors().nth(3)
+}
+
+/// Construct the location of the buildpackage archive (`.cnb` file) for a packaged buildpack
+/// directory, which is next to the directory itself.
+///
+/// See [`crate::buildpackage::write_buildpackage`].
+#[must_use]
+pub fn buildpackage_archive_path(packaged_buildpack_dir: &Path) -> PathBuf {
+    // `Path::with_extension` can't be used, since it would replace parts of buildpack directory
+    // names that contain dots.
+    let mut path = packaged_buildpack_dir.as_os_str().to_owned();
+    path.push(".cnb");
+    PathBuf::from(path)
+}
+
+/// Construct the location of the packaging metadata directory for a packaged buildpack directory,
+/// which is next to the directory itself.
+///
+/// Files that are only needed for packaging, such as the manifest of the packaged files (see
+/// [`crate::reproducible::write_buildpack_manifest`]), are kept there instead of in the packaged
+/// buildpack directory, so they don't end up in buildpack images.
+#[must_use]
+pub fn packaging_metadata_dir(packaged_buildpack_dir: &Path) -> PathBuf {
+    // See `buildpackage_archive_path` for why `Path::with_extension` isn't used.
+    let mut path = packaged_buildpack_dir.as_os_str().to_owned();
+    path.push(".libcnb-cargo");
+    PathBuf::from(path)
+}
+
 /// Construct a good default filename for a buildpack directory.
 ///
 /// This function ensures the resulting name is valid and does not contain problematic characters
@@ -33,10 +81,13 @@ pub fn default_buildpack_directory_name(buildpack_id: &BuildpackId) -> String {
 
 #[cfg(test)]
 mod tests {
-    use crate::output::create_packaged_buildpack_dir_resolver;
+    use crate::output::{
+        buildpackage_archive_path, create_multi_target_packaged_buildpack_dir_resolver,
+        create_packaged_buildpack_dir_resolver, package_dir_of,
+    };
     use crate::CargoProfile;
     use libcnb_data::buildpack_id;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
 
     #[test]
     fn test_get_buildpack_target_dir() {
@@ -62,4 +113,50 @@ mod tests {
             PathBuf::from("/package/x86_64-unknown-linux-musl/release/some-org_with-buildpack")
         );
     }
+
+    #[test]
+    fn test_get_multi_target_buildpack_target_dir() {
+        let buildpack_id = buildpack_id!("some-org/with-buildpack");
+        let package_dir = PathBuf::from("/package");
+
+        assert_eq!(
+            create_multi_target_packaged_buildpack_dir_resolver(
+                &package_dir,
+                CargoProfile::Release
+            )(&buildpack_id),
+            PathBuf::from("/package/multi-target/release/some-org_with-buildpack")
+        );
+    }
+
+    #[test]
+    fn test_buildpackage_archive_path() {
+        assert_eq!(
+            buildpackage_archive_path(&PathBuf::from(
+                "/package/x86_64-unknown-linux-musl/release/some-org_v1.2"
+            )),
+            PathBuf::from("/package/x86_64-unknown-linux-musl/release/some-org_v1.2.cnb")
+        );
+    }
+
+    #[test]
+    fn test_package_dir_of() {
+        let buildpack_id = buildpack_id!("some-org/with-buildpack");
+        let package_dir = PathBuf::from("/package");
+
+        assert_eq!(
+            package_dir_of(&create_packaged_buildpack_dir_resolver(
+                &package_dir,
+                CargoProfile::Dev,
+                "x86_64-unknown-linux-musl"
+            )(&buildpack_id)),
+            Some(Path::new("/package"))
+        );
+        assert_eq!(
+            package_dir_of(&create_multi_target_packaged_buildpack_dir_resolver(
+                &package_dir,
+                CargoProfile::Release
+            )(&buildpack_id)),
+            Some(Path::new("/package"))
+        );
+    }
 }
@@ -0,0 +1,261 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Represents a `pack build` command.
+///
+/// Convert it into a [`Command`] to run it:
+///
+/// ```no_run
+/// use libcnb_package::pack::PackBuildCommand;
+/// use std::path::PathBuf;
+/// use std::process::Command;
+///
+/// let mut pack_build_command =
+///     PackBuildCommand::new("heroku/builder:24", "/path/to/app", "my-image");
+/// pack_build_command.buildpack(PathBuf::from("/path/to/packaged/buildpack"));
+///
+/// let status = Command::from(pack_build_command).status();
+/// ```
+#[derive(Clone, Debug)]
+pub struct PackBuildCommand {
+    build_cache_volume_name: Option<String>,
+    builder: String,
+    buildpacks: Vec<BuildpackReference>,
+    env: BTreeMap<String, String>,
+    image_name: String,
+    launch_cache_volume_name: Option<String>,
+    path: PathBuf,
+    pull_policy: PullPolicy,
+    trust_builder: bool,
+    trust_extra_buildpacks: bool,
+}
+
+/// A buildpack passed to `pack build`, either by ID (or image URL) or by the path of a packaged
+/// buildpack directory.
+#[derive(Clone, Debug)]
+pub enum BuildpackReference {
+    Id(String),
+    Path(PathBuf),
+}
+
+impl From<PathBuf> for BuildpackReference {
+    fn from(path: PathBuf) -> Self {
+        Self::Path(path)
+    }
+}
+
+impl From<String> for BuildpackReference {
+    fn from(id: String) -> Self {
+        Self::Id(id)
+    }
+}
+
+/// Controls whether Pack should pull images.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PullPolicy {
+    /// Always pull images.
+    Always,
+    /// Use local images if they are already present, rather than pulling updated images.
+    IfNotPresent,
+    /// Never pull images. If the required images are not already available locally the pack command will fail.
+    Never,
+}
+
+impl PackBuildCommand {
+    /// Creates a `pack build` command that builds the app at the given path into an image with the
+    /// given name, using the given builder.
+    ///
+    /// The builder and all buildpacks are trusted and images are only pulled if they aren't
+    /// present locally. Pack chooses the names of the cache volumes based on the image name,
+    /// unless they are set with [`PackBuildCommand::cache_volumes`].
+    pub fn new(
+        builder: impl Into<String>,
+        path: impl Into<PathBuf>,
+        image_name: impl Into<String>,
+    ) -> Self {
+        Self {
+            build_cache_volume_name: None,
+            builder: builder.into(),
+            buildpacks: Vec::new(),
+            env: BTreeMap::new(),
+            image_name: image_name.into(),
+            launch_cache_volume_name: None,
+            path: path.into(),
+            // Prevent redundant image-pulling, which slows builds and risks hitting registry rate limits.
+            pull_policy: PullPolicy::IfNotPresent,
+            trust_builder: true,
+            trust_extra_buildpacks: true,
+        }
+    }
+
+    /// Adds a buildpack to run, in the order the buildpacks are added. Buildpacks can be passed
+    /// by the path of a packaged buildpack directory or by ID, which pack resolves from the
+    /// builder or a registry.
+    pub fn buildpack(&mut self, b: impl Into<BuildpackReference>) -> &mut Self {
+        self.buildpacks.push(b.into());
+        self
+    }
+
+    /// Sets an environment variable for the build, replacing a previously set value of the same
+    /// variable.
+    pub fn env(&mut self, k: impl Into<String>, v: impl Into<String>) -> &mut Self {
+        self.env.insert(k.into(), v.into());
+        self
+    }
+
+    /// Sets the names of the Docker volumes used for the build and launch caches.
+    pub fn cache_volumes(
+        &mut self,
+        build_cache_volume_name: impl Into<String>,
+        launch_cache_volume_name: impl Into<String>,
+    ) -> &mut Self {
+        self.build_cache_volume_name = Some(build_cache_volume_name.into());
+        self.launch_cache_volume_name = Some(launch_cache_volume_name.into());
+        self
+    }
+
+    /// Sets whether pack pulls the builder and run images, which defaults to
+    /// [`PullPolicy::IfNotPresent`].
+    pub fn pull_policy(&mut self, pull_policy: PullPolicy) -> &mut Self {
+        self.pull_policy = pull_policy;
+        self
+    }
+}
+
+impl From<PackBuildCommand> for Command {
+    fn from(pack_build_command: PackBuildCommand) -> Self {
+        let mut command = Self::new("pack");
+
+        command.args([
+            "build",
+            &pack_build_command.image_name,
+            "--builder",
+            &pack_build_command.builder,
+        ]);
+
+        if let Some(build_cache_volume_name) = &pack_build_command.build_cache_volume_name {
+            command.args([
+                "--cache",
+                &format!("type=build;format=volume;name={build_cache_volume_name}"),
+            ]);
+        }
+
+        if let Some(launch_cache_volume_name) = &pack_build_command.launch_cache_volume_name {
+            command.args([
+                "--cache",
+                &format!("type=launch;format=volume;name={launch_cache_volume_name}"),
+            ]);
+        }
+
+        command.args([
+            "--path",
+            &pack_build_command.path.to_string_lossy(),
+            "--pull-policy",
+            match pack_build_command.pull_policy {
+                PullPolicy::Always => "always",
+                PullPolicy::IfNotPresent => "if-not-present",
+                PullPolicy::Never => "never",
+            },
+        ]);
+
+        for buildpack in pack_build_command.buildpacks {
+            command.args([
+                "--buildpack",
+                &match buildpack {
+                    BuildpackReference::Id(id) => id,
+                    BuildpackReference::Path(path_buf) => path_buf.to_string_lossy().to_string(),
+                },
+            ]);
+        }
+
+        for (env_key, env_value) in &pack_build_command.env {
+            command.args(["--env", &format!("{env_key}={env_value}")]);
+        }
+
+        if pack_build_command.trust_builder {
+            command.arg("--trust-builder");
+        }
+
+        if pack_build_command.trust_extra_buildpacks {
+            command.arg("--trust-extra-buildpacks");
+        }
+
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn from_pack_build_command_to_command() {
+        let mut input = PackBuildCommand {
+            build_cache_volume_name: Some(String::from("build-cache-volume")),
+            builder: String::from("builder:20"),
+            buildpacks: vec![
+                BuildpackReference::Id(String::from("libcnb/buildpack1")),
+                BuildpackReference::Path(PathBuf::from("/tmp/buildpack2")),
+            ],
+            env: BTreeMap::from([
+                (String::from("ENV_FOO"), String::from("FOO_VALUE")),
+                (String::from("ENV_BAR"), String::from("WHITESPACE VALUE")),
+            ]),
+            image_name: String::from("my-image"),
+            launch_cache_volume_name: Some(String::from("launch-cache-volume")),
+            path: PathBuf::from("/tmp/foo/bar"),
+            pull_policy: PullPolicy::IfNotPresent,
+            trust_builder: true,
+            trust_extra_buildpacks: true,
+        };
+
+        let command: Command = input.clone().into();
+
+        assert_eq!(command.get_program(), "pack");
+
+        assert_eq!(
+            command.get_args().collect::<Vec<&OsStr>>(),
+            [
+                "build",
+                "my-image",
+                "--builder",
+                "builder:20",
+                "--cache",
+                "type=build;format=volume;name=build-cache-volume",
+                "--cache",
+                "type=launch;format=volume;name=launch-cache-volume",
+                "--path",
+                "/tmp/foo/bar",
+                "--pull-policy",
+                "if-not-present",
+                "--buildpack",
+                "libcnb/buildpack1",
+                "--buildpack",
+                "/tmp/buildpack2",
+                "--env",
+                "ENV_BAR=WHITESPACE VALUE",
+                "--env",
+                "ENV_FOO=FOO_VALUE",
+                "--trust-builder",
+                "--trust-extra-buildpacks",
+            ]
+        );
+
+        assert_eq!(command.get_envs().collect::<Vec<_>>(), Vec::new());
+
+        // Assert conditional '--trust-builder' flag works as expected:
+        input.trust_builder = false;
+        let command: Command = input.clone().into();
+        assert!(!command
+            .get_args()
+            .any(|arg| arg == OsStr::new("--trust-builder")));
+
+        // Assert pack chooses the cache volumes if none are set:
+        input.build_cache_volume_name = None;
+        input.launch_cache_volume_name = None;
+        let command: Command = input.into();
+        assert!(!command.get_args().any(|arg| arg == OsStr::new("--cache")));
+    }
+}
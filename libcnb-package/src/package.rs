@@ -1,11 +1,22 @@
-use crate::build::build_buildpack_binaries;
+use crate::assets::{copy_buildpack_assets, find_buildpack_assets, FindBuildpackAssetsError};
+use crate::binary_audit::{
+    audit_buildpack_binaries, write_binary_audit_report, BinaryAuditReport, Linkage,
+};
+use crate::build::{build_buildpack_binaries, BuildpackBinaries, CargoBuildOptions};
 use crate::buildpack_kind::{determine_buildpack_kind, BuildpackKind};
+use crate::cargo::libcnb_cargo_metadata;
+use crate::output::{package_dir_of, packaging_metadata_dir};
 use crate::package_descriptor::{normalize_package_descriptor, NormalizePackageDescriptorError};
+use crate::reproducible::{
+    normalize_buildpack_directory, normalized_file_mode, verify_buildpack_manifest,
+    write_buildpack_manifest,
+};
 use crate::{assemble_buildpack_directory, CargoProfile};
 use cargo_metadata::MetadataCommand;
 use libcnb_common::toml_file::{read_toml_file, write_toml_file, TomlFileError};
 use libcnb_data::buildpack::BuildpackId;
 use libcnb_data::package_descriptor::PackageDescriptor;
+use sha2::{Digest, Sha256};
 use std::collections::BTreeMap;
 use std::ffi::OsString;
 use std::fs;
@@ -13,32 +24,121 @@ use std::path::{Path, PathBuf};
 
 /// Packages either a libcnb.rs or a composite buildpack.
 ///
+/// After packaging, a manifest of all files and their digests is written (see
+/// [`write_buildpack_manifest`]) and file modes and modification times are normalized (see
+/// [`normalize_buildpack_directory`]), so packaging the same sources results in identical
+/// directories.
+///
+/// libcnb.rs buildpacks contain their compiled binaries and the assets declared in
+/// `[package.metadata.libcnb]` of their `Cargo.toml` (see [`find_buildpack_assets`]). Their
+/// binaries are built with the given [`CargoBuildOptions`], combined with the ones in their
+/// `Cargo.toml`.
+///
+/// The binaries of libcnb.rs buildpacks are audited (see [`crate::binary_audit`]) and the report
+/// is written to the packaging metadata directory (see [`packaging_metadata_dir`]). Packaging
+/// fails if a binary built for a musl target is dynamically linked, since it likely won't run on
+/// the distribution of the run image.
+///
+/// Packaging is incremental: a fingerprint of the packaging inputs (the buildpack descriptor, the
+/// compiled buildpack binaries and assets or the normalized package descriptor and the target
+/// triple) is stored in the packaging metadata directory of the destination. If the destination
+/// was already packaged from inputs with the same fingerprint and hasn't been modified since, it
+/// is left untouched and [`PackageBuildpackOutcome::UpToDate`] is returned. Buildpack binaries
+/// are always built, so Cargo decides if they need to be recompiled.
+///
 /// # Errors
 ///
 /// Returns `Err` if packaging failed or the given buildpack directory is unsupported.
 pub fn package_buildpack(
     buildpack_directory: &Path,
     cargo_profile: CargoProfile,
+    cargo_build_options: &CargoBuildOptions,
     target_triple: &str,
     cargo_build_env: &[(OsString, OsString)],
     destination: &Path,
     dependencies: &BTreeMap<BuildpackId, PathBuf>,
-) -> Result<(), PackageBuildpackError> {
-    match determine_buildpack_kind(buildpack_directory) {
-        Some(BuildpackKind::LibCnbRs) => package_libcnb_buildpack(
+) -> Result<PackageBuildpackOutcome, PackageBuildpackError> {
+    let packaging_input = match determine_buildpack_kind(buildpack_directory) {
+        Some(BuildpackKind::LibCnbRs) => prepare_libcnb_buildpack(
             buildpack_directory,
             cargo_profile,
+            cargo_build_options,
             target_triple,
             cargo_build_env,
             destination,
         )
+        .map(|(buildpack_binaries, asset_paths)| {
+            PackagingInput::Libcnb(buildpack_binaries, asset_paths)
+        })
         .map_err(PackageBuildpackError::PackageLibcnbBuildpackError),
         Some(BuildpackKind::Composite) => {
-            package_composite_buildpack(buildpack_directory, destination, dependencies)
+            normalized_package_descriptor(buildpack_directory, dependencies)
+                .map(|package_descriptor| PackagingInput::Composite(Box::new(package_descriptor)))
                 .map_err(PackageBuildpackError::PackageCompositeBuildpackError)
         }
         _ => Err(PackageBuildpackError::UnsupportedBuildpack),
+    }?;
+
+    let fingerprint = packaging_input
+        .fingerprint(buildpack_directory, target_triple)
+        .map_err(PackageBuildpackError::CalculateFingerprint)?;
+
+    if is_up_to_date(destination, &fingerprint) {
+        return Ok(PackageBuildpackOutcome::UpToDate);
+    }
+
+    let binary_audit_report = match &packaging_input {
+        PackagingInput::Libcnb(buildpack_binaries, _) => Some(
+            audit_libcnb_buildpack_binaries(buildpack_binaries, target_triple)
+                .map_err(PackageBuildpackError::PackageLibcnbBuildpackError)?,
+        ),
+        PackagingInput::Composite(_) => None,
+    };
+
+    // Removing fails if the destination doesn't exist yet, which is fine.
+    let _ = fs::remove_dir_all(destination);
+    let _ = fs::remove_dir_all(packaging_metadata_dir(destination));
+    fs::create_dir_all(destination).map_err(PackageBuildpackError::CreateDestinationDirectory)?;
+
+    match packaging_input {
+        PackagingInput::Libcnb(buildpack_binaries, asset_paths) => {
+            assemble_libcnb_buildpack_directory(
+                buildpack_directory,
+                &buildpack_binaries,
+                &asset_paths,
+                destination,
+            )
+        }
+        .map_err(PackageBuildpackError::PackageLibcnbBuildpackError),
+        PackagingInput::Composite(package_descriptor) => write_composite_buildpack_directory(
+            buildpack_directory,
+            &package_descriptor,
+            destination,
+        )
+        .map_err(PackageBuildpackError::PackageCompositeBuildpackError),
+    }?;
+
+    if let Some(binary_audit_report) = binary_audit_report {
+        write_binary_audit_report(destination, &binary_audit_report)
+            .map_err(PackageBuildpackError::WriteBinaryAuditReport)?;
     }
+
+    write_fingerprint(destination, &fingerprint)
+        .and_then(|()| write_buildpack_manifest(destination))
+        .and_then(|()| normalize_buildpack_directory(destination))
+        .map_err(PackageBuildpackError::NormalizeBuildpackDirectory)?;
+
+    Ok(PackageBuildpackOutcome::Packaged)
+}
+
+/// The outcome of [`package_buildpack`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PackageBuildpackOutcome {
+    /// The buildpack was packaged into the destination.
+    Packaged,
+    /// The destination already contained the buildpack packaged from the same inputs and was left
+    /// untouched.
+    UpToDate,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -49,39 +149,208 @@ pub enum PackageBuildpackError {
     PackageLibcnbBuildpackError(PackageLibcnbBuildpackError),
     #[error("Buildpack is not supported to be packaged")]
     UnsupportedBuildpack,
+    #[error("Couldn't calculate fingerprint of packaging inputs: {0}")]
+    CalculateFingerprint(std::io::Error),
+    #[error("Couldn't create packaged buildpack directory: {0}")]
+    CreateDestinationDirectory(std::io::Error),
+    #[error("Couldn't normalize packaged buildpack directory: {0}")]
+    NormalizeBuildpackDirectory(std::io::Error),
+    #[error("Couldn't write binary audit report: {0}")]
+    WriteBinaryAuditReport(std::io::Error),
+}
+
+// What a buildpack directory is assembled from, depending on the kind of buildpack.
+enum PackagingInput {
+    Libcnb(BuildpackBinaries, Vec<PathBuf>),
+    Composite(Box<PackageDescriptor>),
+}
+
+impl PackagingInput {
+    // Calculates a SHA-256 digest over everything that ends up in the packaged buildpack directory.
+    // The version of this crate is included, since changes to packaging itself can change the
+    // packaged buildpack directory as well.
+    fn fingerprint(
+        &self,
+        buildpack_directory: &Path,
+        target_triple: &str,
+    ) -> std::io::Result<String> {
+        let mut inputs = vec![
+            format!("libcnb-package {}", env!("CARGO_PKG_VERSION")),
+            format!("target {target_triple}"),
+            format!(
+                "buildpack.toml {}",
+                sha256_digest(&fs::read(buildpack_directory.join("buildpack.toml"))?)
+            ),
+        ];
+
+        match self {
+            Self::Libcnb(buildpack_binaries, asset_paths) => {
+                inputs.push(format!(
+                    "bin/build {}",
+                    sha256_digest(&fs::read(&buildpack_binaries.buildpack_target_binary_path)?)
+                ));
+
+                let mut additional_binaries = buildpack_binaries
+                    .additional_target_binary_paths
+                    .iter()
+                    .collect::<Vec<_>>();
+                additional_binaries.sort();
+
+                for (binary_target_name, binary_path) in additional_binaries {
+                    let packaged_path = buildpack_binaries
+                        .additional_binary_packaged_paths
+                        .get(binary_target_name)
+                        .map_or_else(String::new, |path| format!(" {}", path.display()));
+
+                    inputs.push(format!(
+                        "additional-bin/{binary_target_name}{packaged_path} {}",
+                        sha256_digest(&fs::read(binary_path)?)
+                    ));
+                }
+
+                for asset_path in asset_paths {
+                    let source_path = buildpack_directory.join(asset_path);
+                    inputs.push(format!(
+                        "asset {} {:o} {}",
+                        asset_path.display(),
+                        normalized_file_mode(&fs::metadata(&source_path)?),
+                        sha256_digest(&fs::read(&source_path)?)
+                    ));
+                }
+            }
+            Self::Composite(package_descriptor) => {
+                inputs.push(format!(
+                    "package.toml {}",
+                    sha256_digest(&serde_json::to_vec(package_descriptor)?)
+                ));
+            }
+        }
+
+        Ok(sha256_digest(inputs.join("\n").as_bytes()))
+    }
+}
+
+fn is_up_to_date(destination: &Path, fingerprint: &str) -> bool {
+    fs::read_to_string(fingerprint_path(destination))
+        .is_ok_and(|existing_fingerprint| existing_fingerprint.trim() == fingerprint)
+        && verify_buildpack_manifest(destination)
+            .is_ok_and(|mismatched_paths| mismatched_paths.is_empty())
+}
+
+fn write_fingerprint(destination: &Path, fingerprint: &str) -> std::io::Result<()> {
+    let fingerprint_path = fingerprint_path(destination);
+
+    if let Some(parent) = fingerprint_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(fingerprint_path, format!("{fingerprint}\n"))
+}
+
+fn sha256_digest(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+fn fingerprint_path(destination: &Path) -> PathBuf {
+    packaging_metadata_dir(destination).join("fingerprint")
 }
 
-/// Packages a libcnb.rs buildpack after (cross-) compiling.
+/// Builds the binaries of a libcnb.rs buildpack and finds the assets declared in
+/// `[package.metadata.libcnb]` (see [`find_buildpack_assets`]). The Cargo target directory and the
+/// package directory of the given destination never contain assets.
 ///
 /// # Errors
 ///
-/// Returns `Err` if compilation or packaging failed.
-fn package_libcnb_buildpack(
+/// Returns `Err` if compilation failed or the assets couldn't be determined.
+fn prepare_libcnb_buildpack(
     buildpack_directory: &Path,
     cargo_profile: CargoProfile,
+    cargo_build_options: &CargoBuildOptions,
     target_triple: &str,
     cargo_build_env: &[(OsString, OsString)],
     destination: &Path,
-) -> Result<(), PackageLibcnbBuildpackError> {
+) -> Result<(BuildpackBinaries, Vec<PathBuf>), PackageLibcnbBuildpackError> {
     let cargo_metadata = MetadataCommand::new()
         .manifest_path(buildpack_directory.join("Cargo.toml"))
         .exec()
         .map_err(PackageLibcnbBuildpackError::CargoMetadataError)?;
 
+    let libcnb_cargo_metadata = libcnb_cargo_metadata(&cargo_metadata)
+        .map_err(PackageLibcnbBuildpackError::InvalidLibcnbCargoMetadata)?;
+
+    let asset_paths = find_buildpack_assets(
+        buildpack_directory,
+        &libcnb_cargo_metadata.include,
+        &libcnb_cargo_metadata.exclude,
+        &[
+            cargo_metadata.target_directory.as_std_path().to_path_buf(),
+            package_dir_of(destination)
+                .unwrap_or(destination)
+                .to_path_buf(),
+        ],
+    )
+    .map_err(PackageLibcnbBuildpackError::FindAssets)?;
+
     let buildpack_binaries = build_buildpack_binaries(
         buildpack_directory,
         &cargo_metadata,
+        &libcnb_cargo_metadata,
         cargo_profile,
+        cargo_build_options,
         cargo_build_env,
         target_triple,
     )
     .map_err(PackageLibcnbBuildpackError::BuildBinariesError)?;
 
+    Ok((buildpack_binaries, asset_paths))
+}
+
+/// Audits the compiled binaries of a libcnb.rs buildpack.
+///
+/// # Errors
+///
+/// Returns `Err` if a binary couldn't be read or a binary built for a musl target is dynamically
+/// linked.
+fn audit_libcnb_buildpack_binaries(
+    buildpack_binaries: &BuildpackBinaries,
+    target_triple: &str,
+) -> Result<BinaryAuditReport, PackageLibcnbBuildpackError> {
+    let binary_audit_report = audit_buildpack_binaries(buildpack_binaries)
+        .map_err(PackageLibcnbBuildpackError::AuditBinaries)?;
+
+    if target_triple.contains("-musl") {
+        if let Some((path, binary_audit)) = binary_audit_report
+            .binaries
+            .iter()
+            .find(|(_, binary_audit)| binary_audit.linkage == Linkage::Dynamic)
+        {
+            return Err(PackageLibcnbBuildpackError::DynamicallyLinkedMuslBinary(
+                path.clone(),
+                binary_audit.needed_libraries.clone(),
+            ));
+        }
+    }
+
+    Ok(binary_audit_report)
+}
+
+/// Assembles the directory of a libcnb.rs buildpack from its compiled binaries and assets.
+///
+/// # Errors
+///
+/// Returns `Err` if packaging failed.
+fn assemble_libcnb_buildpack_directory(
+    buildpack_directory: &Path,
+    buildpack_binaries: &BuildpackBinaries,
+    asset_paths: &[PathBuf],
+    destination: &Path,
+) -> Result<(), PackageLibcnbBuildpackError> {
     assemble_buildpack_directory(
         destination,
         buildpack_directory.join("buildpack.toml"),
-        &buildpack_binaries,
+        buildpack_binaries,
     )
+    .and_then(|()| copy_buildpack_assets(buildpack_directory, asset_paths, destination))
     .map_err(PackageLibcnbBuildpackError::AssembleBuildpackDirectory)?;
 
     fs::write(
@@ -101,6 +370,14 @@ pub enum PackageLibcnbBuildpackError {
     BuildBinariesError(crate::build::BuildBinariesError),
     #[error("Obtaining Cargo metadata failed: {0}")]
     CargoMetadataError(cargo_metadata::Error),
+    #[error("Invalid [package.metadata.libcnb] in Cargo.toml: {0}")]
+    InvalidLibcnbCargoMetadata(serde_json::Error),
+    #[error("Finding buildpack assets failed: {0}")]
+    FindAssets(FindBuildpackAssetsError),
+    #[error("Auditing buildpack binaries failed: {0}")]
+    AuditBinaries(std::io::Error),
+    #[error("Binary {0} was built for a musl target but is dynamically linked (needed libraries: {libraries})", libraries = .1.join(", "))]
+    DynamicallyLinkedMuslBinary(String, Vec<String>),
 }
 
 /// Packages a composite buildpack.
@@ -120,6 +397,40 @@ pub fn package_composite_buildpack(
     buildpack_directory: &Path,
     destination: &Path,
     buildpack_paths: &BTreeMap<BuildpackId, PathBuf>,
+) -> Result<(), PackageCompositeBuildpackError> {
+    normalized_package_descriptor(buildpack_directory, buildpack_paths).and_then(
+        |package_descriptor| {
+            write_composite_buildpack_directory(
+                buildpack_directory,
+                &package_descriptor,
+                destination,
+            )
+        },
+    )
+}
+
+fn normalized_package_descriptor(
+    buildpack_directory: &Path,
+    buildpack_paths: &BTreeMap<BuildpackId, PathBuf>,
+) -> Result<PackageDescriptor, PackageCompositeBuildpackError> {
+    let package_descriptor_path = buildpack_directory.join("package.toml");
+
+    read_toml_file::<PackageDescriptor>(&package_descriptor_path)
+        .map_err(PackageCompositeBuildpackError::CouldNotReadPackageDescriptor)
+        .and_then(|package_descriptor| {
+            normalize_package_descriptor(
+                &package_descriptor,
+                &package_descriptor_path,
+                buildpack_paths,
+            )
+            .map_err(PackageCompositeBuildpackError::NormalizePackageDescriptorError)
+        })
+}
+
+fn write_composite_buildpack_directory(
+    buildpack_directory: &Path,
+    normalized_package_descriptor: &PackageDescriptor,
+    destination: &Path,
 ) -> Result<(), PackageCompositeBuildpackError> {
     fs::copy(
         buildpack_directory.join("buildpack.toml"),
@@ -127,22 +438,8 @@ pub fn package_composite_buildpack(
     )
     .map_err(PackageCompositeBuildpackError::CouldNotCopyBuildpackToml)?;
 
-    let package_descriptor_path = buildpack_directory.join("package.toml");
-
-    let normalized_package_descriptor =
-        read_toml_file::<PackageDescriptor>(&package_descriptor_path)
-            .map_err(PackageCompositeBuildpackError::CouldNotReadPackageDescriptor)
-            .and_then(|package_descriptor| {
-                normalize_package_descriptor(
-                    &package_descriptor,
-                    &package_descriptor_path,
-                    buildpack_paths,
-                )
-                .map_err(PackageCompositeBuildpackError::NormalizePackageDescriptorError)
-            })?;
-
     write_toml_file(
-        &normalized_package_descriptor,
+        normalized_package_descriptor,
         destination.join("package.toml"),
     )
     .map_err(PackageCompositeBuildpackError::CouldNotWritePackageDescriptor)
@@ -159,3 +456,80 @@ pub enum PackageCompositeBuildpackError {
     #[error("Couldn't write package.toml: {0}")]
     CouldNotWritePackageDescriptor(TomlFileError),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    fn reject_dynamically_linked_musl_binaries() {
+        // The test binary itself is dynamically linked, since it's built for a glibc target.
+        let buildpack_binaries = BuildpackBinaries {
+            buildpack_target_binary_path: std::env::current_exe().unwrap(),
+            additional_target_binary_paths: std::collections::HashMap::new(),
+            additional_binary_packaged_paths: BTreeMap::new(),
+        };
+
+        assert!(matches!(
+            audit_libcnb_buildpack_binaries(&buildpack_binaries, "x86_64-unknown-linux-musl"),
+            Err(PackageLibcnbBuildpackError::DynamicallyLinkedMuslBinary(path, _)) if path == "bin/build"
+        ));
+        assert!(
+            audit_libcnb_buildpack_binaries(&buildpack_binaries, "x86_64-unknown-linux-gnu")
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn package_composite_buildpack_incrementally() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let buildpack_dir = temp_dir.path().join("composite");
+        let destination = temp_dir.path().join("packaged");
+
+        fs::create_dir_all(&buildpack_dir).unwrap();
+        fs::write(
+            buildpack_dir.join("buildpack.toml"),
+            "api = \"0.10\"\n\n[buildpack]\nid = \"example/composite\"\nversion = \"0.0.1\"\n\n[[order]]\n[[order.group]]\nid = \"example/other\"\nversion = \"0.0.1\"\n",
+        )
+        .unwrap();
+        fs::write(
+            buildpack_dir.join("package.toml"),
+            "[buildpack]\nuri = \".\"\n\n[[dependencies]]\nuri = \"docker://docker.io/heroku/example:1.2.3\"\n",
+        )
+        .unwrap();
+
+        let package = || {
+            package_buildpack(
+                &buildpack_dir,
+                CargoProfile::Dev,
+                &CargoBuildOptions::default(),
+                "x86_64-unknown-linux-musl",
+                &[],
+                &destination,
+                &BTreeMap::new(),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(package(), PackageBuildpackOutcome::Packaged);
+        assert!(fingerprint_path(&destination).is_file());
+        // Packaging metadata is kept out of the packaged buildpack directory.
+        assert!(!destination.join(".libcnb-cargo").exists());
+        assert_eq!(package(), PackageBuildpackOutcome::UpToDate);
+
+        // Modifications of the packaged buildpack directory are detected via its manifest.
+        fs::write(destination.join("extra"), "").unwrap();
+        assert_eq!(package(), PackageBuildpackOutcome::Packaged);
+        assert!(!destination.join("extra").exists());
+        assert_eq!(package(), PackageBuildpackOutcome::UpToDate);
+
+        fs::write(
+            buildpack_dir.join("package.toml"),
+            "[buildpack]\nuri = \".\"\n\n[[dependencies]]\nuri = \"docker://docker.io/heroku/example:2.0.0\"\n",
+        )
+        .unwrap();
+        assert_eq!(package(), PackageBuildpackOutcome::Packaged);
+        assert_eq!(package(), PackageBuildpackOutcome::UpToDate);
+    }
+}
@@ -95,7 +95,11 @@ fn absolutize_dependency_paths(
                     &PathBuf::from(dependency.uri.path().to_string()),
                     &descriptor_parent_path,
                 )),
-                _ => Ok(dependency.clone()),
+                // Version requirements are only meaningful to libcnb.rs, pack rejects them.
+                _ => Ok(PackageDescriptorDependency {
+                    version: None,
+                    ..dependency.clone()
+                }),
             }
         })
         .collect::<Result<Vec<_>, _>>()
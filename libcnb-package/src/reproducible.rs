@@ -0,0 +1,269 @@
+use crate::output::packaging_metadata_dir;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Normalizes file modes and modification times in a packaged buildpack directory, so that
+/// packaging the same sources twice results in identical directories.
+///
+/// Directories get mode `0755`, files `0755` if they were executable before and `0644` otherwise.
+/// All files and directories get the modification time `1980-01-01T00:00:01Z`, the same timestamp
+/// `pack` uses for buildpackage layers. Symlinks are left as-is, since neither their mode nor their
+/// modification time can be changed portably.
+///
+/// File modes and directory modification times are only normalized on Unix.
+///
+/// # Errors
+///
+/// Will return `Err` if any I/O errors happen while normalizing the directory.
+pub fn normalize_buildpack_directory(buildpack_dir: &Path) -> std::io::Result<()> {
+    for entry in fs::read_dir(buildpack_dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            normalize_buildpack_directory(&entry.path())?;
+        } else if file_type.is_file() {
+            normalize_file(&entry.path())?;
+        }
+    }
+
+    normalize_directory(buildpack_dir)
+}
+
+/// Writes a manifest of all files in a packaged buildpack directory, with their SHA-256 digests, to
+/// `manifest.sha256` in its packaging metadata directory (see
+/// [`crate::output::packaging_metadata_dir`]).
+///
+/// The manifest uses the format of `sha256sum`, so a packaged buildpack can be verified by running
+/// `sha256sum --check` with the manifest in its directory. Symlinks are followed, the same way
+/// `sha256sum` does.
+///
+/// # Errors
+///
+/// Will return `Err` if any I/O errors happen while reading the files or writing the manifest.
+pub fn write_buildpack_manifest(buildpack_dir: &Path) -> std::io::Result<()> {
+    let manifest_path = manifest_path(buildpack_dir);
+    let manifest_entries = buildpack_manifest_entries(buildpack_dir)?;
+
+    if let Some(parent) = manifest_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut manifest_file = fs::File::create(manifest_path)?;
+    for (relative_path, digest) in manifest_entries {
+        writeln!(manifest_file, "{digest}  {relative_path}")?;
+    }
+
+    manifest_file.flush()
+}
+
+/// Compares the files in a packaged buildpack directory with the manifest written by
+/// [`write_buildpack_manifest`].
+///
+/// Returns the relative paths of all files that were added, removed or changed since the manifest
+/// was written. An empty list means the directory matches the manifest.
+///
+/// # Errors
+///
+/// Will return `Err` if any I/O errors happen while reading the files or the manifest.
+pub fn verify_buildpack_manifest(buildpack_dir: &Path) -> std::io::Result<Vec<String>> {
+    let manifest = fs::read_to_string(manifest_path(buildpack_dir))?;
+
+    let expected_entries = manifest
+        .lines()
+        .filter_map(|line| line.split_once("  "))
+        .map(|(digest, relative_path)| (String::from(relative_path), String::from(digest)))
+        .collect::<Vec<_>>();
+
+    let actual_entries = buildpack_manifest_entries(buildpack_dir)?;
+
+    let mut mismatched_paths = expected_entries
+        .iter()
+        .filter(|expected| !actual_entries.contains(expected))
+        .chain(
+            actual_entries
+                .iter()
+                .filter(|actual| !expected_entries.contains(actual)),
+        )
+        .map(|(relative_path, _)| relative_path.clone())
+        .collect::<Vec<_>>();
+
+    mismatched_paths.sort();
+    mismatched_paths.dedup();
+
+    Ok(mismatched_paths)
+}
+
+fn manifest_path(buildpack_dir: &Path) -> PathBuf {
+    packaging_metadata_dir(buildpack_dir).join("manifest.sha256")
+}
+
+// Returns the relative paths (with `/` as the separator) and SHA-256 digests of all files in the
+// given directory, sorted by path.
+fn buildpack_manifest_entries(buildpack_dir: &Path) -> std::io::Result<Vec<(String, String)>> {
+    let mut entries = Vec::new();
+
+    for relative_path in relative_file_paths(buildpack_dir, Path::new(""))? {
+        let digest = format!(
+            "{:x}",
+            Sha256::digest(fs::read(buildpack_dir.join(&relative_path))?)
+        );
+
+        let relative_path = relative_path
+            .iter()
+            .map(|component| component.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        entries.push((relative_path, digest));
+    }
+
+    entries.sort();
+    Ok(entries)
+}
+
+fn relative_file_paths(base_dir: &Path, relative_dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut relative_paths = Vec::new();
+
+    for entry in fs::read_dir(base_dir.join(relative_dir))? {
+        let entry = entry?;
+        let relative_path = relative_dir.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() {
+            relative_paths.extend(relative_file_paths(base_dir, &relative_path)?);
+        } else {
+            relative_paths.push(relative_path);
+        }
+    }
+
+    Ok(relative_paths)
+}
+
+/// Returns the file mode a normalized file with the given metadata has.
+pub(crate) fn normalized_file_mode(metadata: &fs::Metadata) -> u32 {
+    if is_executable(metadata) {
+        0o755
+    } else {
+        0o644
+    }
+}
+
+#[cfg(target_family = "unix")]
+fn is_executable(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(target_family = "unix"))]
+fn is_executable(_metadata: &fs::Metadata) -> bool {
+    true
+}
+
+fn normalize_file(path: &Path) -> std::io::Result<()> {
+    #[cfg(target_family = "unix")]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = normalized_file_mode(&fs::metadata(path)?);
+        fs::set_permissions(path, fs::Permissions::from_mode(mode))?;
+    }
+
+    // Opened for writing, since some platforms require write access to change modification times.
+    // The file is always writable by its owner at this point due to the normalized mode.
+    fs::OpenOptions::new()
+        .write(true)
+        .open(path)?
+        .set_modified(normalized_mtime())
+}
+
+#[cfg(target_family = "unix")]
+fn normalize_directory(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    fs::File::open(path)?.set_modified(normalized_mtime())
+}
+
+#[cfg(not(target_family = "unix"))]
+fn normalize_directory(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+fn normalized_mtime() -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(NORMALIZED_TIMESTAMP)
+}
+
+// 1980-01-01T00:00:01Z, the same fixed timestamp pack uses for reproducible buildpackages.
+pub(crate) const NORMALIZED_TIMESTAMP: u64 = 315_532_801;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_and_verify_buildpack_directory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let buildpack_dir = &temp_dir.path().join("buildpack");
+
+        fs::create_dir_all(buildpack_dir.join("bin")).unwrap();
+        fs::write(buildpack_dir.join("buildpack.toml"), "api = \"0.10\"\n").unwrap();
+        fs::write(buildpack_dir.join("bin").join("build"), "binary").unwrap();
+
+        #[cfg(target_family = "unix")]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(
+                buildpack_dir.join("bin").join("build"),
+                fs::Permissions::from_mode(0o700),
+            )
+            .unwrap();
+            fs::set_permissions(
+                buildpack_dir.join("buildpack.toml"),
+                fs::Permissions::from_mode(0o600),
+            )
+            .unwrap();
+        }
+
+        write_buildpack_manifest(buildpack_dir).unwrap();
+        normalize_buildpack_directory(buildpack_dir).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(manifest_path(buildpack_dir)).unwrap(),
+            "\
+            9a3a45d01531a20e89ac6ae10b0b0beb0492acd7216a368aa062d1a5fecaf9cd  bin/build\n\
+            7847654686068dd9a3f8c4bc4988ef22b0b9b1cce70c7e1f93d8e768267fe179  buildpack.toml\n"
+        );
+
+        for path in [
+            buildpack_dir.join("buildpack.toml"),
+            buildpack_dir.join("bin"),
+        ] {
+            assert_eq!(
+                fs::metadata(&path).unwrap().modified().unwrap(),
+                normalized_mtime()
+            );
+        }
+
+        #[cfg(target_family = "unix")]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |path: PathBuf| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+
+            assert_eq!(mode(buildpack_dir.join("bin").join("build")), 0o755);
+            assert_eq!(mode(buildpack_dir.join("buildpack.toml")), 0o644);
+            assert_eq!(mode(buildpack_dir.join("bin")), 0o755);
+        }
+
+        assert!(verify_buildpack_manifest(buildpack_dir).unwrap().is_empty());
+
+        fs::write(buildpack_dir.join("bin").join("build"), "changed").unwrap();
+        fs::write(buildpack_dir.join("extra"), "").unwrap();
+        fs::remove_file(buildpack_dir.join("buildpack.toml")).unwrap();
+
+        assert_eq!(
+            verify_buildpack_manifest(buildpack_dir).unwrap(),
+            ["bin/build", "buildpack.toml", "extra"]
+        );
+    }
+}
@@ -0,0 +1,343 @@
+use crate::buildpack_kind::{determine_buildpack_kind, BuildpackKind};
+use crate::dependency_reference::{
+    DependencyReference, DockerImageReference, ParseDependencyReferenceError,
+    RegistryBuildpackReference,
+};
+use crate::find_buildpack_dirs;
+use crate::util::absolutize_path;
+use libcnb_common::toml_file::{read_toml_file, TomlFileError};
+use libcnb_data::buildpack::{BuildpackDescriptor, BuildpackId};
+use libcnb_data::package_descriptor::PackageDescriptor;
+use petgraph::graph::NodeIndex;
+use petgraph::Graph;
+use std::path::{Path, PathBuf};
+
+/// Creates a graph of all buildpacks in a directory and everything they depend on.
+///
+/// Unlike [`crate::buildpack_dependency_graph::build_libcnb_buildpacks_dependency_graph`], the
+/// graph contains all buildpacks, not only libcnb.rs and composite buildpacks, and all dependencies
+/// declared in `package.toml` files. Dependencies on buildpacks in the directory (via `libcnb:`
+/// URIs or relative paths) are edges to their nodes. Buildpack images and registry buildpacks are
+/// edges to [`WorkspaceGraphNode::DockerImage`] and [`WorkspaceGraphNode::RegistryBuildpack`]
+/// leaf nodes, all other dependencies are edges to [`WorkspaceGraphNode::External`] nodes. An edge
+/// points from a buildpack to its dependency.
+///
+/// Nodes are added in the order of their buildpack directories, external nodes after all buildpacks
+/// in the order they're first referenced.
+///
+/// # Errors
+///
+/// Returns `Err` if a buildpack declares an invalid dependency or unknown `libcnb:` dependency,
+/// has an invalid buildpack.toml or package.toml or an I/O error occurred while traversing the
+/// given directory.
+pub fn build_workspace_graph(
+    start_dir: &Path,
+) -> Result<Graph<WorkspaceGraphNode, ()>, BuildWorkspaceGraphError> {
+    let mut buildpack_dirs = find_buildpack_dirs(start_dir)
+        .map_err(BuildWorkspaceGraphError::FindBuildpackDirectories)?;
+    buildpack_dirs.sort();
+
+    let mut graph = Graph::new();
+
+    for buildpack_dir in buildpack_dirs {
+        let buildpack_id =
+            read_toml_file::<BuildpackDescriptor>(buildpack_dir.join("buildpack.toml"))
+                .map_err(BuildWorkspaceGraphError::ReadBuildpackDescriptorError)?
+                .buildpack()
+                .id
+                .clone();
+
+        graph.add_node(match determine_buildpack_kind(&buildpack_dir) {
+            Some(BuildpackKind::LibCnbRs) => WorkspaceGraphNode::Libcnb {
+                buildpack_id,
+                path: buildpack_dir,
+            },
+            Some(BuildpackKind::Composite) => WorkspaceGraphNode::Composite {
+                buildpack_id,
+                path: buildpack_dir,
+            },
+            Some(BuildpackKind::Other) | None => WorkspaceGraphNode::Other {
+                buildpack_id,
+                path: buildpack_dir,
+            },
+        });
+    }
+
+    for node_index in graph.node_indices().collect::<Vec<_>>() {
+        let WorkspaceGraphNode::Composite { path, .. } = &graph[node_index] else {
+            continue;
+        };
+
+        let package_descriptor_path = path.join("package.toml");
+        if !package_descriptor_path.is_file() {
+            continue;
+        }
+
+        let buildpack_dir = path.clone();
+        let package_descriptor = read_toml_file::<PackageDescriptor>(package_descriptor_path)
+            .map_err(BuildWorkspaceGraphError::ReadPackageDescriptorError)?;
+
+        for dependency in &package_descriptor.dependencies {
+            let dependency_index = match DependencyReference::parse(dependency)
+                .map_err(BuildWorkspaceGraphError::InvalidDependencyReference)?
+            {
+                DependencyReference::Libcnb(buildpack_id) => graph
+                    .node_indices()
+                    .find(|index| graph[*index].buildpack_id() == Some(&buildpack_id))
+                    .ok_or(BuildWorkspaceGraphError::UnknownLibcnbDependency(
+                        buildpack_id,
+                    ))?,
+                DependencyReference::Path(path) => {
+                    let dependency_path = absolutize_path(&path, &buildpack_dir);
+
+                    graph
+                        .node_indices()
+                        .find(|index| graph[*index].path() == Some(&dependency_path))
+                        .unwrap_or_else(|| {
+                            leaf_node_index(
+                                &mut graph,
+                                WorkspaceGraphNode::External {
+                                    uri: dependency.uri.to_string(),
+                                },
+                            )
+                        })
+                }
+                DependencyReference::DockerImage(reference) => {
+                    leaf_node_index(&mut graph, WorkspaceGraphNode::DockerImage(reference))
+                }
+                DependencyReference::RegistryBuildpack(reference) => {
+                    leaf_node_index(&mut graph, WorkspaceGraphNode::RegistryBuildpack(reference))
+                }
+                DependencyReference::Other(uri) => {
+                    leaf_node_index(&mut graph, WorkspaceGraphNode::External { uri })
+                }
+            };
+
+            graph.update_edge(node_index, dependency_index, ());
+        }
+    }
+
+    Ok(graph)
+}
+
+// Leaf nodes are shared between all buildpacks that depend on them.
+fn leaf_node_index(
+    graph: &mut Graph<WorkspaceGraphNode, ()>,
+    node: WorkspaceGraphNode,
+) -> NodeIndex {
+    graph
+        .node_indices()
+        .find(|index| graph[*index] == node)
+        .unwrap_or_else(|| graph.add_node(node))
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum BuildWorkspaceGraphError {
+    #[error("Error while finding buildpack directories: {0}")]
+    FindBuildpackDirectories(ignore::Error),
+    #[error("Couldn't read buildpack.toml: {0}")]
+    ReadBuildpackDescriptorError(TomlFileError),
+    #[error("Couldn't read package.toml: {0}")]
+    ReadPackageDescriptorError(TomlFileError),
+    #[error("Invalid dependency: {0}")]
+    InvalidDependencyReference(ParseDependencyReferenceError),
+    #[error("Dependency libcnb:{0} doesn't refer to a buildpack")]
+    UnknownLibcnbDependency(BuildpackId),
+}
+
+/// A node in the graph created by [`build_workspace_graph`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum WorkspaceGraphNode {
+    /// A buildpack implemented with libcnb.rs.
+    Libcnb {
+        buildpack_id: BuildpackId,
+        path: PathBuf,
+    },
+    /// A composite buildpack.
+    Composite {
+        buildpack_id: BuildpackId,
+        path: PathBuf,
+    },
+    /// A component buildpack that isn't implemented with libcnb.rs, for example with bash.
+    Other {
+        buildpack_id: BuildpackId,
+        path: PathBuf,
+    },
+    /// A buildpack image, referenced via `docker://`.
+    DockerImage(DockerImageReference),
+    /// A buildpack from the buildpack registry, referenced via `urn:cnb:registry:`.
+    RegistryBuildpack(RegistryBuildpackReference),
+    /// Any other dependency that isn't a buildpack in the workspace, such as a buildpack archive
+    /// or a path outside the workspace.
+    External { uri: String },
+}
+
+impl WorkspaceGraphNode {
+    /// The buildpack ID for buildpacks in the workspace, or the URI for all other nodes.
+    #[must_use]
+    pub fn id(&self) -> String {
+        match self {
+            Self::Libcnb { buildpack_id, .. }
+            | Self::Composite { buildpack_id, .. }
+            | Self::Other { buildpack_id, .. } => buildpack_id.to_string(),
+            Self::DockerImage(reference) => reference.to_string(),
+            Self::RegistryBuildpack(reference) => reference.to_string(),
+            Self::External { uri } => uri.clone(),
+        }
+    }
+
+    #[must_use]
+    pub fn buildpack_id(&self) -> Option<&BuildpackId> {
+        match self {
+            Self::Libcnb { buildpack_id, .. }
+            | Self::Composite { buildpack_id, .. }
+            | Self::Other { buildpack_id, .. } => Some(buildpack_id),
+            Self::DockerImage(_) | Self::RegistryBuildpack(_) | Self::External { .. } => None,
+        }
+    }
+
+    #[must_use]
+    pub fn path(&self) -> Option<&PathBuf> {
+        match self {
+            Self::Libcnb { path, .. } | Self::Composite { path, .. } | Self::Other { path, .. } => {
+                Some(path)
+            }
+            Self::DockerImage(_) | Self::RegistryBuildpack(_) | Self::External { .. } => None,
+        }
+    }
+
+    /// The version a buildpack image or registry buildpack is pinned to: the image digest if there
+    /// is one, otherwise the image tag or registry buildpack version.
+    #[must_use]
+    pub fn pinned_version(&self) -> Option<String> {
+        match self {
+            Self::DockerImage(reference) => reference
+                .digest
+                .clone()
+                .or_else(|| reference.tag.clone())
+                .filter(|_| reference.is_pinned()),
+            Self::RegistryBuildpack(reference) => {
+                reference.version.as_ref().map(ToString::to_string)
+            }
+            Self::Libcnb { .. }
+            | Self::Composite { .. }
+            | Self::Other { .. }
+            | Self::External { .. } => None,
+        }
+    }
+
+    /// Whether the node is a buildpack image or registry buildpack that isn't pinned to a
+    /// specific version, see [`DependencyReference::is_pinned`].
+    #[must_use]
+    pub fn is_unpinned(&self) -> bool {
+        match self {
+            Self::DockerImage(reference) => !reference.is_pinned(),
+            Self::RegistryBuildpack(reference) => !reference.is_pinned(),
+            Self::Libcnb { .. }
+            | Self::Composite { .. }
+            | Self::Other { .. }
+            | Self::External { .. } => false,
+        }
+    }
+
+    /// A short name for the kind of node: `libcnb`, `composite`, `other`, `docker`, `registry` or
+    /// `external`.
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Libcnb { .. } => "libcnb",
+            Self::Composite { .. } => "composite",
+            Self::Other { .. } => "other",
+            Self::DockerImage(_) => "docker",
+            Self::RegistryBuildpack(_) => "registry",
+            Self::External { .. } => "external",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn build_workspace_graph_with_all_node_kinds() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+
+        let composite_dir = root.join("composite");
+        fs::create_dir_all(&composite_dir).unwrap();
+        fs::write(
+            composite_dir.join("buildpack.toml"),
+            "api = \"0.10\"\n\n[buildpack]\nid = \"example/composite\"\nversion = \"0.0.1\"\n\n[[order]]\n[[order.group]]\nid = \"example/libcnb\"\nversion = \"0.0.1\"\n",
+        )
+        .unwrap();
+        fs::write(
+            composite_dir.join("package.toml"),
+            "[buildpack]\nuri = \".\"\n\n[[dependencies]]\nuri = \"libcnb:example/libcnb\"\n\n[[dependencies]]\nuri = \"../bash\"\n\n[[dependencies]]\nuri = \"docker://docker.io/heroku/example:1.2.3\"\n\n[[dependencies]]\nuri = \"urn:cnb:registry:heroku/example\"\n",
+        )
+        .unwrap();
+
+        for (dir_name, buildpack_id) in [("bash", "example/bash"), ("libcnb", "example/libcnb")] {
+            fs::create_dir_all(root.join(dir_name)).unwrap();
+            fs::write(
+                root.join(dir_name).join("buildpack.toml"),
+                format!(
+                    "api = \"0.10\"\n\n[buildpack]\nid = \"{buildpack_id}\"\nversion = \"0.0.1\"\n"
+                ),
+            )
+            .unwrap();
+        }
+        fs::write(root.join("libcnb").join("Cargo.toml"), "").unwrap();
+
+        let graph = build_workspace_graph(root).unwrap();
+
+        assert_eq!(
+            graph
+                .node_weights()
+                .map(|node| (node.id(), node.kind()))
+                .collect::<Vec<_>>(),
+            [
+                (String::from("example/bash"), "other"),
+                (String::from("example/composite"), "composite"),
+                (String::from("example/libcnb"), "libcnb"),
+                (
+                    String::from("docker://docker.io/heroku/example:1.2.3"),
+                    "docker"
+                ),
+                (String::from("urn:cnb:registry:heroku/example"), "registry"),
+            ]
+        );
+
+        assert_eq!(
+            graph
+                .node_weights()
+                .filter(|node| node.buildpack_id().is_none())
+                .map(|node| (node.pinned_version(), node.is_unpinned()))
+                .collect::<Vec<_>>(),
+            [(Some(String::from("1.2.3")), false), (None, true)]
+        );
+
+        let composite_index = graph
+            .node_indices()
+            .find(|index| graph[*index].kind() == "composite")
+            .unwrap();
+
+        let mut dependencies = graph
+            .neighbors(composite_index)
+            .map(|index| graph[index].id())
+            .collect::<Vec<_>>();
+        dependencies.sort();
+
+        assert_eq!(
+            dependencies,
+            [
+                "docker://docker.io/heroku/example:1.2.3",
+                "example/bash",
+                "example/libcnb",
+                "urn:cnb:registry:heroku/example"
+            ]
+        );
+        assert_eq!(graph.edge_count(), 4);
+    }
+}
@@ -1,5 +1,6 @@
 use libcnb_common::toml_file::{read_toml_file, TomlFileError};
 use libcnb_data::buildpack::{BuildpackDescriptor, BuildpackId};
+use libcnb_package::build::CargoBuildOptions;
 use libcnb_package::buildpack_dependency_graph::{
     build_libcnb_buildpacks_dependency_graph, BuildBuildpackDependencyGraphError,
 };
@@ -88,6 +89,7 @@ pub(crate) fn package_buildpack(
         libcnb_package::package::package_buildpack(
             &node.path,
             cargo_profile,
+            &CargoBuildOptions::default(),
             target_triple.as_ref(),
             &cargo_build_env,
             &buildpack_destination_dir,
@@ -1,7 +1,7 @@
 use crate::docker::{DockerRemoveImageCommand, DockerRemoveVolumeCommand};
-use crate::pack::PackBuildCommand;
 use crate::util::CommandError;
 use crate::{app, build, util, BuildConfig, BuildpackReference, PackResult, TestContext};
+use libcnb_package::pack::PackBuildCommand;
 use std::borrow::Borrow;
 use std::env;
 use std::path::PathBuf;
@@ -102,10 +102,10 @@ impl TestRunner {
         let buildpacks_target_dir =
             tempdir().expect("Error creating temporary directory for compiled buildpacks");
 
-        let mut pack_command = PackBuildCommand::new(
-            &config.builder_name,
-            &app_dir,
-            &docker_resources.image_name,
+        let mut pack_command =
+            PackBuildCommand::new(&config.builder_name, &app_dir, &docker_resources.image_name);
+
+        pack_command.cache_volumes(
             &docker_resources.build_cache_volume_name,
             &docker_resources.launch_cache_volume_name,
         );
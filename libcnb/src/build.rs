@@ -4,7 +4,8 @@ use crate::buildpack::Buildpack;
 use crate::data::layer::LayerName;
 use crate::data::store::Store;
 use crate::data::{
-    buildpack::ComponentBuildpackDescriptor, buildpack_plan::BuildpackPlan, launch::Launch,
+    buildpack::BuildpackApi, buildpack::ComponentBuildpackDescriptor,
+    buildpack_plan::BuildpackPlan, launch::Launch,
 };
 use crate::layer::trait_api::handling::LayerErrorOrBuildpackError;
 use crate::layer::{
@@ -471,6 +472,15 @@ impl<B: Buildpack + ?Sized> BuildContext<B> {
             &self.layers_dir,
         )
     }
+
+    /// The Buildpack API version of this buildpack, as declared in `buildpack.toml`.
+    ///
+    /// libcnb supports more than one Buildpack API version. Use this to account for behavioural
+    /// differences between them, if necessary.
+    #[must_use]
+    pub fn api_version(&self) -> BuildpackApi {
+        self.buildpack_descriptor.api
+    }
 }
 
 /// Describes the result of the build phase.
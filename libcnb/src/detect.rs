@@ -1,6 +1,7 @@
 //! Provides detect phase specific types and helpers.
 
 use crate::buildpack::Buildpack;
+use crate::data::buildpack::BuildpackApi;
 use crate::Target;
 use crate::{data::build_plan::BuildPlan, data::buildpack::ComponentBuildpackDescriptor};
 use std::fmt::Debug;
@@ -15,6 +16,17 @@ pub struct DetectContext<B: Buildpack + ?Sized> {
     pub buildpack_descriptor: ComponentBuildpackDescriptor<B::Metadata>,
 }
 
+impl<B: Buildpack + ?Sized> DetectContext<B> {
+    /// The Buildpack API version of this buildpack, as declared in `buildpack.toml`.
+    ///
+    /// libcnb supports more than one Buildpack API version. Use this to account for behavioural
+    /// differences between them, if necessary.
+    #[must_use]
+    pub fn api_version(&self) -> BuildpackApi {
+        self.buildpack_descriptor.api
+    }
+}
+
 /// Describes the result of the detect phase.
 ///
 /// Besides indicating passing or failing detection, it also contains detect phase output such as
@@ -19,7 +19,7 @@ use crate::layer::{
     ExistingLayerStrategy, Layer, LayerData, LayerResult, LayerResultBuilder, MetadataMigration,
 };
 use crate::layer_env::{LayerEnv, ModificationBehavior, Scope};
-use crate::{read_toml_file, Buildpack, Env, Target, LIBCNB_SUPPORTED_BUILDPACK_API};
+use crate::{read_toml_file, Buildpack, Env, Target, LIBCNB_SUPPORTED_BUILDPACK_APIS};
 use libcnb_data::buildpack::{BuildpackTarget, BuildpackVersion, ComponentBuildpackDescriptor};
 use libcnb_data::buildpack_plan::BuildpackPlan;
 use libcnb_data::layer_content_metadata::LayerContentMetadata;
@@ -912,7 +912,7 @@ fn build_context(temp_dir: &TempDir) -> BuildContext<TestBuildpack> {
             entries: Vec::new(),
         },
         buildpack_descriptor: ComponentBuildpackDescriptor {
-            api: LIBCNB_SUPPORTED_BUILDPACK_API,
+            api: *LIBCNB_SUPPORTED_BUILDPACK_APIS.end(),
             buildpack: crate::data::buildpack::Buildpack {
                 id: buildpack_id!("libcnb/test"),
                 name: None,
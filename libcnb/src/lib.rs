@@ -38,8 +38,13 @@ use serde_json as _;
 #[doc(inline)]
 pub use libcnb_data as data;
 
-const LIBCNB_SUPPORTED_BUILDPACK_API: data::buildpack::BuildpackApi =
-    data::buildpack::BuildpackApi {
+/// The range of Buildpack API versions that libcnb supports.
+///
+/// Buildpacks can use [`BuildContext::api_version`](build::BuildContext::api_version) and
+/// [`DetectContext::api_version`](detect::DetectContext::api_version) to account for differences
+/// between the supported versions.
+const LIBCNB_SUPPORTED_BUILDPACK_APIS: std::ops::RangeInclusive<data::buildpack::BuildpackApi> =
+    data::buildpack::BuildpackApi { major: 0, minor: 9 }..=data::buildpack::BuildpackApi {
         major: 0,
         minor: 10,
     };
@@ -8,7 +8,7 @@ use crate::sbom::cnb_sbom_path;
 #[cfg(feature = "trace")]
 use crate::tracing::start_trace;
 use crate::util::is_not_found_error_kind;
-use crate::{exit_code, Target, TomlFileError, LIBCNB_SUPPORTED_BUILDPACK_API};
+use crate::{exit_code, Target, TomlFileError, LIBCNB_SUPPORTED_BUILDPACK_APIS};
 use libcnb_common::toml_file::{read_toml_file, write_toml_file};
 use libcnb_data::buildpack::ComponentBuildpackDescriptor;
 use libcnb_data::store::Store;
@@ -34,20 +34,24 @@ use std::{env, fs};
 #[doc(hidden)]
 pub fn libcnb_runtime<B: Buildpack>(buildpack: &B) {
     // Before we do anything else, we must validate that the Buildpack's API version
-    // matches that supported by libcnb, to improve the UX in cases where the lifecycle
+    // is one of those supported by libcnb, to improve the UX in cases where the lifecycle
     // passes us arguments or env vars we don't expect, due to changes between API versions.
     // We use a cut-down buildpack descriptor type, to ensure we can still read the API
     // version even if the rest of buildpack.toml doesn't match the spec (or the buildpack's
     // chosen custom `metadata` type).
     match read_buildpack_descriptor::<BuildpackDescriptorApiOnly, B::Error>() {
         Ok(buildpack_descriptor) => {
-            if buildpack_descriptor.api != LIBCNB_SUPPORTED_BUILDPACK_API {
+            if !LIBCNB_SUPPORTED_BUILDPACK_APIS.contains(&buildpack_descriptor.api) {
                 eprintln!("Error: Cloud Native Buildpack API mismatch");
                 eprintln!(
                     "This buildpack uses Cloud Native Buildpacks API version {} (specified in buildpack.toml).",
                     &buildpack_descriptor.api,
                 );
-                eprintln!("However, the underlying libcnb.rs library only supports CNB API {LIBCNB_SUPPORTED_BUILDPACK_API}.");
+                eprintln!(
+                    "However, the underlying libcnb.rs library only supports CNB API {} to {}.",
+                    LIBCNB_SUPPORTED_BUILDPACK_APIS.start(),
+                    LIBCNB_SUPPORTED_BUILDPACK_APIS.end()
+                );
                 exit(exit_code::GENERIC_CNB_API_VERSION_ERROR)
             }
         }
@@ -146,7 +150,7 @@ pub fn libcnb_runtime_detect<B: Buildpack>(
 
     let build_plan_path = args.build_plan_path;
 
-    let target = context_target().inspect_err(|err| trace_error(err))?;
+    let target = context_target(buildpack_descriptor.api).inspect_err(|err| trace_error(err))?;
 
     let detect_context = DetectContext {
         app_dir,
@@ -223,7 +227,7 @@ pub fn libcnb_runtime_build<B: Buildpack>(
     .map_err(Error::CannotReadStore)
     .inspect_err(|err| trace_error(err))?;
 
-    let target = context_target().inspect_err(|err| trace_error(err))?;
+    let target = context_target(buildpack_descriptor.api).inspect_err(|err| trace_error(err))?;
 
     let build_context = BuildContext {
         layers_dir: layers_dir.clone(),
@@ -357,10 +361,17 @@ fn read_buildpack_descriptor<BD: DeserializeOwned, E: Debug>() -> crate::Result<
     })
 }
 
-fn context_target<E>() -> crate::Result<Target, E>
+fn context_target<E>(buildpack_api: BuildpackApi) -> crate::Result<Target, E>
 where
     E: Debug,
 {
+    // Buildpack API 0.9 predates targets and lifecycle does not pass the `CNB_TARGET_*` env vars
+    // to buildpacks using it. To still provide buildpacks with a target, it is determined from the
+    // build environment itself, similar to how lifecycle determines it for newer API versions.
+    if buildpack_api < BUILDPACK_API_WITH_TARGETS {
+        return Ok(build_environment_target());
+    }
+
     let os = env::var("CNB_TARGET_OS").map_err(Error::CannotDetermineTargetOs)?;
     let arch = env::var("CNB_TARGET_ARCH").map_err(Error::CannotDetermineTargetArch)?;
     let arch_variant = env::var("CNB_TARGET_ARCH_VARIANT").ok();
@@ -380,3 +391,95 @@ where
         distro_version,
     })
 }
+
+const BUILDPACK_API_WITH_TARGETS: BuildpackApi = BuildpackApi {
+    major: 0,
+    minor: 10,
+};
+
+fn build_environment_target() -> Target {
+    // The buildpack binary is compiled for the platform it runs on, so the compile-time OS and
+    // architecture match those of the build environment.
+    let os = String::from(env::consts::OS);
+    let arch = go_arch_name(env::consts::ARCH);
+
+    // Minimal and distroless images might not have an /etc/os-release, or one without all fields.
+    // Lifecycle accepts those images as well, leaving the distro empty.
+    let (distro_name, distro_version) = if os == "linux" {
+        fs::read_to_string("/etc/os-release")
+            .map(|contents| parse_os_release_distro(&contents))
+            .unwrap_or_default()
+    } else {
+        (String::new(), String::new())
+    };
+
+    Target {
+        os,
+        arch,
+        arch_variant: None,
+        distro_name,
+        distro_version,
+    }
+}
+
+// Rust and Go use different names for some CPU architectures. Target values follow Go's naming.
+fn go_arch_name(rust_arch: &str) -> String {
+    String::from(match rust_arch {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "x86" => "386",
+        "powerpc64" => "ppc64",
+        other => other,
+    })
+}
+
+// Returns the `ID` and `VERSION_ID` fields of an os-release file, empty if they're missing.
+fn parse_os_release_distro(contents: &str) -> (String, String) {
+    let value = |key: &str| {
+        contents
+            .lines()
+            .find_map(|line| line.trim().strip_prefix(key)?.strip_prefix('='))
+            .map(|value| value.trim().trim_matches(['"', '\'']).to_string())
+            .unwrap_or_default()
+    };
+
+    (value("ID"), value("VERSION_ID"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_os_release_distro_ubuntu() {
+        let contents = r#"PRETTY_NAME="Ubuntu 24.04 LTS"
+NAME="Ubuntu"
+VERSION_ID="24.04"
+VERSION="24.04 LTS (Noble Numbat)"
+ID=ubuntu
+ID_LIKE=debian
+"#;
+
+        assert_eq!(
+            parse_os_release_distro(contents),
+            (String::from("ubuntu"), String::from("24.04"))
+        );
+    }
+
+    #[test]
+    fn parse_os_release_distro_missing_field() {
+        assert_eq!(
+            parse_os_release_distro("NAME=\"Alpine Linux\"\nID=alpine\n"),
+            (String::from("alpine"), String::new())
+        );
+        assert_eq!(parse_os_release_distro(""), (String::new(), String::new()));
+    }
+
+    #[test]
+    fn go_arch_names() {
+        assert_eq!(go_arch_name("x86_64"), "amd64");
+        assert_eq!(go_arch_name("aarch64"), "arm64");
+        assert_eq!(go_arch_name("arm"), "arm");
+        assert_eq!(go_arch_name("riscv64"), "riscv64");
+    }
+}
@@ -14,6 +14,10 @@ pub mod error;
 pub mod fs;
 #[cfg(feature = "log")]
 pub mod log;
+#[cfg(feature = "procfile")]
+pub mod procfile;
+#[cfg(feature = "slice")]
+pub mod slice;
 #[cfg(feature = "tar")]
 pub mod tar;
 #[cfg(feature = "toml")]
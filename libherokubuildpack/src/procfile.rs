@@ -0,0 +1,473 @@
+use libcnb::data::launch::{Process, ProcessBuilder, ProcessType, ProcessTypeError};
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+
+/// An app's `Procfile`, declaring the process types of the app and the commands that run them.
+///
+/// Entries are kept in the order they appear in the file. If a process type is declared more than
+/// once, the last declaration wins, but the process keeps the position of its first declaration.
+/// This mirrors how the Heroku platform interprets `Procfile`s.
+///
+/// # Examples
+/// ```
+/// use libcnb::data::process_type;
+/// use libherokubuildpack::procfile::{Procfile, ProcfileProcessStrategy};
+///
+/// let procfile: Procfile = "web: bundle exec puma -p $PORT\nworker: bundle exec sidekiq\n"
+///     .parse()
+///     .unwrap();
+///
+/// assert_eq!(
+///     procfile.get(&process_type!("worker")),
+///     Some("bundle exec sidekiq")
+/// );
+///
+/// let processes = procfile
+///     .launch_processes(ProcfileProcessStrategy::BashC)
+///     .unwrap();
+///
+/// assert_eq!(processes[0].r#type, process_type!("web"));
+/// assert_eq!(
+///     processes[0].command,
+///     ["bash", "-c", "bundle exec puma -p $PORT"]
+/// );
+/// assert!(processes[0].default);
+/// ```
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct Procfile {
+    entries: Vec<(ProcessType, String)>,
+}
+
+impl Procfile {
+    /// Creates an empty `Procfile`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads and parses the `Procfile` at the given path.
+    ///
+    /// Returns `Ok(None)` if there is no file at the given path.
+    pub fn read(path: impl AsRef<Path>) -> Result<Option<Self>, ReadProcfileError> {
+        match std::fs::read_to_string(path.as_ref()) {
+            Ok(contents) => contents
+                .parse()
+                .map(Some)
+                .map_err(ReadProcfileError::ParseError),
+            Err(io_error) if io_error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(io_error) => Err(ReadProcfileError::IoError(io_error)),
+        }
+    }
+
+    /// Adds a process type with the given command, replacing the command of an already existing
+    /// process type with the same name.
+    pub fn insert(&mut self, process_type: ProcessType, command: impl Into<String>) {
+        let command = command.into();
+
+        match self
+            .entries
+            .iter_mut()
+            .find(|(existing_process_type, _)| existing_process_type == &process_type)
+        {
+            Some((_, existing_command)) => *existing_command = command,
+            None => self.entries.push((process_type, command)),
+        }
+    }
+
+    /// Returns the command for the given process type, if it exists.
+    #[must_use]
+    pub fn get(&self, process_type: &ProcessType) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(existing_process_type, _)| existing_process_type == process_type)
+            .map(|(_, command)| command.as_str())
+    }
+
+    /// Returns an iterator over all process types and their commands, in declaration order.
+    pub fn iter(&self) -> impl Iterator<Item = (&ProcessType, &str)> {
+        self.entries
+            .iter()
+            .map(|(process_type, command)| (process_type, command.as_str()))
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Converts the entries of this `Procfile` into launch processes.
+    ///
+    /// The `web` process is marked as the default process. If there is no `web` process and the
+    /// `Procfile` only declares a single process, that process is marked as default instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a command cannot be split into words when using
+    /// [`ProcfileProcessStrategy::Exec`].
+    pub fn launch_processes(
+        &self,
+        strategy: ProcfileProcessStrategy,
+    ) -> Result<Vec<Process>, ProcfileCommandError> {
+        let default_process_type = self
+            .entries
+            .iter()
+            .find(|(process_type, _)| process_type.as_str() == "web")
+            .or(match self.entries.as_slice() {
+                [single_entry] => Some(single_entry),
+                _ => None,
+            })
+            .map(|(process_type, _)| process_type);
+
+        self.entries
+            .iter()
+            .map(|(process_type, command)| {
+                let command = match strategy {
+                    ProcfileProcessStrategy::BashC => {
+                        vec![String::from("bash"), String::from("-c"), command.clone()]
+                    }
+                    ProcfileProcessStrategy::Exec => {
+                        split_command(command).map_err(|error| ProcfileCommandError {
+                            process_type: process_type.clone(),
+                            kind: error,
+                        })?
+                    }
+                };
+
+                Ok(ProcessBuilder::new(process_type.clone(), command)
+                    .default(Some(process_type) == default_process_type)
+                    .build())
+            })
+            .collect()
+    }
+}
+
+impl FromStr for Procfile {
+    type Err = ProcfileParsingError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut procfile = Procfile::new();
+
+        // Some editors on Windows prepend a byte order mark, which must not end up in the first
+        // process type name.
+        for (index, line) in value.trim_start_matches('\u{feff}').lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (name, command) =
+                line.split_once(':')
+                    .ok_or_else(|| ProcfileParsingError::MissingSeparator {
+                        line_number,
+                        line: line.to_string(),
+                    })?;
+
+            let process_type = name
+                .trim()
+                .parse::<ProcessType>()
+                .map_err(|error| ProcfileParsingError::InvalidProcessType { line_number, error })?;
+
+            let command = command.trim();
+            if command.is_empty() {
+                return Err(ProcfileParsingError::EmptyCommand {
+                    line_number,
+                    process_type,
+                });
+            }
+
+            procfile.insert(process_type, command);
+        }
+
+        Ok(procfile)
+    }
+}
+
+impl Display for Procfile {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        for (process_type, command) in &self.entries {
+            writeln!(formatter, "{process_type}: {command}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Determines how `Procfile` commands are turned into launch process commands.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ProcfileProcessStrategy {
+    /// Runs the command with `bash -c`, allowing shell features such as environment variable
+    /// expansion, pipes and `&&`. This matches how the Heroku platform runs `Procfile` commands.
+    BashC,
+    /// Splits the command into words, using shell-like quoting rules, and executes it directly
+    /// without a shell. Shell features such as environment variable expansion are not available.
+    Exec,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ProcfileParsingError {
+    #[error("Line {line_number} is not in the format `<process type>: <command>`: {line}")]
+    MissingSeparator { line_number: usize, line: String },
+    #[error("Line {line_number} has an invalid process type: {error}")]
+    InvalidProcessType {
+        line_number: usize,
+        error: ProcessTypeError,
+    },
+    #[error("Line {line_number} has an empty command for process type {process_type}")]
+    EmptyCommand {
+        line_number: usize,
+        process_type: ProcessType,
+    },
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ReadProcfileError {
+    #[error("Couldn't read Procfile: {0}")]
+    IoError(#[source] std::io::Error),
+    #[error("Couldn't parse Procfile: {0}")]
+    ParseError(#[source] ProcfileParsingError),
+}
+
+#[derive(thiserror::Error, Debug, Eq, PartialEq)]
+#[error("Couldn't split command of process type {process_type}: {kind}")]
+pub struct ProcfileCommandError {
+    pub process_type: ProcessType,
+    pub kind: SplitCommandError,
+}
+
+#[derive(thiserror::Error, Debug, Eq, PartialEq)]
+pub enum SplitCommandError {
+    #[error("Unterminated quote")]
+    UnterminatedQuote,
+    #[error("Trailing backslash")]
+    TrailingBackslash,
+}
+
+/// Splits a command into words, following the quoting rules of POSIX shells.
+///
+/// Single quotes preserve everything literally, double quotes allow backslash escapes and a
+/// backslash outside of quotes escapes the following character.
+fn split_command(command: &str) -> Result<Vec<String>, SplitCommandError> {
+    let mut words = Vec::new();
+    let mut current_word: Option<String> = None;
+    let mut chars = command.chars();
+
+    while let Some(char) = chars.next() {
+        match char {
+            '\'' => {
+                let word = current_word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(char) => word.push(char),
+                        None => return Err(SplitCommandError::UnterminatedQuote),
+                    }
+                }
+            }
+            '"' => {
+                let word = current_word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(char @ ('"' | '\\' | '$' | '`')) => word.push(char),
+                            Some(char) => {
+                                word.push('\\');
+                                word.push(char);
+                            }
+                            None => return Err(SplitCommandError::UnterminatedQuote),
+                        },
+                        Some(char) => word.push(char),
+                        None => return Err(SplitCommandError::UnterminatedQuote),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some(char) => current_word.get_or_insert_with(String::new).push(char),
+                None => return Err(SplitCommandError::TrailingBackslash),
+            },
+            char if char.is_whitespace() => {
+                if let Some(word) = current_word.take() {
+                    words.push(word);
+                }
+            }
+            char => current_word.get_or_insert_with(String::new).push(char),
+        }
+    }
+
+    words.extend(current_word);
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libcnb::data::process_type;
+
+    #[test]
+    fn parse_preserves_order() {
+        let procfile: Procfile = "worker: sidekiq\nweb: puma\nrelease: rake db:migrate\n"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            procfile.iter().collect::<Vec<_>>(),
+            [
+                (&process_type!("worker"), "sidekiq"),
+                (&process_type!("web"), "puma"),
+                (&process_type!("release"), "rake db:migrate"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_ignores_comments_blank_lines_and_whitespace() {
+        let procfile: Procfile =
+            "\u{feff}# A comment\r\n\r\n  web:   puma -C config/puma.rb  \r\n\t\n"
+                .parse()
+                .unwrap();
+
+        assert_eq!(
+            procfile.iter().collect::<Vec<_>>(),
+            [(&process_type!("web"), "puma -C config/puma.rb")]
+        );
+    }
+
+    #[test]
+    fn parse_duplicate_process_type() {
+        let procfile: Procfile = "web: first\nworker: sidekiq\nweb: second\n"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            procfile.iter().collect::<Vec<_>>(),
+            [
+                (&process_type!("web"), "second"),
+                (&process_type!("worker"), "sidekiq"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(matches!(
+            "web: puma\nnot a process\n".parse::<Procfile>(),
+            Err(ProcfileParsingError::MissingSeparator { line_number: 2, .. })
+        ));
+
+        assert!(matches!(
+            "web/foo: puma".parse::<Procfile>(),
+            Err(ProcfileParsingError::InvalidProcessType { line_number: 1, .. })
+        ));
+
+        assert!(matches!(
+            ": puma".parse::<Procfile>(),
+            Err(ProcfileParsingError::InvalidProcessType { line_number: 1, .. })
+        ));
+
+        assert!(matches!(
+            "\nweb:   \n".parse::<Procfile>(),
+            Err(ProcfileParsingError::EmptyCommand { line_number: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn display_roundtrip() {
+        let procfile: Procfile = "web:puma\n\nworker:   sidekiq".parse().unwrap();
+
+        assert_eq!(procfile.to_string(), "web: puma\nworker: sidekiq\n");
+        assert_eq!(procfile.to_string().parse::<Procfile>().unwrap(), procfile);
+    }
+
+    #[test]
+    fn launch_processes_bash_c() {
+        let procfile: Procfile = "worker: sidekiq -q default\nweb: puma -p $PORT"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            procfile
+                .launch_processes(ProcfileProcessStrategy::BashC)
+                .unwrap(),
+            [
+                ProcessBuilder::new(
+                    process_type!("worker"),
+                    ["bash", "-c", "sidekiq -q default"]
+                )
+                .build(),
+                ProcessBuilder::new(process_type!("web"), ["bash", "-c", "puma -p $PORT"])
+                    .default(true)
+                    .build(),
+            ]
+        );
+    }
+
+    #[test]
+    fn launch_processes_exec() {
+        let procfile: Procfile = r#"web: java -jar "target/my app.jar" --name='a b' \"quoted\""#
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            procfile
+                .launch_processes(ProcfileProcessStrategy::Exec)
+                .unwrap(),
+            [ProcessBuilder::new(
+                process_type!("web"),
+                [
+                    "java",
+                    "-jar",
+                    "target/my app.jar",
+                    "--name=a b",
+                    "\"quoted\""
+                ]
+            )
+            .default(true)
+            .build()]
+        );
+
+        let procfile: Procfile = "web: echo 'unterminated".parse().unwrap();
+
+        assert_eq!(
+            procfile.launch_processes(ProcfileProcessStrategy::Exec),
+            Err(ProcfileCommandError {
+                process_type: process_type!("web"),
+                kind: SplitCommandError::UnterminatedQuote
+            })
+        );
+    }
+
+    #[test]
+    fn launch_processes_default_process() {
+        let procfile: Procfile = "worker: sidekiq".parse().unwrap();
+        let processes = procfile
+            .launch_processes(ProcfileProcessStrategy::BashC)
+            .unwrap();
+        assert!(processes[0].default);
+
+        let procfile: Procfile = "worker: sidekiq\nclock: clockwork".parse().unwrap();
+        let processes = procfile
+            .launch_processes(ProcfileProcessStrategy::BashC)
+            .unwrap();
+        assert!(processes.iter().all(|process| !process.default));
+    }
+
+    #[test]
+    fn split_command_edge_cases() {
+        assert_eq!(split_command("  ").unwrap(), Vec::<String>::new());
+        assert_eq!(split_command("a ''").unwrap(), ["a", ""]);
+        assert_eq!(split_command(r"a\ b c").unwrap(), ["a b", "c"]);
+        assert_eq!(split_command(r#""a\$b\n""#).unwrap(), [r"a$b\n"]);
+        assert_eq!(
+            split_command("a\\"),
+            Err(SplitCommandError::TrailingBackslash)
+        );
+    }
+}
@@ -0,0 +1,601 @@
+use libcnb::data::launch::Slice;
+use std::collections::BTreeMap;
+use std::fs;
+use std::iter::Peekable;
+use std::path::{Component, Path, PathBuf};
+use std::str::Chars;
+
+/// Resolves the path globs of the given launch slices against the app directory.
+///
+/// This mirrors what the CNB lifecycle does during export: each glob is matched against the
+/// files in the app directory, using the [Go `filepath.Match` syntax](https://golang.org/pkg/path/filepath/#Match).
+/// When a glob matches a directory, all files within that directory are part of the slice. Files
+/// that are matched by multiple slices only end up in the first of those slices, and all files
+/// that are not matched by any slice end up in the regular app layer.
+///
+/// Since the lifecycle silently ignores most mistakes in slice definitions, this function also
+/// reports problems such as globs that match nothing, slices that end up empty and slices that
+/// overlap. See [`SliceProblem`] for details.
+///
+/// # Examples
+/// ```
+/// use libcnb::data::launch::Slice;
+/// use libherokubuildpack::slice::{resolve_slices, SliceProblem};
+/// use std::fs;
+/// use std::path::PathBuf;
+/// use tempfile::tempdir;
+///
+/// let app_dir = tempdir().unwrap();
+/// fs::create_dir_all(app_dir.path().join("public/assets")).unwrap();
+/// fs::write(app_dir.path().join("public/assets/app.js"), "alert(1);").unwrap();
+/// fs::write(app_dir.path().join("public/index.html"), "<html/>").unwrap();
+/// fs::write(app_dir.path().join("server.rb"), "puts 1").unwrap();
+///
+/// let resolved_slices = resolve_slices(
+///     app_dir.path(),
+///     &[
+///         Slice {
+///             path_globs: vec![String::from("public/assets")],
+///         },
+///         Slice {
+///             path_globs: vec![String::from("public/*.html"), String::from("*.css")],
+///         },
+///     ],
+/// )
+/// .unwrap();
+///
+/// assert_eq!(
+///     resolved_slices.slices[0].contents.files,
+///     [PathBuf::from("public/assets/app.js")]
+/// );
+/// assert_eq!(resolved_slices.slices[0].contents.size_in_bytes, 9);
+/// assert_eq!(
+///     resolved_slices.unsliced.files,
+///     [PathBuf::from("server.rb")]
+/// );
+/// assert_eq!(
+///     resolved_slices.problems,
+///     [SliceProblem::UnmatchedGlob {
+///         slice_index: 1,
+///         glob: String::from("*.css")
+///     }]
+/// );
+/// ```
+///
+/// # Errors
+///
+/// Returns `Err` if the app directory couldn't be traversed.
+pub fn resolve_slices(
+    app_dir: impl AsRef<Path>,
+    slices: &[Slice],
+) -> Result<ResolvedSlices, std::io::Error> {
+    let mut problems = Vec::new();
+
+    let slice_patterns = slices
+        .iter()
+        .enumerate()
+        .map(|(slice_index, slice)| {
+            slice
+                .path_globs
+                .iter()
+                .filter_map(|glob| match GlobPattern::parse(glob) {
+                    Ok(pattern) => Some((glob, pattern)),
+                    Err(GlobPatternError::BadPattern) => {
+                        problems.push(SliceProblem::InvalidGlob {
+                            slice_index,
+                            glob: glob.clone(),
+                        });
+                        None
+                    }
+                    Err(GlobPatternError::OutsideAppDir) => {
+                        problems.push(SliceProblem::GlobOutsideAppDir {
+                            slice_index,
+                            glob: glob.clone(),
+                        });
+                        None
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut resolved_slices = slices
+        .iter()
+        .map(|slice| ResolvedSlice {
+            path_globs: slice.path_globs.clone(),
+            contents: SliceContents::default(),
+        })
+        .collect::<Vec<_>>();
+
+    let mut unsliced = SliceContents::default();
+    let mut matched_globs = slice_patterns
+        .iter()
+        .map(|patterns| vec![false; patterns.len()])
+        .collect::<Vec<_>>();
+
+    // Maps pairs of (slice that received the files, later slice that also matched them) to the
+    // overlapping files.
+    let mut overlaps = BTreeMap::<(usize, usize), Vec<PathBuf>>::new();
+
+    for (relative_path, size_in_bytes) in collect_files(app_dir.as_ref())? {
+        let mut matching_slice_indices = Vec::new();
+
+        for (slice_index, patterns) in slice_patterns.iter().enumerate() {
+            let mut slice_matches = false;
+
+            for (pattern_index, (_, pattern)) in patterns.iter().enumerate() {
+                if pattern.matches_path_or_ancestor(&relative_path) {
+                    matched_globs[slice_index][pattern_index] = true;
+                    slice_matches = true;
+                }
+            }
+
+            if slice_matches {
+                matching_slice_indices.push(slice_index);
+            }
+        }
+
+        match matching_slice_indices.split_first() {
+            Some((&slice_index, other_slice_indices)) => {
+                for &other_slice_index in other_slice_indices {
+                    overlaps
+                        .entry((slice_index, other_slice_index))
+                        .or_default()
+                        .push(relative_path.clone());
+                }
+
+                resolved_slices[slice_index]
+                    .contents
+                    .push(relative_path, size_in_bytes);
+            }
+            None => unsliced.push(relative_path, size_in_bytes),
+        }
+    }
+
+    for (slice_index, patterns) in slice_patterns.iter().enumerate() {
+        for (pattern_index, (glob, _)) in patterns.iter().enumerate() {
+            if !matched_globs[slice_index][pattern_index] {
+                problems.push(SliceProblem::UnmatchedGlob {
+                    slice_index,
+                    glob: (*glob).clone(),
+                });
+            }
+        }
+    }
+
+    for ((slice_index, other_slice_index), files) in overlaps {
+        problems.push(SliceProblem::Overlap {
+            slice_index,
+            other_slice_index,
+            files,
+        });
+    }
+
+    for (slice_index, resolved_slice) in resolved_slices.iter().enumerate() {
+        if resolved_slice.contents.files.is_empty() {
+            problems.push(SliceProblem::EmptySlice { slice_index });
+        }
+    }
+
+    Ok(ResolvedSlices {
+        slices: resolved_slices,
+        unsliced,
+        problems,
+    })
+}
+
+/// The result of resolving launch slices against an app directory.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ResolvedSlices {
+    /// The resolved slices, in the same order as they were given.
+    pub slices: Vec<ResolvedSlice>,
+    /// Files that are not part of any slice and will end up in the regular app layer.
+    pub unsliced: SliceContents,
+    /// Problems found while resolving the slices.
+    pub problems: Vec<SliceProblem>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ResolvedSlice {
+    pub path_globs: Vec<String>,
+    pub contents: SliceContents,
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct SliceContents {
+    /// Files of the slice, relative to the app directory and sorted by path.
+    pub files: Vec<PathBuf>,
+    /// The estimated size of the slice, the sum of the sizes of all its files.
+    pub size_in_bytes: u64,
+}
+
+impl SliceContents {
+    fn push(&mut self, path: PathBuf, size_in_bytes: u64) {
+        self.files.push(path);
+        self.size_in_bytes += size_in_bytes;
+    }
+}
+
+/// A problem with a slice definition.
+///
+/// Slices are referenced by their index in the list of slices that was resolved.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SliceProblem {
+    /// The glob is not a valid pattern and will be ignored.
+    InvalidGlob { slice_index: usize, glob: String },
+    /// The glob refers to paths outside the app directory and will be ignored.
+    GlobOutsideAppDir { slice_index: usize, glob: String },
+    /// The glob did not match any file in the app directory.
+    UnmatchedGlob { slice_index: usize, glob: String },
+    /// Files are matched by both slices. Only the slice with `slice_index` will contain them.
+    Overlap {
+        slice_index: usize,
+        other_slice_index: usize,
+        files: Vec<PathBuf>,
+    },
+    /// The slice will not contain any files.
+    EmptySlice { slice_index: usize },
+}
+
+/// Recursively collects all non-directory entries in the given directory, with their path
+/// relative to that directory and their size. Symlinks are not followed.
+fn collect_files(dir: &Path) -> Result<Vec<(PathBuf, u64)>, std::io::Error> {
+    fn collect(
+        dir: &Path,
+        relative_dir: &Path,
+        files: &mut Vec<(PathBuf, u64)>,
+    ) -> Result<(), std::io::Error> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let metadata = entry.path().symlink_metadata()?;
+            let relative_path = relative_dir.join(entry.file_name());
+
+            if metadata.is_dir() {
+                collect(&entry.path(), &relative_path, files)?;
+            } else {
+                files.push((relative_path, metadata.len()));
+            }
+        }
+
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    collect(dir, Path::new(""), &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+/// A slice path glob, split into its path components.
+#[derive(Debug)]
+struct GlobPattern {
+    components: Vec<Vec<GlobToken>>,
+}
+
+#[derive(Debug)]
+enum GlobToken {
+    Literal(char),
+    AnyChar,
+    AnySequence,
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum GlobPatternError {
+    BadPattern,
+    OutsideAppDir,
+}
+
+impl GlobPattern {
+    fn parse(glob: &str) -> Result<Self, GlobPatternError> {
+        // Like the lifecycle, which joins each glob with the app directory, leading separators
+        // are ignored and the path is cleaned lexically before matching.
+        let mut components = Vec::new();
+        for component in Path::new(glob).components() {
+            match component {
+                Component::Normal(component) => components.push(
+                    component
+                        .to_str()
+                        .ok_or(GlobPatternError::BadPattern)
+                        .and_then(parse_glob_component)?,
+                ),
+                Component::ParentDir => {
+                    components.pop().ok_or(GlobPatternError::OutsideAppDir)?;
+                }
+                Component::RootDir | Component::CurDir | Component::Prefix(_) => {}
+            }
+        }
+
+        Ok(Self { components })
+    }
+
+    fn matches_path_or_ancestor(&self, relative_path: &Path) -> bool {
+        let path_components = relative_path.components().collect::<Vec<_>>();
+
+        self.components.len() <= path_components.len()
+            && self
+                .components
+                .iter()
+                .zip(path_components)
+                .all(|(tokens, path_component)| {
+                    path_component.as_os_str().to_str().is_some_and(|name| {
+                        match_glob_tokens(tokens, &name.chars().collect::<Vec<_>>())
+                    })
+                })
+    }
+}
+
+fn parse_glob_component(component: &str) -> Result<Vec<GlobToken>, GlobPatternError> {
+    let mut tokens = Vec::new();
+    let mut chars = component.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        tokens.push(match char {
+            '*' => GlobToken::AnySequence,
+            '?' => GlobToken::AnyChar,
+            '\\' => GlobToken::Literal(chars.next().ok_or(GlobPatternError::BadPattern)?),
+            '[' => {
+                let negated = chars.next_if_eq(&'^').is_some();
+                let mut ranges = Vec::new();
+
+                loop {
+                    if !ranges.is_empty() && chars.next_if_eq(&']').is_some() {
+                        break;
+                    }
+
+                    let low = next_class_char(&mut chars)?;
+                    let high = if chars.next_if_eq(&'-').is_some() {
+                        next_class_char(&mut chars)?
+                    } else {
+                        low
+                    };
+
+                    if high < low {
+                        return Err(GlobPatternError::BadPattern);
+                    }
+
+                    ranges.push((low, high));
+                }
+
+                GlobToken::Class { negated, ranges }
+            }
+            other => GlobToken::Literal(other),
+        });
+    }
+
+    Ok(tokens)
+}
+
+// Go's `filepath.Match` does not allow unescaped `-` or `]` as bounds of character class ranges.
+fn next_class_char(chars: &mut Peekable<Chars>) -> Result<char, GlobPatternError> {
+    match chars.next() {
+        Some('\\') => chars.next(),
+        Some('-' | ']') | None => None,
+        other => other,
+    }
+    .ok_or(GlobPatternError::BadPattern)
+}
+
+// Matches iteratively like Go's `filepath.Match`: when the name stops matching, the most recent
+// `*` absorbs one more character and matching resumes after it. Earlier stars never have to be
+// revisited, so matching doesn't take exponential time for patterns with many stars.
+fn match_glob_tokens(tokens: &[GlobToken], name: &[char]) -> bool {
+    let mut token_index = 0;
+    let mut name_index = 0;
+    // The token index after the most recent `*` and the name index it was resumed at.
+    let mut star_resume: Option<(usize, usize)> = None;
+
+    while name_index < name.len() {
+        match tokens.get(token_index) {
+            Some(GlobToken::AnySequence) => {
+                token_index += 1;
+                star_resume = Some((token_index, name_index));
+            }
+            Some(token) if glob_token_matches(token, name[name_index]) => {
+                token_index += 1;
+                name_index += 1;
+            }
+            _ => match star_resume {
+                Some((star_token_index, star_name_index)) => {
+                    token_index = star_token_index;
+                    name_index = star_name_index + 1;
+                    star_resume = Some((star_token_index, name_index));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    tokens[token_index..]
+        .iter()
+        .all(|token| matches!(token, GlobToken::AnySequence))
+}
+
+fn glob_token_matches(token: &GlobToken, char: char) -> bool {
+    match token {
+        GlobToken::Literal(literal) => *literal == char,
+        GlobToken::AnyChar | GlobToken::AnySequence => true,
+        GlobToken::Class { negated, ranges } => {
+            ranges
+                .iter()
+                .any(|&(low, high)| (low..=high).contains(&char))
+                != *negated
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write_files(dir: &Path, files: &[(&str, &str)]) {
+        for (path, contents) in files {
+            let path = dir.join(path);
+            if let Some(parent_dir) = path.parent() {
+                fs::create_dir_all(parent_dir).unwrap();
+            }
+            fs::write(path, contents).unwrap();
+        }
+    }
+
+    fn slice(path_globs: &[&str]) -> Slice {
+        Slice {
+            path_globs: path_globs.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn resolve_slices_with_directories_and_wildcards() {
+        let app_dir = tempdir().unwrap();
+        write_files(
+            app_dir.path(),
+            &[
+                ("public/assets/app.js", "12345"),
+                ("public/assets/vendor/lib.js", "123"),
+                ("public/robots.txt", "1"),
+                ("public/index.html", "12"),
+                ("app.rb", "1234"),
+            ],
+        );
+
+        let resolved_slices = resolve_slices(
+            app_dir.path(),
+            &[slice(&["./public/assets/"]), slice(&["/public/*.txt"])],
+        )
+        .unwrap();
+
+        assert_eq!(
+            resolved_slices.slices,
+            [
+                ResolvedSlice {
+                    path_globs: vec![String::from("./public/assets/")],
+                    contents: SliceContents {
+                        files: vec![
+                            PathBuf::from("public/assets/app.js"),
+                            PathBuf::from("public/assets/vendor/lib.js")
+                        ],
+                        size_in_bytes: 8
+                    }
+                },
+                ResolvedSlice {
+                    path_globs: vec![String::from("/public/*.txt")],
+                    contents: SliceContents {
+                        files: vec![PathBuf::from("public/robots.txt")],
+                        size_in_bytes: 1
+                    }
+                }
+            ]
+        );
+        assert_eq!(
+            resolved_slices.unsliced,
+            SliceContents {
+                files: vec![PathBuf::from("app.rb"), PathBuf::from("public/index.html")],
+                size_in_bytes: 6
+            }
+        );
+        assert_eq!(resolved_slices.problems, []);
+    }
+
+    #[test]
+    fn resolve_slices_reports_overlaps_and_empty_slices() {
+        let app_dir = tempdir().unwrap();
+        write_files(
+            app_dir.path(),
+            &[("static/a.css", "a"), ("static/b.css", "b"), ("app.rb", "")],
+        );
+
+        let resolved_slices = resolve_slices(
+            app_dir.path(),
+            &[slice(&["static"]), slice(&["static/*.css"]), slice(&[])],
+        )
+        .unwrap();
+
+        assert_eq!(resolved_slices.slices[0].contents.files.len(), 2);
+        assert_eq!(resolved_slices.slices[1].contents.files.len(), 0);
+        assert_eq!(
+            resolved_slices.problems,
+            [
+                SliceProblem::Overlap {
+                    slice_index: 0,
+                    other_slice_index: 1,
+                    files: vec![PathBuf::from("static/a.css"), PathBuf::from("static/b.css")]
+                },
+                SliceProblem::EmptySlice { slice_index: 1 },
+                SliceProblem::EmptySlice { slice_index: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_slices_reports_invalid_globs() {
+        let app_dir = tempdir().unwrap();
+        write_files(app_dir.path(), &[("app.rb", "")]);
+
+        let resolved_slices = resolve_slices(
+            app_dir.path(),
+            &[slice(&["[a-", "../outside/*", "app.rb", "missing/*"])],
+        )
+        .unwrap();
+
+        assert_eq!(
+            resolved_slices.problems,
+            [
+                SliceProblem::InvalidGlob {
+                    slice_index: 0,
+                    glob: String::from("[a-")
+                },
+                SliceProblem::GlobOutsideAppDir {
+                    slice_index: 0,
+                    glob: String::from("../outside/*")
+                },
+                SliceProblem::UnmatchedGlob {
+                    slice_index: 0,
+                    glob: String::from("missing/*")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn glob_component_matching() {
+        let matches = |pattern: &str, name: &str| {
+            match_glob_tokens(
+                &parse_glob_component(pattern).unwrap(),
+                &name.chars().collect::<Vec<_>>(),
+            )
+        };
+
+        assert!(matches("*", ".hidden"));
+        assert!(matches("*.js", "app.js"));
+        assert!(!matches("*.js", "app.jsx"));
+        assert!(matches("a?c", "abc"));
+        assert!(!matches("a?c", "ac"));
+        assert!(matches("[a-c]x", "bx"));
+        assert!(!matches("[^a-c]x", "bx"));
+        assert!(matches("[\\]]", "]"));
+        assert!(matches("\\*", "*"));
+        assert!(!matches("\\*", "a"));
+        assert!(matches("a*b*c", "abxbc"));
+        assert!(matches("a*b*c", "abc"));
+        assert!(!matches("a*b*c", "abcb"));
+        assert!(matches("*a*", "bab"));
+        assert!(matches("**", ""));
+        assert!(!matches("*?", ""));
+
+        // Backtracking into every star would take exponential time for this pattern.
+        assert!(!matches(&"a*".repeat(50), &"a".repeat(40)));
+        assert!(!matches(&format!("{}b", "a*".repeat(50)), &"a".repeat(100)));
+
+        for invalid_pattern in ["[", "[]", "[a", "[z-a]", "\\", "[a-]"] {
+            assert_eq!(
+                parse_glob_component(invalid_pattern).err(),
+                Some(GlobPatternError::BadPattern),
+                "{invalid_pattern}"
+            );
+        }
+    }
+}